@@ -0,0 +1,58 @@
+//! Compile test for `watermill` without `std`.
+//!
+//! Build it for a bare metal target, e.g. `cargo build --target thumbv7em-none-eabihf`.
+#![no_std]
+
+use watermill::ewmean::EWMean;
+use watermill::maximum::Max;
+use watermill::mean::Mean;
+use watermill::minimum::Min;
+use watermill::ptp::PeakToPeak;
+use watermill::quantile::RollingQuantile;
+use watermill::stats::Univariate;
+use watermill::sum::Sum;
+use watermill::variance::Variance;
+
+/// Summary of a batch of sensor readings.
+pub struct Summary {
+    pub sum: f32,
+    pub mean: f32,
+    pub variance: f32,
+    pub min: f32,
+    pub max: f32,
+    pub peak_to_peak: f32,
+    pub ewmean: f32,
+    pub median: f32,
+}
+
+pub fn summarize(readings: &[f32]) -> Summary {
+    let mut sum: Sum<f32> = Sum::new();
+    let mut mean: Mean<f32> = Mean::new();
+    let mut variance: Variance<f32> = Variance::default();
+    let mut min: Min<f32> = Min::new();
+    let mut max: Max<f32> = Max::new();
+    let mut peak_to_peak: PeakToPeak<f32> = PeakToPeak::new();
+    let mut ewmean: EWMean<f32> = EWMean::new(0.5).unwrap();
+    // Window based statistics only need `alloc`
+    let mut median: RollingQuantile<f32> = RollingQuantile::new(0.5, 16).unwrap();
+    for x in readings.iter().copied() {
+        sum.update(x);
+        mean.update(x);
+        variance.update(x);
+        min.update(x);
+        max.update(x);
+        peak_to_peak.update(x);
+        ewmean.update(x);
+        median.update(x);
+    }
+    Summary {
+        sum: sum.get(),
+        mean: mean.get(),
+        variance: variance.get(),
+        min: min.get(),
+        max: max.get(),
+        peak_to_peak: peak_to_peak.get(),
+        ewmean: ewmean.get(),
+        median: median.get(),
+    }
+}
@@ -0,0 +1,30 @@
+//! Time `RollingQuantile` updates for growing window sizes.
+//!
+//! Run with `cargo run --release --example sorted_window_bench`.
+use std::time::Instant;
+
+use watermill::quantile::RollingQuantile;
+use watermill::stats::Univariate;
+
+fn main() {
+    let n_updates = 1_000_000;
+    for window_size in [100, 1_000, 10_000, 100_000] {
+        let mut rolling_median: RollingQuantile<f64> =
+            RollingQuantile::new(0.5, window_size).unwrap();
+        let mut state: u64 = 42;
+        let start = Instant::now();
+        for _ in 0..n_updates {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            rolling_median.update((state >> 11) as f64 / (1u64 << 53) as f64);
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "window_size={:>7} {:>8.1} ns/update (median {:.4})",
+            window_size,
+            elapsed.as_nanos() as f64 / n_updates as f64,
+            rolling_median.get()
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use serde::{Deserialize, Serialize};
+
+// Buckets kept per row before the two oldest are merged into the next row
+const MAX_BUCKETS: usize = 5;
+// A change is looked for every `CLOCK` updates
+const CLOCK: usize = 32;
+// Smallest size of each side of a cut
+const MIN_WINDOW_LENGTH: usize = 5;
+// No change is looked for before the window holds this many values
+const GRACE_PERIOD: usize = 10;
+
+// Summary of `2^i` consecutive values, `i` being the row of the bucket
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Bucket<F> {
+    total: F,
+    // Sum of the squared deviations from the mean of the bucket
+    variance: F,
+}
+
+/// Adaptive windowing (ADWIN) change detector.
+///
+/// The window grows while the stream is stationary and drops its oldest values as soon as two
+/// sub-windows have means that differ more than the bound given by the confidence `delta`.
+/// Values are compressed in an exponential histogram of buckets, so the memory and the cost of an
+/// update are `O(log(width))`. Cuts are only checked every `32` updates.
+/// # Arguments
+/// * `delta` - Confidence of the test, in `(0, 1)`. The lower, the fewer false alarms but the
+///   slower the detection. Defaults to `0.002`.
+/// # Examples
+/// ```
+/// use watermill::adwin::ADWIN;
+/// let mut adwin: ADWIN<f64> = ADWIN::default();
+/// let mut detections = Vec::new();
+/// for i in 0..2000{
+///     let x = if i < 1000 { (i % 2) as f64 } else { 10. + (i % 2) as f64 };
+///     if adwin.update(x){
+///         detections.push(i);
+///     }
+/// }
+/// assert!(!detections.is_empty() && detections[0] >= 1000);
+/// assert!((adwin.estimation() - 10.5).abs() < 0.1);
+/// ```
+/// # References
+/// [^1]: [Bifet, A. and Gavalda, R., 2007. Learning from time-changing data with adaptive windowing. In Proceedings of the 2007 SIAM international conference on data mining (pp. 443-448).](https://epubs.siam.org/doi/10.1137/1.9781611972771.42)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ADWIN<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub delta: F,
+    // Row `i` holds buckets of `2^i` values, oldest first
+    rows: Vec<VecDeque<Bucket<F>>>,
+    total: F,
+    variance: F,
+    width: usize,
+    tick: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ADWIN<F> {
+    pub fn new(delta: F) -> Result<Self, &'static str> {
+        if !(delta > F::from_f64(0.).unwrap() && delta < F::from_f64(1.).unwrap()) {
+            return Err("delta should be in (0, 1)");
+        }
+        Ok(Self {
+            delta,
+            rows: Vec::new(),
+            total: F::from_f64(0.).unwrap(),
+            variance: F::from_f64(0.).unwrap(),
+            width: 0,
+            tick: 0,
+        })
+    }
+    /// Add `x` to the window and return whether a change was detected, the window then only
+    /// holds the values seen after the change.
+    pub fn update(&mut self, x: F) -> bool {
+        self.insert(x);
+        self.tick += 1;
+        if self.tick.is_multiple_of(CLOCK) && self.width > GRACE_PERIOD {
+            return self.detect_change();
+        }
+        false
+    }
+    /// Number of values in the window.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Mean of the window, `NaN` while the window is empty.
+    pub fn estimation(&self) -> F {
+        if self.width == 0 {
+            return F::nan();
+        }
+        self.total / F::from_usize(self.width).unwrap()
+    }
+    /// Population variance of the window, `NaN` while the window is empty.
+    pub fn variance(&self) -> F {
+        if self.width == 0 {
+            return F::nan();
+        }
+        self.variance / F::from_usize(self.width).unwrap()
+    }
+    fn insert(&mut self, x: F) {
+        self.width += 1;
+        if self.width > 1 {
+            let n = F::from_usize(self.width).unwrap();
+            let mean = self.total / (n - F::from_f64(1.).unwrap());
+            self.variance += (n - F::from_f64(1.).unwrap()) * (x - mean) * (x - mean) / n;
+        }
+        self.total += x;
+        if self.rows.is_empty() {
+            self.rows.push(VecDeque::with_capacity(MAX_BUCKETS + 1));
+        }
+        self.rows[0].push_back(Bucket {
+            total: x,
+            variance: F::from_f64(0.).unwrap(),
+        });
+        self.compress();
+    }
+    // Merge the two oldest buckets of every full row into the next row
+    fn compress(&mut self) {
+        let mut i = 0;
+        while i < self.rows.len() && self.rows[i].len() > MAX_BUCKETS {
+            let first = self.rows[i].pop_front().expect("The row is full");
+            let second = self.rows[i].pop_front().expect("The row is full");
+            let size = F::from_usize(1 << i).unwrap();
+            let mean_difference = first.total / size - second.total / size;
+            let merged = Bucket {
+                total: first.total + second.total,
+                variance: first.variance
+                    + second.variance
+                    + size * size * mean_difference * mean_difference / (size + size),
+            };
+            if i + 1 == self.rows.len() {
+                self.rows.push(VecDeque::with_capacity(MAX_BUCKETS + 1));
+            }
+            self.rows[i + 1].push_back(merged);
+            i += 1;
+        }
+    }
+    // Drop the oldest bucket of the window
+    fn delete_oldest(&mut self) {
+        let last = self.rows.len() - 1;
+        let bucket = self.rows[last]
+            .pop_front()
+            .expect("The last row holds a bucket");
+        if self.rows[last].is_empty() {
+            self.rows.pop();
+        }
+        let size = 1 << last;
+        self.width -= size;
+        self.total -= bucket.total;
+        let n_bucket = F::from_usize(size).unwrap();
+        let n = F::from_usize(self.width).unwrap();
+        let mean_difference = bucket.total / n_bucket - self.total / n;
+        self.variance -=
+            bucket.variance + n_bucket * n * mean_difference * mean_difference / (n_bucket + n);
+    }
+    fn detect_change(&mut self) -> bool {
+        let mut detected = false;
+        let mut reduced = true;
+        while reduced {
+            reduced = false;
+            let mut n_old = 0;
+            let mut n_new = self.width;
+            let mut total_old = F::from_f64(0.).unwrap();
+            let mut total_new = self.total;
+            // Move the cut from the oldest bucket towards the newest
+            'cuts: for i in (0..self.rows.len()).rev() {
+                let size = 1 << i;
+                for bucket in self.rows[i].iter() {
+                    n_old += size;
+                    n_new -= size;
+                    total_old += bucket.total;
+                    total_new -= bucket.total;
+                    if n_new < MIN_WINDOW_LENGTH {
+                        break 'cuts;
+                    }
+                    if n_old >= MIN_WINDOW_LENGTH && self.is_cut(n_old, n_new, total_old, total_new)
+                    {
+                        reduced = true;
+                        detected = true;
+                        break 'cuts;
+                    }
+                }
+            }
+            if reduced {
+                self.delete_oldest();
+            }
+        }
+        detected
+    }
+    fn is_cut(&self, n_old: usize, n_new: usize, total_old: F, total_new: F) -> bool {
+        let one = F::from_f64(1.).unwrap();
+        let n_old_f = F::from_usize(n_old).unwrap();
+        let n_new_f = F::from_usize(n_new).unwrap();
+        let mean_difference = (total_old / n_old_f - total_new / n_new_f).abs();
+        let min_length = F::from_usize(MIN_WINDOW_LENGTH).unwrap();
+        let m = one / (n_old_f - min_length + one) + one / (n_new_f - min_length + one);
+        let dd =
+            (F::from_f64(2.).unwrap() * F::from_usize(self.width).unwrap().ln() / self.delta).ln();
+        let epsilon = (F::from_f64(2.).unwrap() * m * self.variance() * dd).sqrt()
+            + F::from_f64(2. / 3.).unwrap() * dd * m;
+        mean_difference > epsilon
+    }
+}
+
+impl<F> Default for ADWIN<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(0.002).unwrap()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Uniform values in [0, 1)
+    fn uniform_sample(n: usize, seed: u64) -> Vec<f64> {
+        let mut state: u64 = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+    #[test]
+    fn abrupt_mean_shift() {
+        let mut adwin: ADWIN<f64> = ADWIN::default();
+        assert!(adwin.estimation().is_nan());
+        let mut data = uniform_sample(2000, 1);
+        data.extend(uniform_sample(2000, 2).into_iter().map(|x| x + 0.5));
+        let mut detections = Vec::new();
+        for (i, x) in data.into_iter().enumerate() {
+            if adwin.update(x) {
+                detections.push(i);
+            }
+        }
+        assert!(!detections.is_empty());
+        assert!(detections[0] >= 2000 && detections[0] < 2000 + 200);
+        // The window forgot the values before the change
+        assert!(adwin.width() < 2000 + 64);
+        assert!((adwin.estimation() - 1.).abs() < 0.05);
+    }
+    #[test]
+    fn stationary_stream() {
+        let mut adwin: ADWIN<f64> = ADWIN::default();
+        for x in uniform_sample(20_000, 3) {
+            assert!(!adwin.update(x));
+        }
+        assert_eq!(adwin.width(), 20_000);
+        assert!((adwin.estimation() - 0.5).abs() < 0.01);
+        assert!((adwin.variance() - 1. / 12.).abs() < 0.005);
+    }
+    #[test]
+    fn bucket_summaries() {
+        let data = uniform_sample(1000, 4);
+        let mut adwin: ADWIN<f64> = ADWIN::default();
+        for x in data.iter() {
+            adwin.update(*x);
+        }
+        let mean = data.iter().sum::<f64>() / 1000.;
+        let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 1000.;
+        assert!((adwin.estimation() - mean).abs() < 1e-12);
+        assert!((adwin.variance() - variance).abs() < 1e-12);
+        // log2 of the width rows, at most 5 buckets each
+        assert!(adwin.rows.len() <= 10);
+        assert!(adwin.rows.iter().all(|row| row.len() <= MAX_BUCKETS));
+        assert!(ADWIN::<f64>::new(0.).is_err());
+        assert!(ADWIN::<f64>::new(1.).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::pearson::PearsonCorrelation;
+use crate::stats::{Bivariate, Univariate};
+use serde::{Deserialize, Serialize};
+
+/// Running autocorrelation at a fixed lag.
+///
+/// The last `lag` values are buffered, each new value `x_t` is paired with `x_{t-lag}` and the
+/// pair updates a Pearson correlation. `get` returns `0` until `lag + 2` values were seen.
+/// # Arguments
+/// * `lag` - Number of steps between the correlated values, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::autocorr::AutoCorr;
+/// use watermill::stats::Univariate;
+/// let mut running_autocorr: AutoCorr<f64> = AutoCorr::new(2).unwrap();
+/// for i in 0..100{
+///     // Alternates between two values every two steps
+///     running_autocorr.update(((i / 2) % 2) as f64);
+/// }
+/// assert!((running_autocorr.get() + 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on autocorrelation](https://www.wikiwand.com/en/Autocorrelation)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AutoCorr<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    lag: usize,
+    lagged: VecDeque<F>,
+    corr: PearsonCorrelation<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> AutoCorr<F> {
+    pub fn new(lag: usize) -> Result<Self, &'static str> {
+        if lag == 0 {
+            return Err("lag should not equal to 0");
+        }
+        Ok(Self {
+            lag,
+            lagged: VecDeque::with_capacity(lag),
+            corr: PearsonCorrelation::default(),
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for AutoCorr<F> {
+    fn update(&mut self, x: F) {
+        if self.lagged.len() == self.lag {
+            let lagged = self.lagged.pop_front().expect("lag is greater than 0");
+            self.corr.update(lagged, x);
+        }
+        self.lagged.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.corr.get()
+    }
+    fn n(&self) -> usize {
+        // Values waiting for their lagged pair are counted as well
+        self.lagged.len() + self.corr.var_x.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn sine_wave() {
+        let period = 20;
+        let mut at_period: AutoCorr<f64> = AutoCorr::new(period).unwrap();
+        let mut at_half_period: AutoCorr<f64> = AutoCorr::new(period / 2).unwrap();
+        let mut at_quarter_period: AutoCorr<f64> = AutoCorr::new(period / 4).unwrap();
+        for i in 0..1000 {
+            let x = (2. * core::f64::consts::PI * i as f64 / period as f64).sin();
+            at_period.update(x);
+            at_half_period.update(x);
+            at_quarter_period.update(x);
+        }
+        assert!(at_period.get() > 0.999);
+        assert!(at_half_period.get() < -0.999);
+        assert!(at_quarter_period.get().abs() < 0.05);
+    }
+    #[test]
+    fn matches_pearson_on_shifted_series() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4., 10., 0.];
+        let lag = 3;
+        let mut autocorr: AutoCorr<f64> = AutoCorr::new(lag).unwrap();
+        let mut pearson: PearsonCorrelation<f64> = PearsonCorrelation::default();
+        for x in data.iter() {
+            autocorr.update(*x);
+        }
+        for (lagged, x) in data.iter().zip(data[lag..].iter()) {
+            pearson.update(*lagged, *x);
+        }
+        assert_eq!(autocorr.get(), pearson.get());
+        assert!(AutoCorr::<f64>::new(0).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::covariance::Covariance;
+use crate::stats::{Bivariate, RevertableBivariate, Univariate};
+use serde::{Deserialize, Serialize};
+
+/// Rolling autocovariance at a fixed lag.
+///
+/// Each pair `(x_{t-lag}, x_t)` with both values among the last `window_size` observations
+/// contributes to a sample covariance, the pair holding the oldest value is reverted when the window slides.
+/// # Arguments
+/// * `lag` - Number of steps between the paired values, must be greater than 0.
+/// * `window_size` - Size of the rolling window, must be greater than `lag`.
+/// # Examples
+/// ```
+/// use watermill::autocov::RollingAutoCov;
+/// use watermill::stats::Univariate;
+/// let mut rolling_autocov: RollingAutoCov<f64> = RollingAutoCov::new(1, 4).unwrap();
+/// for x in [3., 1., 4., 1., 5., 9., 2.]{
+///     rolling_autocov.update(x);
+/// }
+/// // Pairs (1, 5), (5, 9) and (9, 2)
+/// assert!((rolling_autocov.get() + 6.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingAutoCov<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    lag: usize,
+    window: VecDeque<F>,
+    window_size: usize,
+    cov: Covariance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingAutoCov<F> {
+    pub fn new(lag: usize, window_size: usize) -> Result<Self, &'static str> {
+        if lag == 0 {
+            return Err("lag should not equal to 0");
+        }
+        if lag >= window_size {
+            return Err("lag should be lower than the window size");
+        }
+        Ok(Self {
+            lag,
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            cov: Covariance::default(),
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingAutoCov<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.cov
+                .revert(oldest, self.window[self.lag - 1])
+                .expect("Every value in the window was paired");
+        }
+        if self.window.len() >= self.lag {
+            self.cov
+                .update(self.window[self.window.len() - self.lag], x);
+        }
+        self.window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.cov.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn brute_force(window: &[f64], lag: usize) -> f64 {
+        let mut cov: Covariance<f64> = Covariance::default();
+        for (lagged, x) in window.iter().zip(window[lag..].iter()) {
+            cov.update(*lagged, *x);
+        }
+        cov.get()
+    }
+    #[test]
+    fn matches_brute_force() {
+        let data: Vec<f64> = vec![
+            9., 7., 3., 2., 6., 1., 8., 5., 4., 10., 0., 3.5, -2., 7.25, 6., 1.,
+        ];
+        for (lag, window_size) in [(1, 2), (1, 5), (2, 5), (3, 4), (4, 8)] {
+            let mut rolling_autocov: RollingAutoCov<f64> =
+                RollingAutoCov::new(lag, window_size).unwrap();
+            for (i, x) in data.iter().enumerate() {
+                rolling_autocov.update(*x);
+                let window = &data[(i + 1).saturating_sub(window_size)..=i];
+                if window.len() > lag {
+                    assert!((rolling_autocov.get() - brute_force(window, lag)).abs() < 1e-9);
+                }
+            }
+        }
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(RollingAutoCov::<f64>::new(0, 10).is_err());
+        assert!(RollingAutoCov::<f64>::new(10, 10).is_err());
+        assert!(RollingAutoCov::<f64>::new(1, 0).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::RollingMean;
+use crate::stats::Univariate;
+use crate::std_dev::RollingStandardDeviation;
+use serde::{Deserialize, Serialize};
+
+/// Rolling Bollinger bands, the rolling mean plus or minus `k` rolling standard deviations.
+///
+/// The mean and the population standard deviation (`ddof = 0`) are computed over the same window
+/// of the latest `window_size` values. `get` returns `(lower, middle, upper)`, `(0, 0, 0)` before
+/// any update.
+/// # Arguments
+/// * `window_size` - Size of the rolling window. Defaults to `20`.
+/// * `k` - Number of standard deviations between the middle and the outer bands, must not be negative. Defaults to `2`.
+/// # Examples
+/// ```
+/// use watermill::bollinger::BollingerBands;
+/// let prices: Vec<f64> = vec![100., 101.5, 101., 102.5, 104., 103., 105.5, 106., 105., 107.];
+/// let mut bollinger_bands: BollingerBands<f64> = BollingerBands::new(4, 2.).unwrap();
+/// for price in prices.into_iter(){
+///     bollinger_bands.update(price);
+/// }
+/// let (lower, middle, upper) = bollinger_bands.get();
+/// assert!((middle - 105.875).abs() < 1e-12);
+/// assert!((upper - middle - 2. * 0.546875_f64.sqrt()).abs() < 1e-9);
+/// assert!((middle - lower - 2. * 0.546875_f64.sqrt()).abs() < 1e-9);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on Bollinger Bands](https://www.wikiwand.com/en/Bollinger_Bands)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BollingerBands<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub mean: RollingMean<F>,
+    pub std: RollingStandardDeviation<F>,
+    pub k: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> BollingerBands<F> {
+    pub fn new(window_size: usize, k: F) -> Result<Self, &'static str> {
+        if !(k >= F::from_f64(0.).unwrap() && k.is_finite()) {
+            return Err("k should be finite and not negative");
+        }
+        Ok(Self {
+            mean: RollingMean::new(window_size)?,
+            std: RollingStandardDeviation::new(window_size, 0)?,
+            k,
+        })
+    }
+    pub fn update(&mut self, x: F) {
+        self.mean.update(x);
+        self.std.update(x);
+    }
+    /// The `(lower, middle, upper)` bands.
+    pub fn get(&self) -> (F, F, F) {
+        let middle = self.mean.get();
+        let width = self.k * self.std.get();
+        (middle - width, middle, middle + width)
+    }
+}
+
+impl<F> Default for BollingerBands<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(20, F::from_f64(2.).unwrap()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn bands_widen_with_volatility() {
+        let mut bollinger_bands: BollingerBands<f64> = BollingerBands::default();
+        assert_eq!(bollinger_bands.get(), (0., 0., 0.));
+        // A calm price followed by large swings around the same level
+        for i in 0..40 {
+            bollinger_bands.update(100. + (i % 2) as f64 * 0.5);
+        }
+        let (calm_lower, calm_middle, calm_upper) = bollinger_bands.get();
+        assert!((calm_middle - 100.25).abs() < 1e-9);
+        assert!((calm_upper - calm_lower - 1.).abs() < 1e-9);
+        for i in 0..40 {
+            bollinger_bands.update(100.25 + if i % 2 == 0 { -5. } else { 5. });
+        }
+        let (lower, middle, upper) = bollinger_bands.get();
+        assert!((middle - 100.25).abs() < 1e-9);
+        assert!((upper - lower - 20.).abs() < 1e-9);
+        assert!(lower < calm_lower && upper > calm_upper);
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(BollingerBands::<f64>::new(0, 2.).is_err());
+        assert!(BollingerBands::<f64>::new(20, -1.).is_err());
+        assert!(BollingerBands::<f64>::new(20, f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Statistic of a statistic, every value updates `first` and the new value of `first` updates
+/// `second`.
+///
+/// `get` returns the value of `second`, and `n` its number of values, which is the number of
+/// updates unless `second` is itself rolling. Pipes can be nested to chain more statistics.
+/// # Arguments
+/// * `first` - Statistic fed with the values of the stream.
+/// * `second` - Statistic fed with the values of `first`.
+/// # Examples
+/// ```
+/// use watermill::compose::Pipe;
+/// use watermill::mean::RollingMean;
+/// use watermill::stats::Univariate;
+/// use watermill::variance::Variance;
+/// // Variance of the rolling mean
+/// let mut pipe = Pipe::new(RollingMean::<f64>::new(2).unwrap(), Variance::new(0));
+/// for x in vec![1., 3., 5., 3.].into_iter(){
+///     pipe.update(x);
+/// }
+/// // Variance of the rolling means 1, 2, 4, 4
+/// assert!((pipe.get() - 1.6875).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pipe<A, B, F>
+where
+    A: Univariate<F>,
+    B: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub first: A,
+    pub second: B,
+    #[serde(skip)]
+    _float: PhantomData<F>,
+}
+
+impl<A, B, F> Pipe<A, B, F>
+where
+    A: Univariate<F>,
+    B: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            _float: PhantomData,
+        }
+    }
+}
+
+impl<A, B, F> Univariate<F> for Pipe<A, B, F>
+where
+    A: Univariate<F>,
+    B: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn update(&mut self, x: F) {
+        self.first.update(x);
+        self.second.update(self.first.get());
+    }
+    fn get(&self) -> F {
+        self.second.get()
+    }
+    fn try_get(&self) -> Option<F> {
+        self.second.try_get()
+    }
+    fn n(&self) -> usize {
+        self.second.n()
+    }
+}
+
+/// Broadcast every value to several statistics of the same stream.
+///
+/// The statistics are boxed `Univariate` trait objects, so statistics of different types can be
+/// mixed. `update_many` is not available on trait objects, values are forwarded one by one.
+/// # Examples
+/// ```
+/// use watermill::compose::Tee;
+/// use watermill::maximum::Max;
+/// use watermill::mean::Mean;
+/// use watermill::minimum::Min;
+/// use watermill::variance::Variance;
+/// let mut tee: Tee<f64> = Tee::new();
+/// tee.push(Mean::new());
+/// tee.push(Variance::default());
+/// tee.push(Min::new());
+/// tee.push(Max::new());
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     tee.update(x);
+/// }
+/// let values = tee.get_all();
+/// assert_eq!(values[0], 5.);
+/// assert!((values[1] - 7.5).abs() < 1e-12);
+/// assert_eq!(values[2..], [1., 9.]);
+/// ```
+#[derive(Default)]
+pub struct Tee<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    stats: Vec<Box<dyn Univariate<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Tee<F> {
+    pub fn new() -> Self {
+        Self { stats: Vec::new() }
+    }
+    /// Add a statistic, it only sees the values updated from now on.
+    pub fn push<U: Univariate<F> + 'static>(&mut self, stat: U) {
+        self.stats.push(Box::new(stat));
+    }
+    pub fn update(&mut self, x: F) {
+        for stat in self.stats.iter_mut() {
+            stat.update(x);
+        }
+    }
+    /// Values of the statistics, in the order they were pushed.
+    pub fn get_all(&self) -> Vec<F> {
+        self.stats.iter().map(|stat| stat.get()).collect()
+    }
+    /// The statistics, in the order they were pushed.
+    pub fn stats(&self) -> &[Box<dyn Univariate<F>>] {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::maximum::Max;
+    use crate::mean::{Mean, RollingMean};
+    use crate::scaler::StandardScaler;
+    use crate::variance::Variance;
+    #[test]
+    fn mean_then_variance() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut pipe = Pipe::new(Mean::new(), Variance::new(1));
+        let mut mean: Mean<f64> = Mean::new();
+        let mut variance: Variance<f64> = Variance::new(1);
+        for x in data.into_iter() {
+            pipe.update(x);
+            mean.update(x);
+            variance.update(mean.get());
+            assert_eq!(pipe.get(), variance.get());
+        }
+        assert_eq!(pipe.n(), 9);
+        assert_eq!(pipe.first.get(), 5.);
+    }
+    #[test]
+    fn nested_pipes() {
+        // Rolling mean of the z-scores, then its running max
+        let mut pipe = Pipe::new(
+            Pipe::new(StandardScaler::default(), RollingMean::new(3).unwrap()),
+            Max::new(),
+        );
+        let mut scaler: StandardScaler<f64> = StandardScaler::default();
+        let mut rolling_mean: RollingMean<f64> = RollingMean::new(3).unwrap();
+        let mut max = f64::MIN;
+        for i in 0..50 {
+            let x = ((i * 13) % 7) as f64;
+            pipe.update(x);
+            scaler.update(x);
+            rolling_mean.update(scaler.get());
+            max = max.max(rolling_mean.get());
+            assert_eq!(pipe.get(), max);
+        }
+        let serialized = serde_json::to_string(&pipe).unwrap();
+        type ScoreMean = Pipe<StandardScaler<f64>, RollingMean<f64>, f64>;
+        let deserialized: Pipe<ScoreMean, Max<f64>, f64> =
+            serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get(), max);
+    }
+    #[test]
+    fn tee_matches_independent_statistics() {
+        use crate::minimum::Min;
+        use crate::quantile::Quantile;
+        let data: Vec<f64> = (0..500).map(|i| ((i * 7919) % 1009) as f64 / 10.).collect();
+        let mut tee: Tee<f64> = Tee::new();
+        tee.push(Mean::new());
+        tee.push(Variance::new(1));
+        tee.push(Min::new());
+        tee.push(Max::new());
+        tee.push(Quantile::new(0.99).unwrap());
+        let mut mean: Mean<f64> = Mean::new();
+        let mut variance: Variance<f64> = Variance::new(1);
+        let mut min: Min<f64> = Min::new();
+        let mut max: Max<f64> = Max::new();
+        let mut p99: Quantile<f64> = Quantile::new(0.99).unwrap();
+        for x in data.into_iter() {
+            tee.update(x);
+            mean.update(x);
+            variance.update(x);
+            min.update(x);
+            max.update(x);
+            p99.update(x);
+        }
+        assert_eq!(
+            tee.get_all(),
+            vec![mean.get(), variance.get(), min.get(), max.get(), p99.get()]
+        );
+        assert!(tee.stats().iter().all(|stat| stat.n() == 500));
+    }
+}
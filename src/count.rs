@@ -1,7 +1,7 @@
-use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive, PrimInt};
 
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::stats::{IntUnivariate, Mergeable, Revertable, Scalable, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running count.
 /// # Examples
@@ -39,6 +39,14 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Count<F> {
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Count<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Count<F> {
     #[warn(unused_variables)]
     fn update(&mut self, _x: F) {
@@ -47,10 +55,13 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Count<F
     fn get(&self) -> F {
         self.count
     }
+    fn n(&self) -> usize {
+        self.count.round().to_usize().unwrap_or(0)
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Count<F> {
-    fn revert(&mut self, _x: F) -> std::result::Result<(), &'static str> {
+    fn revert(&mut self, _x: F) -> Result<(), &'static str> {
         if self.count == F::from_f64(0.).unwrap() {
             return Err("Count cannot go below 0");
         }
@@ -59,4 +70,92 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Count<F
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Count<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Count<F> {
+    fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Scalable<F> for Count<F> {
+    fn scale(&mut self, factor: F) {
+        self.count = self.count * factor;
+    }
+}
+
+/// Running count held in an integer, exact as long as it fits in `I`.
+/// # Examples
+/// ```
+/// use watermill::stats::IntUnivariate;
+/// use watermill::count::IntCount;
+/// let mut running_count: IntCount<u64> = IntCount::new();
+/// for i in 1..10{
+///     running_count.update(i);
+/// }
+/// assert_eq!(running_count.get(), 9);
+///```
+/// # Panics
+/// `update` panics if the count overflows `I`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct IntCount<I: PrimInt> {
+    pub count: I,
+}
+
+impl<I: PrimInt> Default for IntCount<I> {
+    fn default() -> Self {
+        Self { count: I::zero() }
+    }
+}
+impl<I: PrimInt> IntCount<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<I: PrimInt> IntUnivariate<I> for IntCount<I> {
+    fn update(&mut self, _x: I) {
+        self.count = self
+            .count
+            .checked_add(&I::one())
+            .expect("IntCount overflowed");
+    }
+    fn get(&self) -> I {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_count_saturates() {
+        let mut running_count: Count<f64> = Count::new();
+        let mut rolling_count = Rolling::new(&mut running_count, 4).unwrap();
+        for i in 1..10 {
+            rolling_count.update(i as f64);
+            assert_eq!(rolling_count.get(), (i.min(4)) as f64);
+        }
+    }
+    #[test]
+    fn int_count() {
+        let mut int_count: IntCount<u16> = IntCount::new();
+        for i in 0..1000 {
+            int_count.update(i);
+        }
+        assert_eq!(int_count.get(), 1000);
+    }
+    #[test]
+    fn merge() {
+        let mut left: Count<f64> = Count::new();
+        let mut right: Count<f64> = Count::new();
+        for i in 0..10 {
+            if i < 3 {
+                left.update(i as f64);
+            } else {
+                right.update(i as f64);
+            }
+        }
+        left.merge(&right);
+        assert_eq!(left.get(), 10.);
+    }
+}
@@ -1,8 +1,8 @@
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::mean::Mean;
-use crate::stats::{Bivariate, Univariate};
+use crate::stats::{Bivariate, Revertable, RevertableBivariate, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running Covariance.
 /// # Examples
@@ -71,3 +71,53 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for Covarian
         self.cov
     }
 }
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for Covariance<F> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        let mean_y = self.mean_y.get();
+        self.mean_x.revert(x)?;
+        self.mean_y.revert(y)?;
+        self.c -= (x - self.mean_x.get()) * (y - mean_y);
+        self.cov = self.c
+            / (F::from_f64(1.)
+                .unwrap()
+                .max(self.mean_x.n.get() - F::from_u32(self.ddof).unwrap()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn matches_numpy_cov() {
+        let x: Vec<f64> = vec![0.5, -1.2, 3.3, 2.0, 0.0, 4.1, -0.7];
+        let y: Vec<f64> = vec![1.0, 0.4, 2.2, 2.5, -0.3, 3.9, 0.1];
+        let mut sample_cov: Covariance<f64> = Covariance::default();
+        let mut population_cov: Covariance<f64> = Covariance::new(0);
+        for (xi, yi) in x.iter().zip(y.iter()) {
+            sample_cov.update(*xi, *yi);
+            population_cov.update(*xi, *yi);
+        }
+        // numpy.cov(x, y, ddof=1)[0][1] and numpy.cov(x, y, ddof=0)[0][1]
+        assert!((sample_cov.get() - 2.8333333333333335).abs() < 1e-12);
+        assert!((population_cov.get() - 2.4285714285714284).abs() < 1e-12);
+    }
+    #[test]
+    fn revert() {
+        let x: Vec<f64> = vec![0.5, -1.2, 3.3, 2.0, 0.0, 4.1, -0.7];
+        let y: Vec<f64> = vec![1.0, 0.4, 2.2, 2.5, -0.3, 3.9, 0.1];
+        let mut running_cov: Covariance<f64> = Covariance::default();
+        for (xi, yi) in x.iter().zip(y.iter()) {
+            running_cov.update(*xi, *yi);
+        }
+        for (xi, yi) in x.iter().zip(y.iter()).take(4) {
+            running_cov.revert(*xi, *yi).unwrap();
+        }
+        let mut tail_cov: Covariance<f64> = Covariance::default();
+        for (xi, yi) in x.iter().zip(y.iter()).skip(4) {
+            tail_cov.update(*xi, *yi);
+        }
+        assert!((running_cov.get() - tail_cov.get()).abs() < 1e-12);
+    }
+}
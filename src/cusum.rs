@@ -0,0 +1,155 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::Mean;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Two-sided cumulative sum (CUSUM) change detector.
+///
+/// The deviations of the values from the mean of the previous ones are accumulated in a positive
+/// sum `s+ = max(0, s+ + x - mean - drift)` and a negative sum `s- = max(0, s- - x + mean - drift)`.
+/// An alarm is raised when either sum exceeds `threshold`, the sums and the mean then restart from
+/// the values following the change.
+/// # Arguments
+/// * `threshold` - Alarm threshold of the sums, must be positive. Defaults to `5`.
+/// * `drift` - Deviation tolerated at every step, must not be negative. Defaults to `0.5`.
+/// # Examples
+/// ```
+/// use watermill::cusum::CuSum;
+/// let mut cusum: CuSum<f64> = CuSum::new(4., 0.5).unwrap();
+/// let mut alarms = Vec::new();
+/// for i in 0..100{
+///     let x = if i < 50 { (i % 2) as f64 } else { 3. + (i % 2) as f64 };
+///     if cusum.update(x){
+///         alarms.push(i);
+///     }
+/// }
+/// assert_eq!(alarms, vec![51]);
+/// assert_eq!(cusum.negative_sum(), 0.);
+/// ```
+/// # References
+/// [^1]: [Page, E.S., 1954. Continuous inspection schemes. Biometrika, 41(1/2), pp.100-115.](https://www.jstor.org/stable/2333009)
+///
+/// [^2]: [Montgomery, D.C., 2009. Introduction to statistical quality control. John Wiley & Sons.](https://www.wiley.com/en-us/Introduction+to+Statistical+Quality+Control%2C+8th+Edition-p-9781119399308)
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct CuSum<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub threshold: F,
+    pub drift: F,
+    mean: Mean<F>,
+    positive_sum: F,
+    negative_sum: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> CuSum<F> {
+    pub fn new(threshold: F, drift: F) -> Result<Self, &'static str> {
+        if !(threshold > F::from_f64(0.).unwrap() && threshold.is_finite()) {
+            return Err("threshold should be positive and finite");
+        }
+        if !(drift >= F::from_f64(0.).unwrap() && drift.is_finite()) {
+            return Err("drift should be finite and not negative");
+        }
+        Ok(Self {
+            threshold,
+            drift,
+            mean: Mean::new(),
+            positive_sum: F::from_f64(0.).unwrap(),
+            negative_sum: F::from_f64(0.).unwrap(),
+        })
+    }
+    /// Add `x` to the sums and return whether an alarm was raised.
+    /// # Panics
+    /// Panics on `NaN` values.
+    pub fn update(&mut self, x: F) -> bool {
+        if x.is_nan() {
+            panic!("CuSum does not accept NaN values");
+        }
+        let zero = F::from_f64(0.).unwrap();
+        // The first value is its own reference
+        let deviation = self.mean.try_get().map_or(zero, |mean| x - mean);
+        self.mean.update(x);
+        self.positive_sum = (self.positive_sum + deviation - self.drift).max(zero);
+        self.negative_sum = (self.negative_sum - deviation - self.drift).max(zero);
+        if self.positive_sum > self.threshold || self.negative_sum > self.threshold {
+            *self = Self::new(self.threshold, self.drift).unwrap();
+            self.mean.update(x);
+            return true;
+        }
+        false
+    }
+    /// Cumulative sum of the upward deviations.
+    pub fn positive_sum(&self) -> F {
+        self.positive_sum
+    }
+    /// Cumulative sum of the downward deviations.
+    pub fn negative_sum(&self) -> F {
+        self.negative_sum
+    }
+}
+
+impl<F> Default for CuSum<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(5.).unwrap(), F::from_f64(0.5).unwrap()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Uniform values in [-0.5, 0.5)
+    fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut state: u64 = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+            })
+            .collect()
+    }
+    fn alarms(cusum: &mut CuSum<f64>, data: Vec<f64>) -> Vec<usize> {
+        data.into_iter()
+            .enumerate()
+            .filter_map(|(i, x)| cusum.update(x).then_some(i))
+            .collect()
+    }
+    #[test]
+    fn upward_shift() {
+        let mut cusum: CuSum<f64> = CuSum::new(10., 0.1).unwrap();
+        let mut data = noise(1000, 1);
+        data.extend(noise(1000, 2).into_iter().map(|x| x + 1.));
+        let alarms = alarms(&mut cusum, data);
+        assert_eq!(alarms.len(), 1);
+        assert!((1000..1030).contains(&alarms[0]));
+        assert!(cusum.positive_sum() < 10.);
+    }
+    #[test]
+    fn downward_shift() {
+        let mut cusum: CuSum<f64> = CuSum::new(10., 0.1).unwrap();
+        let mut data = noise(1000, 3);
+        data.extend(noise(1000, 4).into_iter().map(|x| x - 1.));
+        let mut alarms = Vec::new();
+        for (i, x) in data.into_iter().enumerate() {
+            let positive_sum = cusum.positive_sum();
+            if cusum.update(x) {
+                alarms.push(i);
+                // Only the negative sum grows after a downward shift
+                assert!(positive_sum < 1.);
+            }
+        }
+        assert_eq!(alarms.len(), 1);
+        assert!((1000..1030).contains(&alarms[0]));
+    }
+    #[test]
+    fn noise_only() {
+        let mut cusum: CuSum<f64> = CuSum::new(10., 0.1).unwrap();
+        assert!(alarms(&mut cusum, noise(20_000, 5)).is_empty());
+        assert!(cusum.positive_sum() < 10. && cusum.negative_sum() < 10.);
+        assert!(CuSum::<f64>::new(0., 0.1).is_err());
+        assert!(CuSum::<f64>::new(10., -0.1).is_err());
+    }
+}
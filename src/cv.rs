@@ -0,0 +1,114 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Revertable, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running coefficient of variation, `std / mean`.
+///
+/// The ratio is undefined when the mean is `0`, `get` then returns `NaN` if `nan_on_zero_mean` is set and `0` otherwise.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom forwarded to the variance. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen elements. Defaults to `1`, the sample variance, use `0` for the population variance.
+/// * `nan_on_zero_mean` - Whether a zero mean yields `NaN` or `0`. Defaults to `true`.
+/// # Examples
+/// ```
+/// use watermill::cv::CoefficientOfVariation;
+/// use watermill::stats::{Univariate, Revertable};
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let data_revert = data.clone();
+/// let mut running_cv: CoefficientOfVariation<f64> = CoefficientOfVariation::default();
+/// for x in data.into_iter(){
+///     running_cv.update(x);
+/// }
+/// assert_eq!(running_cv.get(), 12.566666666666668_f64.sqrt() / 6.833333333333333);
+/// // You can revert the coefficient of variation
+///
+/// for x in data_revert.into_iter().rev(){
+///     running_cv.revert(x);
+/// }
+/// assert!(running_cv.get().is_nan());
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the coefficient of variation](https://www.wikiwand.com/en/Coefficient_of_variation)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CoefficientOfVariation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+    pub nan_on_zero_mean: bool,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> CoefficientOfVariation<F> {
+    pub fn new(ddof: u32, nan_on_zero_mean: bool) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+            nan_on_zero_mean,
+        }
+    }
+}
+
+impl<F> Default for CoefficientOfVariation<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1, true)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for CoefficientOfVariation<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        let mean = self.variance.mean.get();
+        if mean == F::from_f64(0.).unwrap() {
+            if self.nan_on_zero_mean {
+                return F::nan();
+            }
+            return F::from_f64(0.).unwrap();
+        }
+        self.variance.get().sqrt() / mean
+    }
+    fn n(&self) -> usize {
+        self.variance.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for CoefficientOfVariation<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.variance.revert(x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn positive_sequence() {
+        let mut running_cv: CoefficientOfVariation<f64> = CoefficientOfVariation::new(0, true);
+        for x in vec![2., 4., 4., 4., 5., 5., 7., 9.].into_iter() {
+            running_cv.update(x);
+        }
+        // mean 5, population std 2
+        assert!((running_cv.get() - 0.4).abs() < 1e-12);
+    }
+    #[test]
+    fn zero_mean() {
+        let mut running_cv: CoefficientOfVariation<f64> = CoefficientOfVariation::new(1, false);
+        for x in vec![-1., 1.].into_iter() {
+            running_cv.update(x);
+        }
+        assert_eq!(running_cv.get(), 0.);
+        running_cv.nan_on_zero_mean = true;
+        assert!(running_cv.get().is_nan());
+    }
+    #[test]
+    fn rolling_cv() {
+        let data: Vec<f64> = vec![100., 1., 2., 4., 4., 4., 5., 5., 7., 9.];
+        let mut running_cv: CoefficientOfVariation<f64> = CoefficientOfVariation::new(0, true);
+        let mut rolling_cv = Rolling::new(&mut running_cv, 8).unwrap();
+        for x in data.into_iter() {
+            rolling_cv.update(x);
+        }
+        assert!((rolling_cv.get() - 0.4).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::stats::{Scalable, Univariate};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+/// Generic wrapper weighting values with an exponential forgetting factor.
+///
+/// Before each update the wrapped statistic is scaled by `decay`, so a value seen `k` updates ago
+/// weighs `decay^k`. Unlike `rolling::Rolling`, no value is stored and old values fade smoothly
+/// instead of being dropped. It can be wrapped around any struct implementing the `Univariate`
+/// and `Scalable` traits.
+/// # Arguments
+/// * `to_decay` - A running statistic which implements `Univariate` and `Scalable` traits.
+/// * `decay` - Forgetting factor, must be in `(0, 1)`. The closer to `0` the faster past values are forgotten.
+/// # Examples
+/// ```
+/// use watermill::decaying::Decaying;
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let mut running_mean: Mean<f64> = Mean::new();
+/// let mut decaying_mean: Decaying<_, f64> = Decaying::new(&mut running_mean, 0.5).unwrap();
+/// for x in vec![0., 0., 0., 8.].into_iter(){
+///     decaying_mean.update(x);
+/// }
+/// // The weights are 1/8, 1/4, 1/2 and 1
+/// assert!((decaying_mean.get() - 8. / 1.875).abs() < 1e-12);
+/// ```
+pub struct Decaying<'a, U, F>
+where
+    U: Univariate<F> + Scalable<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    to_decay: &'a mut U,
+    decay: F,
+}
+
+impl<'a, U, F> Decaying<'a, U, F>
+where
+    U: Univariate<F> + Scalable<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub fn new(to_decay: &'a mut U, decay: F) -> Result<Self, &'static str> {
+        if !(decay > F::from_f64(0.).unwrap() && decay < F::from_f64(1.).unwrap()) {
+            return Err("decay should be in (0, 1)");
+        }
+        Ok(Self { to_decay, decay })
+    }
+}
+
+impl<'a, U, F> Univariate<F> for Decaying<'a, U, F>
+where
+    U: Univariate<F> + Scalable<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn update(&mut self, x: F) {
+        self.to_decay.scale(self.decay);
+        self.to_decay.update(x);
+    }
+    fn get(&self) -> F {
+        self.to_decay.get()
+    }
+    fn n(&self) -> usize {
+        self.to_decay.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::count::Count;
+    use crate::mean::Mean;
+    use crate::sum::Sum;
+    #[test]
+    fn recent_values_dominate() {
+        let mut plain_mean: Mean<f64> = Mean::new();
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut decaying_mean = Decaying::new(&mut running_mean, 0.9).unwrap();
+        for i in 0..200 {
+            let x = if i < 150 { 0. } else { 10. };
+            plain_mean.update(x);
+            decaying_mean.update(x);
+        }
+        assert!((plain_mean.get() - 2.5).abs() < 1e-12);
+        // The last 50 values hold nearly all the weight, 1 - 0.9^50 of a geometric series
+        let expected = 10. * (1. - 0.9_f64.powi(50)) / (1. - 0.9_f64.powi(200));
+        assert!((decaying_mean.get() - expected).abs() < 1e-9);
+        assert!(decaying_mean.get() > 9.9);
+    }
+    #[test]
+    fn sum_and_count_converge() {
+        let mut running_sum: Sum<f64> = Sum::new();
+        let mut running_count: Count<f64> = Count::new();
+        let mut decaying_sum = Decaying::new(&mut running_sum, 0.75).unwrap();
+        let mut decaying_count = Decaying::new(&mut running_count, 0.75).unwrap();
+        for _ in 0..200 {
+            decaying_sum.update(3.);
+            decaying_count.update(3.);
+        }
+        // Geometric series of ratio 0.75
+        assert!((decaying_sum.get() - 12.).abs() < 1e-9);
+        assert!((decaying_count.get() - 4.).abs() < 1e-9);
+    }
+    #[test]
+    fn invalid_decay() {
+        let mut running_sum: Sum<f64> = Sum::new();
+        assert!(Decaying::new(&mut running_sum, 0.).is_err());
+        assert!(Decaying::new(&mut running_sum, 1.).is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use crate::utils::to_key;
+use serde::{Deserialize, Serialize};
+
+// Frequency table maintaining `sum(c * ln(c))` so the entropy is available in constant time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Frequencies<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counts: BTreeMap<u64, usize>,
+    n: usize,
+    sum_c_ln_c: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Frequencies<F> {
+    fn new() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            n: 0,
+            sum_c_ln_c: F::from_f64(0.).unwrap(),
+        }
+    }
+    fn c_ln_c(c: usize) -> F {
+        if c == 0 {
+            return F::from_f64(0.).unwrap();
+        }
+        let c = F::from_usize(c).unwrap();
+        c * c.ln()
+    }
+    fn add(&mut self, key: u64) {
+        let count = self.counts.entry(key).or_insert(0);
+        self.sum_c_ln_c += Self::c_ln_c(*count + 1) - Self::c_ln_c(*count);
+        *count += 1;
+        self.n += 1;
+    }
+    fn remove(&mut self, key: u64) {
+        let count = self.counts.get_mut(&key).expect("Value should be counted");
+        self.sum_c_ln_c -= Self::c_ln_c(*count) - Self::c_ln_c(*count - 1);
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(&key);
+        }
+        self.n -= 1;
+    }
+    // Natural logarithm entropy
+    fn entropy(&self) -> F {
+        if self.n == 0 {
+            return F::from_f64(0.).unwrap();
+        }
+        let n = F::from_usize(self.n).unwrap();
+        // Clamp the rounding errors accumulated by the removals
+        (n.ln() - self.sum_c_ln_c / n).max(F::from_f64(0.).unwrap())
+    }
+}
+
+/// Running Shannon entropy of the observed values, `-sum(p_i * log(p_i))`.
+///
+/// Values are compared by their exact bit pattern.
+/// # Arguments
+/// * `base` - Base of the logarithm, must be positive, finite and different from `1`. Defaults to `e`.
+/// # Examples
+/// ```
+/// use watermill::entropy::Entropy;
+/// use watermill::stats::Univariate;
+/// let mut running_entropy: Entropy<f64> = Entropy::new(2.).unwrap();
+/// for x in vec![0., 1., 2., 3., 0., 1., 2., 3.].into_iter(){
+///     running_entropy.update(x);
+/// }
+/// assert!((running_entropy.get() - 2.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on entropy](https://www.wikiwand.com/en/Entropy_(information_theory))
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entropy<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    frequencies: Frequencies<F>,
+    pub base: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Entropy<F> {
+    pub fn new(base: F) -> Result<Self, &'static str> {
+        if !(base > F::from_f64(0.).unwrap() && base.is_finite())
+            || base == F::from_f64(1.).unwrap()
+        {
+            return Err("base should be positive, finite and different from 1");
+        }
+        Ok(Self {
+            frequencies: Frequencies::new(),
+            base,
+        })
+    }
+}
+
+impl<F> Default for Entropy<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            frequencies: Frequencies::new(),
+            base: F::from_f64(core::f64::consts::E).unwrap(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Entropy<F> {
+    fn update(&mut self, x: F) {
+        self.frequencies.add(to_key(x));
+    }
+    fn get(&self) -> F {
+        self.frequencies.entropy() / self.base.ln()
+    }
+    fn n(&self) -> usize {
+        self.frequencies.n
+    }
+}
+
+// How values are grouped before being counted by `RollingEntropy`
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Binning<F> {
+    Exact,
+    Width(F),
+    Count { low: F, high: F, count: usize },
+}
+
+impl<F: Float + FromPrimitive> Binning<F> {
+    fn exact() -> Self {
+        Self::Exact
+    }
+    fn key(&self, x: F) -> u64 {
+        match *self {
+            Self::Exact => to_key(x),
+            Self::Width(width) => to_key((x / width).floor()),
+            Self::Count { low, high, count } => {
+                let bin = ((x - low) / (high - low) * F::from_usize(count).unwrap())
+                    .floor()
+                    .max(F::from_f64(0.).unwrap())
+                    .min(F::from_usize(count - 1).unwrap());
+                to_key(bin)
+            }
+        }
+    }
+}
+
+/// Rolling Shannon entropy of the values inside a window.
+///
+/// By default values are compared by their exact bit pattern, which suits discrete values.
+/// `with_bin_width` and `with_bin_count` group continuous values into bins first, giving the
+/// entropy of the distribution of the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `base` - Base of the logarithm, must be positive, finite and different from `1`.
+/// # Examples
+/// ```
+/// use watermill::entropy::RollingEntropy;
+/// use watermill::stats::Univariate;
+/// let mut rolling_entropy: RollingEntropy<f64> = RollingEntropy::new(2, 2.).unwrap();
+/// for x in vec![0., 1., 2., 2.].into_iter(){
+///     rolling_entropy.update(x);
+/// }
+/// assert_eq!(rolling_entropy.get(), 0.);
+/// ```
+/// Entropy of the distribution of continuous values, binned in intervals of width `0.5`:
+/// ```
+/// use watermill::entropy::RollingEntropy;
+/// use watermill::stats::Univariate;
+/// let mut rolling_entropy: RollingEntropy<f64> = RollingEntropy::with_bin_width(4, 2., 0.5).unwrap();
+/// for x in vec![0.1, 0.2, 0.7, 0.9].into_iter(){
+///     rolling_entropy.update(x);
+/// }
+/// assert!((rolling_entropy.get() - 1.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingEntropy<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    frequencies: Frequencies<F>,
+    window: VecDeque<u64>,
+    window_size: usize,
+    pub base: F,
+    #[serde(default = "Binning::exact")]
+    binning: Binning<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingEntropy<F> {
+    pub fn new(window_size: usize, base: F) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        if !(base > F::from_f64(0.).unwrap() && base.is_finite())
+            || base == F::from_f64(1.).unwrap()
+        {
+            return Err("base should be positive, finite and different from 1");
+        }
+        Ok(Self {
+            frequencies: Frequencies::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            base,
+            binning: Binning::Exact,
+        })
+    }
+    /// Same as `new`, values are grouped in bins `[k * bin_width, (k + 1) * bin_width)`.
+    pub fn with_bin_width(window_size: usize, base: F, bin_width: F) -> Result<Self, &'static str> {
+        if !(bin_width > F::from_f64(0.).unwrap() && bin_width.is_finite()) {
+            return Err("bin_width should be positive");
+        }
+        let mut rolling_entropy = Self::new(window_size, base)?;
+        rolling_entropy.binning = Binning::Width(bin_width);
+        Ok(rolling_entropy)
+    }
+    /// Same as `new`, values are grouped in `bin_count` bins of equal width spanning `[low, high)`.
+    /// Values outside of the range fall in the first or the last bin.
+    pub fn with_bin_count(
+        window_size: usize,
+        base: F,
+        low: F,
+        high: F,
+        bin_count: usize,
+    ) -> Result<Self, &'static str> {
+        if bin_count == 0 {
+            return Err("bin_count should not equal to 0");
+        }
+        if !(low < high && low.is_finite() && high.is_finite()) {
+            return Err("low should be lower than high");
+        }
+        let mut rolling_entropy = Self::new(window_size, base)?;
+        rolling_entropy.binning = Binning::Count {
+            low,
+            high,
+            count: bin_count,
+        };
+        Ok(rolling_entropy)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingEntropy<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.frequencies.remove(oldest);
+        }
+        let key = self.binning.key(x);
+        self.window.push_back(key);
+        self.frequencies.add(key);
+    }
+    fn get(&self) -> F {
+        self.frequencies.entropy() / self.base.ln()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn uniform_distribution() {
+        for k in [1_usize, 2, 5, 16] {
+            let mut running_entropy: Entropy<f64> = Entropy::default();
+            for i in 0..(k * 10) {
+                running_entropy.update((i % k) as f64);
+            }
+            assert!((running_entropy.get() - (k as f64).ln()).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn rolling_matches_window() {
+        let data: Vec<f64> = vec![1., 1., 2., 3., 1., 4., 4., 4., 2., 5.];
+        let mut rolling_entropy: RollingEntropy<f64> =
+            RollingEntropy::new(4, std::f64::consts::E).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_entropy.update(*x);
+            let mut window_entropy: Entropy<f64> = Entropy::default();
+            for y in data[(i + 1).saturating_sub(4)..=i].iter() {
+                window_entropy.update(*y);
+            }
+            assert!((rolling_entropy.get() - window_entropy.get()).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn binned_uniform_window() {
+        // 100 values spread evenly over 10 bins of width 0.1
+        let data: Vec<f64> = (0..1000)
+            .map(|i| ((i * 37) % 100) as f64 / 100. + 0.005)
+            .collect();
+        let mut by_width: RollingEntropy<f64> =
+            RollingEntropy::with_bin_width(100, std::f64::consts::E, 0.1).unwrap();
+        let mut by_count: RollingEntropy<f64> =
+            RollingEntropy::with_bin_count(100, std::f64::consts::E, 0., 1., 10).unwrap();
+        let mut exact: RollingEntropy<f64> = RollingEntropy::new(100, std::f64::consts::E).unwrap();
+        for x in data.into_iter() {
+            by_width.update(x);
+            by_count.update(x);
+            exact.update(x);
+        }
+        assert!((by_width.get() - 10_f64.ln()).abs() < 1e-9);
+        assert!((by_count.get() - 10_f64.ln()).abs() < 1e-9);
+        // Every value is distinct without binning
+        assert!((exact.get() - 100_f64.ln()).abs() < 1e-9);
+    }
+    #[test]
+    fn binned_single_value_window() {
+        let mut single: RollingEntropy<f64> = RollingEntropy::with_bin_width(1, 2., 0.5).unwrap();
+        let mut same_bin: RollingEntropy<f64> =
+            RollingEntropy::with_bin_count(10, 2., -1., 1., 4).unwrap();
+        for i in 0..50 {
+            single.update(i as f64 * 0.3);
+            assert_eq!(single.get(), 0.);
+            // Values out of range land in the last bin
+            same_bin.update(0.6 + i as f64);
+            assert_eq!(same_bin.get(), 0.);
+        }
+        assert!(RollingEntropy::<f64>::with_bin_width(10, 2., 0.).is_err());
+        assert!(RollingEntropy::<f64>::with_bin_count(10, 2., 0., 1., 0).is_err());
+        assert!(RollingEntropy::<f64>::with_bin_count(10, 2., 1., 1., 4).is_err());
+    }
+    #[test]
+    fn invalid_base() {
+        assert!(Entropy::<f64>::new(1.).is_err());
+        assert!(RollingEntropy::<f64>::new(3, 0.).is_err());
+        assert!(RollingEntropy::<f64>::new(0, 2.).is_err());
+        assert!(Entropy::<f64>::new(f64::NAN).is_err());
+        assert!(Entropy::<f64>::new(f64::INFINITY).is_err());
+        assert!(RollingEntropy::<f64>::new(3, f64::NAN).is_err());
+    }
+}
@@ -1,11 +1,12 @@
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
-use crate::stats::Univariate;
+use crate::count::Count;
+use crate::stats::{Univariate, WeightedUnivariate};
 use serde::{Deserialize, Serialize};
 /// Exponentially weighted mean.
 /// # Arguments
-/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Must be in `(0, 1]`, default value is `0.5`.
 /// # Examples
 /// ```
 /// use watermill::ewmean::EWMean;
@@ -17,6 +18,20 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_ewmean.get(), 9.4296875);
 /// ```
+/// With a custom `alpha`, the first value initialises the mean.
+/// ```
+/// use watermill::ewmean::EWMean;
+/// use watermill::stats::Univariate;
+/// let mut running_ewmean: EWMean<f64> = EWMean::new(0.25).unwrap();
+/// let data = vec![1., 3., 5., 4., 6., 8., 7., 9., 11.];
+/// for i in data.iter(){
+///     running_ewmean.update(*i as f64);
+/// }
+/// assert_eq!(running_ewmean.get(), 7.400238037109375);
+/// assert!(EWMean::new(0.).is_err());
+/// assert!(EWMean::new(1.5).is_err());
+/// assert!(EWMean::new(f64::NAN).is_err());
+/// ```
 /// # References
 /// [^1]: [Finch, T., 2009. Incremental calculation of weighted mean and variance. University of Cambridge, 4(11-5), pp.41-42.](https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf)
 ///
@@ -25,13 +40,36 @@ use serde::{Deserialize, Serialize};
 pub struct EWMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub mean: F,
     pub alpha: F,
+    #[serde(default = "Count::new")]
+    pub n: Count<F>,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWMean<F> {
-    pub fn new(alpha: F) -> Self {
-        Self {
+    pub fn new(alpha: F) -> Result<Self, &'static str> {
+        if !(alpha > F::from_f64(0.).unwrap() && alpha <= F::from_f64(1.).unwrap()) {
+            return Err("alpha should be in (0, 1]");
+        }
+        Ok(Self {
             mean: F::from_f64(0.0).unwrap(),
             alpha,
-        }
+            n: Count::new(),
+        })
+    }
+    /// Effective number of values, the sum of the weights of the values seen so far relative to
+    /// the latest one, `(1 - (1 - alpha)^n) / alpha` after `n` updates. It is `0` before any
+    /// update, `1` after the first one and grows towards `1 / alpha`, the number of values the
+    /// mean is effectively computed on once warmed up. Weighted updates count for their weight.
+    /// # Examples
+    /// ```
+    /// use watermill::ewmean::EWMean;
+    /// use watermill::stats::Univariate;
+    /// let mut running_ewmean: EWMean<f64> = EWMean::new(0.1).unwrap();
+    /// running_ewmean.update(1.);
+    /// running_ewmean.update(2.);
+    /// assert!((running_ewmean.effective_n() - 1.9).abs() < 1e-12);
+    /// ```
+    pub fn effective_n(&self) -> F {
+        let one = F::from_f64(1.).unwrap();
+        (one - (one - self.alpha).powf(self.n.get())) / self.alpha
     }
 }
 
@@ -43,13 +81,15 @@ where
         Self {
             mean: F::from_f64(0.).unwrap(),
             alpha: F::from_f64(0.5).unwrap(),
+            n: Count::new(),
         }
     }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWMean<F> {
     fn update(&mut self, x: F) {
-        if self.mean == F::from_f64(0.).unwrap() {
+        self.n.update(x);
+        if self.n.get() == F::from_f64(1.).unwrap() {
             self.mean = x;
         } else {
             self.mean = self.alpha * x + (F::from_f64(1.).unwrap() - self.alpha) * self.mean;
@@ -58,4 +98,75 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWMean<
     fn get(&self) -> F {
         self.mean
     }
+    fn n(&self) -> usize {
+        self.n.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WeightedUnivariate<F> for EWMean<F> {
+    /// `w` repeated updates decay the previous mean by `(1 - alpha)^w`.
+    fn update_weighted(&mut self, x: F, w: F) {
+        if w == F::from_f64(0.).unwrap() {
+            return;
+        }
+        if self.n.get() == F::from_f64(0.).unwrap() {
+            self.mean = x;
+        } else {
+            self.mean = x + (self.mean - x) * (F::from_f64(1.).unwrap() - self.alpha).powf(w);
+        }
+        self.n.count += w;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn deserialize_without_count() {
+        let running_ewmean: EWMean<f64> =
+            serde_json::from_str(r#"{"mean":2.0,"alpha":0.5}"#).unwrap();
+        assert_eq!(running_ewmean.get(), 2.);
+        assert_eq!(running_ewmean.n(), 0);
+    }
+    #[test]
+    fn zero_mean_is_not_reinitialised() {
+        let mut running_ewmean: EWMean<f64> = EWMean::default();
+        running_ewmean.update(0.);
+        running_ewmean.update(4.);
+        assert_eq!(running_ewmean.get(), 2.);
+    }
+    #[test]
+    fn update_weighted() {
+        let mut repeated: EWMean<f64> = EWMean::new(0.1).unwrap();
+        let mut weighted: EWMean<f64> = EWMean::new(0.1).unwrap();
+        for (x, w) in [(5., 10), (-2.5, 3), (7., 1), (0.25, 40)] {
+            for _ in 0..w {
+                repeated.update(x);
+            }
+            weighted.update_weighted(x, w as f64);
+            assert!((weighted.get() - repeated.get()).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn effective_n_approaches_inverse_alpha() {
+        let mut running_ewmean: EWMean<f64> = EWMean::new(0.05).unwrap();
+        assert_eq!(running_ewmean.effective_n(), 0.);
+        let mut previous = 0.;
+        for i in 0..1000 {
+            running_ewmean.update(i as f64);
+            let effective_n = running_ewmean.effective_n();
+            assert!(effective_n >= previous);
+            assert!(effective_n <= 20.);
+            previous = effective_n;
+        }
+        assert!((previous - 20.).abs() < 1e-9);
+        let mut weighted: EWMean<f64> = EWMean::new(0.05).unwrap();
+        weighted.update_weighted(3., 1000.);
+        assert!((weighted.effective_n() - previous).abs() < 1e-9);
+        // With alpha = 1 only the latest value counts
+        let mut latest: EWMean<f64> = EWMean::new(1.).unwrap();
+        latest.update(1.);
+        latest.update(2.);
+        assert_eq!(latest.effective_n(), 1.);
+    }
 }
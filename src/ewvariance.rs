@@ -1,12 +1,12 @@
 use crate::ewmean::EWMean;
 use crate::stats::Univariate;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 
 /// Exponentially weighted variance.
 /// # Arguments
-/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Must be in `(0, 1]`, default value is `0.5`.
 /// # Examples
 /// ```
 /// use watermill::ewvariance::EWVariance;
@@ -29,12 +29,16 @@ pub struct EWVariance<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub alpha: F,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWVariance<F> {
-    pub fn new(alpha: F) -> Self {
-        Self {
-            mean: EWMean::new(alpha),
-            sq_mean: EWMean::new(alpha),
+    pub fn new(alpha: F) -> Result<Self, &'static str> {
+        Ok(Self {
+            mean: EWMean::new(alpha)?,
+            sq_mean: EWMean::new(alpha)?,
             alpha,
-        }
+        })
+    }
+    /// Effective number of values the variance is computed on, see `EWMean::effective_n`.
+    pub fn effective_n(&self) -> F {
+        self.mean.effective_n()
     }
 }
 
@@ -43,11 +47,10 @@ where
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
     fn default() -> Self {
-        let alpha = F::from_f64(0.5).unwrap();
         Self {
-            mean: EWMean::new(alpha),
-            sq_mean: EWMean::new(alpha),
-            alpha,
+            mean: EWMean::default(),
+            sq_mean: EWMean::default(),
+            alpha: F::from_f64(0.5).unwrap(),
         }
     }
 }
@@ -60,4 +63,97 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWVaria
     fn get(&self) -> F {
         self.sq_mean.get() - self.mean.get().powf(F::from_i8(2).unwrap())
     }
+    fn n(&self) -> usize {
+        self.mean.n()
+    }
+}
+
+/// Exponentially weighted standard deviation, the square root of `EWVariance`.
+/// # Arguments
+/// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Must be in `(0, 1]`, default value is `0.5`.
+/// # Examples
+/// ```
+/// use watermill::ewvariance::EWStd;
+/// use watermill::stats::Univariate;
+/// let mut running_ewstd: EWStd<f64> = EWStd::default();
+/// let data = vec![1., 3., 5., 4., 6., 8., 7., 9., 11.];
+/// for i in data.iter(){
+///     running_ewstd.update(*i as f64);
+/// }
+/// assert_eq!(running_ewstd.get(), 3.56536865234375_f64.sqrt());
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EWStd<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: EWVariance<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWStd<F> {
+    pub fn new(alpha: F) -> Result<Self, &'static str> {
+        Ok(Self {
+            variance: EWVariance::new(alpha)?,
+        })
+    }
+}
+
+impl<F> Default for EWStd<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            variance: EWVariance::default(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for EWStd<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        // Rounding can make the variance slightly negative when it should be 0
+        self.variance.get().max(F::from_f64(0.).unwrap()).sqrt()
+    }
+    fn n(&self) -> usize {
+        self.variance.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn matches_west_recursion() {
+        let alpha = 0.3;
+        let data: Vec<f64> = vec![2.5, -1., 4., 0., 3.2, 7.7, -2.4, 5., 5., 1.];
+        let mut running_ewvariance: EWVariance<f64> = EWVariance::new(alpha).unwrap();
+        let mut running_ewstd: EWStd<f64> = EWStd::new(alpha).unwrap();
+        let (mut mean, mut var) = (data[0], 0.);
+        for (i, x) in data.iter().enumerate() {
+            if i > 0 {
+                let diff = x - mean;
+                let incr = alpha * diff;
+                mean += incr;
+                var = (1. - alpha) * (var + diff * incr);
+            }
+            running_ewvariance.update(*x);
+            running_ewstd.update(*x);
+            assert!((running_ewvariance.get() - var).abs() < 1e-9);
+            assert!((running_ewstd.get() - var.sqrt()).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn invalid_alpha() {
+        assert!(EWVariance::<f64>::new(0.).is_err());
+        assert!(EWStd::<f64>::new(1.1).is_err());
+    }
+    #[test]
+    fn effective_n() {
+        let mut running_ewvariance: EWVariance<f64> = EWVariance::new(0.2).unwrap();
+        for i in 0..200 {
+            running_ewvariance.update((i % 5) as f64);
+            let expected = (1. - 0.8_f64.powi(i + 1)) / 0.2;
+            assert!((running_ewvariance.effective_n() - expected).abs() < 1e-12);
+        }
+        assert!((running_ewvariance.effective_n() - 5.).abs() < 1e-12);
+    }
 }
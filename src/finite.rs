@@ -0,0 +1,206 @@
+use core::marker::PhantomData;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Revertable, Univariate};
+use serde::{Deserialize, Serialize};
+
+/// How a `Finite` wrapper handles `NaN` and infinite values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NonFinitePolicy {
+    /// `try_update` returns an error and `update` panics, the statistic is left untouched.
+    #[default]
+    Reject,
+    /// Non-finite values are ignored, they are counted by `Finite::skipped`.
+    Skip,
+    /// Non-finite values reach the statistic, as if it was not wrapped.
+    Propagate,
+}
+
+/// Generic wrapper guarding a statistic against `NaN` and infinite values.
+///
+/// Most running statistics, e.g. `sum::Sum`, `mean::Mean` or `variance::Variance`, absorb a `NaN`
+/// or an infinite value and are poisoned for good, every later value being `NaN` or infinite.
+/// `Finite` checks each value before it reaches the wrapped statistic and follows its
+/// `NonFinitePolicy`: reject it with a clear error, skip it, or let it through. Reverting follows
+/// the same policy, so a skipped value is skipped again when it leaves a `rolling::Rolling`
+/// window.
+/// # Arguments
+/// * `stat` - Statistic fed with the finite values.
+/// * `policy` - Handling of `NaN` and infinite values, `NonFinitePolicy::default()` rejects them.
+/// # Examples
+/// ```
+/// use watermill::finite::{Finite, NonFinitePolicy};
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let mut strict_mean = Finite::new(Mean::<f64>::new(), NonFinitePolicy::Reject);
+/// strict_mean.update(1.);
+/// assert!(strict_mean.try_update(f64::NAN).is_err());
+/// assert_eq!(strict_mean.get(), 1.);
+///
+/// let mut lenient_mean = Finite::new(Mean::<f64>::new(), NonFinitePolicy::Skip);
+/// for x in vec![1., f64::NAN, 3., f64::INFINITY].into_iter(){
+///     lenient_mean.update(x);
+/// }
+/// assert_eq!(lenient_mean.get(), 2.);
+/// assert_eq!(lenient_mean.skipped(), 2);
+/// ```
+/// # Panics
+/// With `NonFinitePolicy::Reject`, `update` panics on `NaN` and infinite values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Finite<U, F>
+where
+    U: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub stat: U,
+    pub policy: NonFinitePolicy,
+    skipped: usize,
+    #[serde(skip)]
+    _float: PhantomData<F>,
+}
+
+impl<U, F> Finite<U, F>
+where
+    U: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub fn new(stat: U, policy: NonFinitePolicy) -> Self {
+        Self {
+            stat,
+            policy,
+            skipped: 0,
+            _float: PhantomData,
+        }
+    }
+    /// Number of non-finite values skipped with `NonFinitePolicy::Skip`, the ones skipped again
+    /// by `revert` are no longer counted.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+    /// Same as `update`, returning an error instead of panicking on a rejected value.
+    pub fn try_update(&mut self, x: F) -> Result<(), &'static str> {
+        if !x.is_finite() {
+            match self.policy {
+                NonFinitePolicy::Reject => return Err("Value should be finite"),
+                NonFinitePolicy::Skip => {
+                    self.skipped += 1;
+                    return Ok(());
+                }
+                NonFinitePolicy::Propagate => {}
+            }
+        }
+        self.stat.update(x);
+        Ok(())
+    }
+}
+
+impl<U, F> Univariate<F> for Finite<U, F>
+where
+    U: Univariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn update(&mut self, x: F) {
+        self.try_update(x).unwrap();
+    }
+    fn get(&self) -> F {
+        self.stat.get()
+    }
+    fn try_get(&self) -> Option<F> {
+        self.stat.try_get()
+    }
+    fn n(&self) -> usize {
+        self.stat.n()
+    }
+}
+
+impl<U, F> Revertable<F> for Finite<U, F>
+where
+    U: Univariate<F> + Revertable<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        if !x.is_finite() {
+            match self.policy {
+                NonFinitePolicy::Reject => return Err("Value should be finite"),
+                NonFinitePolicy::Skip => {
+                    self.skipped = self.skipped.saturating_sub(1);
+                    return Ok(());
+                }
+                NonFinitePolicy::Propagate => {}
+            }
+        }
+        self.stat.revert(x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mean::Mean;
+    use crate::rolling::RollingOwned;
+    use crate::sum::Sum;
+    use crate::variance::Variance;
+    const NON_FINITE: [f64; 3] = [f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+    // Feed 1, 2, `bad` and 3 under `policy`, returning the statistic and the errors of `try_update`
+    fn feed<U: Univariate<f64>>(
+        stat: U,
+        policy: NonFinitePolicy,
+        bad: f64,
+    ) -> (Finite<U, f64>, usize) {
+        let mut finite = Finite::new(stat, policy);
+        let errors = [1., 2., bad, 3.]
+            .iter()
+            .filter(|x| finite.try_update(**x).is_err())
+            .count();
+        (finite, errors)
+    }
+    #[test]
+    fn reject() {
+        for bad in NON_FINITE {
+            let (sum, errors) = feed(Sum::<f64>::new(), NonFinitePolicy::Reject, bad);
+            assert_eq!((sum.get(), errors), (6., 1));
+            let (mean, errors) = feed(Mean::<f64>::new(), NonFinitePolicy::Reject, bad);
+            assert_eq!((mean.get(), errors), (2., 1));
+            let (variance, errors) = feed(Variance::<f64>::new(1), NonFinitePolicy::Reject, bad);
+            assert_eq!((variance.get(), errors), (1., 1));
+            assert_eq!(variance.n(), 3);
+        }
+    }
+    #[test]
+    fn skip() {
+        for bad in NON_FINITE {
+            let (sum, errors) = feed(Sum::<f64>::new(), NonFinitePolicy::Skip, bad);
+            assert_eq!((sum.get(), errors), (6., 0));
+            assert_eq!(sum.skipped(), 1);
+            let (mean, errors) = feed(Mean::<f64>::new(), NonFinitePolicy::Skip, bad);
+            assert_eq!((mean.get(), errors), (2., 0));
+            let (variance, errors) = feed(Variance::<f64>::new(1), NonFinitePolicy::Skip, bad);
+            assert_eq!((variance.get(), errors), (1., 0));
+        }
+    }
+    #[test]
+    fn propagate() {
+        let (sum, errors) = feed(Sum::<f64>::new(), NonFinitePolicy::Propagate, f64::INFINITY);
+        assert_eq!((sum.get(), errors), (f64::INFINITY, 0));
+        let (mean, _) = feed(Mean::<f64>::new(), NonFinitePolicy::Propagate, f64::NAN);
+        assert!(mean.get().is_nan());
+    }
+    #[test]
+    #[should_panic(expected = "Value should be finite")]
+    fn update_panics_on_reject() {
+        let mut finite = Finite::new(Mean::<f64>::new(), NonFinitePolicy::default());
+        finite.update(f64::NAN);
+    }
+    #[test]
+    fn rolling_skips_on_both_ends() {
+        let finite = Finite::new(Sum::<f64>::new(), NonFinitePolicy::Skip);
+        let mut rolling_sum = RollingOwned::new(finite, 2).unwrap();
+        for x in [1., f64::NAN, 2., 3.] {
+            rolling_sum.update(x);
+        }
+        assert_eq!(rolling_sum.get(), 5.);
+        rolling_sum.update(f64::INFINITY);
+        assert_eq!(rolling_sum.get(), 3.);
+    }
+}
@@ -0,0 +1,141 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use crate::utils::SplitMix64;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SEED: u64 = 0xf1a9;
+
+/// Running quantile estimator using the Frugal-1U algorithm, in constant memory.
+///
+/// The estimate starts at the first value. Then a value above the estimate moves it up by `step`
+/// with probability `q`, and a value below moves it down by `step` with probability `1 - q`, so
+/// the estimate settles where a fraction `q` of the values are below it. Only the estimate and
+/// the state of the random generator are stored. The estimate moves by at most `step` per
+/// update: a large `step` adapts quickly to a shift of the distribution, a small one is more
+/// precise on a stationary stream. `get` returns `NaN` before any update. The random generator is
+/// seeded, `with_seed` gives reproducible estimates.
+/// # Arguments
+/// * `q` - quantile value, must be between `0` and `1`.
+/// * `step` - Increment of the estimate, must be positive. Use `1` for integer-valued streams.
+/// # Examples
+/// ```
+/// use watermill::frugal::Frugal1U;
+/// use watermill::stats::Univariate;
+/// let mut frugal: Frugal1U<f64> = Frugal1U::new(0.5, 1.).unwrap();
+/// for i in 0..20000{
+///     frugal.update(((i * 7919) % 1000) as f64);
+/// }
+/// assert!((frugal.get() - 500.).abs() < 50.);
+/// ```
+/// # References
+/// [^1]: [Ma, Q., Muthukrishnan, S. and Sandler, M., 2013. Frugal streaming for estimating quantiles. In Space-Efficient Data Structures, Streams, and Algorithms (pp. 77-96). Springer.](https://arxiv.org/abs/1407.1121)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Frugal1U<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    q: F,
+    step: F,
+    estimate: F,
+    n: usize,
+    rng: SplitMix64,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Frugal1U<F> {
+    pub fn new(q: F, step: F) -> Result<Self, &'static str> {
+        Self::with_seed(q, step, DEFAULT_SEED)
+    }
+    /// Same as `new`, with the seed of the random generator.
+    pub fn with_seed(q: F, step: F, seed: u64) -> Result<Self, &'static str> {
+        if !(q >= F::from_f64(0.).unwrap() && q <= F::from_f64(1.).unwrap()) {
+            return Err("q should be between 0 and 1");
+        }
+        if !(step > F::from_f64(0.).unwrap() && step.is_finite()) {
+            return Err("step should be positive and finite");
+        }
+        Ok(Self {
+            q,
+            step,
+            estimate: F::nan(),
+            n: 0,
+            rng: SplitMix64::new(seed),
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Frugal1U<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("Frugal1U does not accept NaN values");
+        }
+        self.n += 1;
+        if self.n == 1 {
+            self.estimate = x;
+            return;
+        }
+        let r = F::from_f64(self.rng.next_f64()).unwrap();
+        if x > self.estimate && r < self.q {
+            self.estimate += self.step;
+        } else if x < self.estimate && r >= self.q {
+            self.estimate -= self.step;
+        }
+    }
+    fn get(&self) -> F {
+        self.estimate
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.estimate)
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn uniform(n: usize, low: f64, high: f64, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                low + (high - low) * ((state >> 11) as f64 / (1u64 << 53) as f64)
+            })
+            .collect()
+    }
+    #[test]
+    fn stationary_convergence() {
+        for q in [0.1, 0.5, 0.9] {
+            let mut frugal: Frugal1U<f64> = Frugal1U::new(q, 1.).unwrap();
+            assert!(frugal.get().is_nan() && frugal.try_get().is_none());
+            for x in uniform(30000, 0., 1000., 5) {
+                frugal.update(x);
+            }
+            assert!((frugal.get() - 1000. * q).abs() < 40., "q = {}", q);
+            assert_eq!(frugal.n(), 30000);
+        }
+    }
+    #[test]
+    fn adapts_to_shift() {
+        let mut frugal: Frugal1U<f64> = Frugal1U::new(0.9, 1.).unwrap();
+        for x in uniform(10000, 0., 1000., 9) {
+            frugal.update(x);
+        }
+        assert!((frugal.get() - 900.).abs() < 40.);
+        for x in uniform(20000, 5000., 6000., 10) {
+            frugal.update(x);
+        }
+        assert!((frugal.get() - 5900.).abs() < 40.);
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(Frugal1U::<f64>::new(1.5, 1.).is_err());
+        assert!(Frugal1U::<f64>::new(f64::NAN, 1.).is_err());
+        assert!(Frugal1U::<f64>::new(0.5, 0.).is_err());
+        assert!(Frugal1U::<f64>::new(0.5, f64::INFINITY).is_err());
+    }
+}
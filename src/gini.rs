@@ -0,0 +1,123 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Rolling Gini coefficient.
+///
+/// Computed with the sorted-rank formula `G = 2 * Σ i * x_i / (n * Σ x_i) - (n + 1) / n` where
+/// `x_i` is the `i`-th smallest value of the window. It is `0` when all the values are equal and
+/// tends to `1` when a single value holds the whole sum.
+/// The values are assumed to be non-negative, otherwise the coefficient is not bounded by `[0, 1]`.
+/// `get` returns `NaN` while the window is empty and `0` if all the values are zeros.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::gini::RollingGini;
+/// use watermill::stats::Univariate;
+/// let mut rolling_gini: RollingGini<f64> = RollingGini::new(4).unwrap();
+/// for x in vec![10., 1., 2., 3., 4.].into_iter(){
+///     rolling_gini.update(x);
+/// }
+/// assert!((rolling_gini.get() - 0.25).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Gini coefficient](https://www.wikiwand.com/en/Gini_coefficient)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingGini<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingGini<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingGini<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        if self.sorted_window.is_empty() {
+            return F::nan();
+        }
+        let mut sum = F::from_f64(0.).unwrap();
+        let mut rank_weighted_sum = F::from_f64(0.).unwrap();
+        for (i, x) in self.sorted_window.iter().enumerate() {
+            sum += *x;
+            rank_weighted_sum += F::from_usize(i + 1).unwrap() * *x;
+        }
+        if sum == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        let n = F::from_usize(self.sorted_window.len()).unwrap();
+        F::from_f64(2.).unwrap() * rank_weighted_sum / (n * sum)
+            - (n + F::from_f64(1.).unwrap()) / n
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Mean absolute difference over all pairs, divided by twice the mean
+    fn brute_force(window: &[f64]) -> f64 {
+        let n = window.len() as f64;
+        let mut abs_differences = 0.;
+        for x in window.iter() {
+            for y in window.iter() {
+                abs_differences += (x - y).abs();
+            }
+        }
+        abs_differences / (2. * n * window.iter().sum::<f64>())
+    }
+    #[test]
+    fn all_equal() {
+        let mut rolling_gini: RollingGini<f64> = RollingGini::new(10).unwrap();
+        for _ in 0..25 {
+            rolling_gini.update(3.);
+            assert!(rolling_gini.get().abs() < 1e-12);
+        }
+        let mut zeros: RollingGini<f64> = RollingGini::new(10).unwrap();
+        zeros.update(0.);
+        assert_eq!(zeros.get(), 0.);
+    }
+    #[test]
+    fn maximally_unequal() {
+        for window_size in [2, 10, 100, 1000] {
+            let mut rolling_gini: RollingGini<f64> = RollingGini::new(window_size).unwrap();
+            rolling_gini.update(42.);
+            for _ in 1..window_size {
+                rolling_gini.update(0.);
+            }
+            let n = window_size as f64;
+            assert!((rolling_gini.get() - (n - 1.) / n).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn matches_brute_force() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4., 10., 0., 3.5];
+        let mut rolling_gini: RollingGini<f64> = RollingGini::new(5).unwrap();
+        assert_eq!(rolling_gini.try_get(), None);
+        for (i, x) in data.iter().enumerate() {
+            rolling_gini.update(*x);
+            let window = &data[(i + 1).saturating_sub(5)..=i];
+            assert!((rolling_gini.get() - brute_force(window)).abs() < 1e-12);
+        }
+        assert!(RollingGini::<f64>::new(0).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Tuple<F> {
+    value: F,
+    // Rank gap with the previous tuple
+    g: usize,
+    // Uncertainty on the rank of the tuple
+    delta: usize,
+}
+
+/// Greenwald-Khanna quantile sketch, the rank of any answer is within `epsilon * n` of the asked rank.
+///
+/// Unlike `quantile::Quantile`, the guarantee holds whatever the order or the distribution of the values.
+/// `get` returns the configured quantile, use `quantile` to ask for any other.
+/// # Arguments
+/// * `epsilon` - Allowed rank error as a fraction of the number of values, must be between 0 and 1.
+/// * `q` - Quantile returned by `get`, must be between 0 and 1. Defaults to `0.5`.
+/// # Examples
+/// ```
+/// use watermill::gk::GKQuantile;
+/// use watermill::stats::Univariate;
+/// let mut running_quantile: GKQuantile<f64> = GKQuantile::new(0.01).unwrap();
+/// for i in 0..1000{
+///     running_quantile.update(i as f64);
+/// }
+/// assert!((running_quantile.get() - 500.).abs() <= 10.);
+/// assert!((running_quantile.quantile(0.9) - 900.).abs() <= 10.);
+/// ```
+/// # References
+/// [^1]: [Greenwald, M. and Khanna, S., 2001. Space-efficient online computation of quantile summaries. ACM SIGMOD Record, 30(2), pp.58-66.](https://dl.acm.org/doi/10.1145/376284.375670)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GKQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    epsilon: F,
+    q: F,
+    summary: Vec<Tuple<F>>,
+    n: usize,
+    compress_every: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> GKQuantile<F> {
+    pub fn new(epsilon: F) -> Result<Self, &'static str> {
+        Self::with_quantile(epsilon, F::from_f64(0.5).unwrap())
+    }
+    pub fn with_quantile(epsilon: F, q: F) -> Result<Self, &'static str> {
+        if !(epsilon > F::from_f64(0.).unwrap() && epsilon < F::from_f64(1.).unwrap()) {
+            return Err("epsilon should be between 0 and 1");
+        }
+        if !(q >= F::from_f64(0.).unwrap() && q <= F::from_f64(1.).unwrap()) {
+            return Err("q should be between 0 and 1");
+        }
+        let compress_every = (F::from_f64(1.).unwrap() / (F::from_f64(2.).unwrap() * epsilon))
+            .floor()
+            .to_usize()
+            .unwrap_or(usize::MAX)
+            .max(1);
+        Ok(Self {
+            epsilon,
+            q,
+            summary: Vec::new(),
+            n: 0,
+            compress_every,
+        })
+    }
+    /// Number of values seen.
+    pub fn count(&self) -> usize {
+        self.n
+    }
+    /// Value whose rank is within `epsilon * n` of `q * n`, `q` is clamped to `[0, 1]`.
+    /// Returns `NaN` if no value was seen.
+    pub fn quantile(&self, q: F) -> F {
+        let first = match self.summary.first() {
+            Some(tuple) => tuple.value,
+            None => return F::nan(),
+        };
+        let n = F::from_usize(self.n).unwrap();
+        let q = q
+            .max(F::from_f64(0.).unwrap())
+            .min(F::from_f64(1.).unwrap());
+        let bound = (q * n).ceil() + self.epsilon * n;
+        let mut previous = first;
+        let mut min_rank = 0;
+        for tuple in self.summary.iter() {
+            min_rank += tuple.g;
+            if F::from_usize(min_rank + tuple.delta).unwrap() > bound {
+                return previous;
+            }
+            previous = tuple.value;
+        }
+        previous
+    }
+    fn max_band(&self) -> usize {
+        (F::from_f64(2.).unwrap() * self.epsilon * F::from_usize(self.n).unwrap())
+            .floor()
+            .to_usize()
+            .unwrap()
+    }
+    fn compress(&mut self) {
+        if self.summary.len() < 3 {
+            return;
+        }
+        let threshold = self.max_band();
+        // Walk backwards, merging each tuple into its successor while the band allows it.
+        // The first tuple holds the minimum and is never merged.
+        let mut compressed: Vec<Tuple<F>> = Vec::with_capacity(self.summary.len());
+        let mut pending = self.summary[self.summary.len() - 1];
+        for tuple in self.summary[1..self.summary.len() - 1].iter().rev() {
+            if tuple.g + pending.g + pending.delta <= threshold {
+                pending.g += tuple.g;
+            } else {
+                compressed.push(pending);
+                pending = *tuple;
+            }
+        }
+        compressed.push(pending);
+        compressed.push(self.summary[0]);
+        compressed.reverse();
+        self.summary = compressed;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for GKQuantile<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("GKQuantile does not accept NaN values");
+        }
+        let position = self.summary.partition_point(|tuple| tuple.value <= x);
+        let delta = if position == 0 || position == self.summary.len() {
+            0
+        } else {
+            self.max_band()
+        };
+        self.summary.insert(
+            position,
+            Tuple {
+                value: x,
+                g: 1,
+                delta,
+            },
+        );
+        self.n += 1;
+        if self.n.is_multiple_of(self.compress_every) {
+            self.compress();
+        }
+    }
+    fn get(&self) -> F {
+        self.quantile(self.q)
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Values are 0..n, so the value itself is the number of values below it
+    fn assert_rank_within_bound(gk: &GKQuantile<f64>, n: usize, epsilon: f64) {
+        for i in 0..=100 {
+            let q = i as f64 / 100.;
+            let value = gk.quantile(q);
+            let rank = value + 1.;
+            assert!(
+                (rank - q * n as f64).abs() <= epsilon * n as f64 + 1.,
+                "q = {q}, value = {value}"
+            );
+        }
+    }
+    #[test]
+    fn sorted_input() {
+        let (n, epsilon) = (10_000, 0.01);
+        let mut gk: GKQuantile<f64> = GKQuantile::new(epsilon).unwrap();
+        for i in 0..n {
+            gk.update(i as f64);
+        }
+        assert_rank_within_bound(&gk, n, epsilon);
+        assert!(gk.summary.len() < n / 10);
+    }
+    #[test]
+    fn reverse_sorted_input() {
+        let (n, epsilon) = (10_000, 0.005);
+        let mut gk: GKQuantile<f64> = GKQuantile::new(epsilon).unwrap();
+        for i in (0..n).rev() {
+            gk.update(i as f64);
+        }
+        assert_rank_within_bound(&gk, n, epsilon);
+        assert!(gk.summary.len() < n / 10);
+    }
+    #[test]
+    fn interleaved_input() {
+        let (n, epsilon) = (10_000, 0.01);
+        let mut gk: GKQuantile<f64> = GKQuantile::with_quantile(epsilon, 0.9).unwrap();
+        for i in 0..n / 2 {
+            gk.update(i as f64);
+            gk.update((n - 1 - i) as f64);
+        }
+        assert_rank_within_bound(&gk, n, epsilon);
+        assert!((gk.get() + 1. - 9000.).abs() <= 101.);
+    }
+    #[test]
+    fn empty_and_invalid() {
+        let gk: GKQuantile<f64> = GKQuantile::new(0.01).unwrap();
+        assert!(gk.quantile(0.5).is_nan());
+        assert_eq!(gk.try_get(), None);
+        assert!(GKQuantile::<f64>::new(0.).is_err());
+        assert!(GKQuantile::<f64>::with_quantile(0.01, 1.5).is_err());
+    }
+}
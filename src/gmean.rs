@@ -0,0 +1,247 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::count::Count;
+use crate::stats::{Revertable, Univariate};
+use crate::utils::NeumaierSum;
+use serde::{Deserialize, Serialize};
+/// Running geometric mean.
+///
+/// The geometric mean is only defined for positive values, it is computed as `exp(mean(ln(x)))`.
+/// # Arguments
+/// * `skip_non_positive` - If `true`, `NaN` and values lower or equal to `0` are ignored by `update` and
+///   `revert`, otherwise they make `update` panic. Defaults to `false`.
+/// # Examples
+/// ```
+/// use watermill::gmean::GeometricMean;
+/// use watermill::stats::{Univariate, Revertable};
+/// let mut running_gmean: GeometricMean<f64> = GeometricMean::default();
+/// for x in vec![1., 2., 4., 8.].into_iter(){
+///     running_gmean.update(x);
+/// }
+/// assert!((running_gmean.get() - 2.8284271247461903).abs() < 1e-12);
+///
+/// // You can revert the geometric mean
+/// running_gmean.revert(8.);
+/// assert!((running_gmean.get() - 2.).abs() < 1e-12);
+/// ```
+/// Skipping the non-positive values.
+/// ```
+/// use watermill::gmean::GeometricMean;
+/// use watermill::stats::Univariate;
+/// let mut running_gmean: GeometricMean<f64> = GeometricMean::new(true);
+/// for x in vec![1., 0., 4., -3.].into_iter(){
+///     running_gmean.update(x);
+/// }
+/// assert_eq!(running_gmean.get(), 2.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GeometricMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub sum_log: F,
+    pub n: Count<F>,
+    pub skip_non_positive: bool,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> GeometricMean<F> {
+    pub fn new(skip_non_positive: bool) -> Self {
+        Self {
+            sum_log: F::from_f64(0.).unwrap(),
+            n: Count::new(),
+            skip_non_positive,
+        }
+    }
+    // Returns `false` if `x` should be skipped
+    fn accept(&self, x: F) -> bool {
+        if x > F::from_f64(0.).unwrap() {
+            return true;
+        }
+        if !self.skip_non_positive {
+            panic!("GeometricMean only accepts positive values");
+        }
+        false
+    }
+}
+
+impl<F> Default for GeometricMean<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for GeometricMean<F> {
+    fn update(&mut self, x: F) {
+        if self.accept(x) {
+            self.n.update(x);
+            self.sum_log += x.ln();
+        }
+    }
+    fn get(&self) -> F {
+        let n = self.n.get();
+        if n == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        (self.sum_log / n).exp()
+    }
+    fn n(&self) -> usize {
+        self.n.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for GeometricMean<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        // Same check as `accept`, `NaN` is skipped on both ends
+        if x.is_nan() || x <= F::from_f64(0.).unwrap() {
+            if self.skip_non_positive {
+                return Ok(());
+            }
+            return Err("GeometricMean only accepts positive values");
+        }
+        self.n.revert(x)?;
+        if self.n.get() == F::from_f64(0.).unwrap() {
+            self.sum_log = F::from_f64(0.).unwrap();
+        } else {
+            self.sum_log -= x.ln();
+        }
+        Ok(())
+    }
+}
+
+/// Rolling geometric mean, `exp(mean(ln(x)))` over the window.
+///
+/// The sum of the logarithms of the window is maintained with compensated summation, the
+/// logarithm of a value leaving the window is subtracted from it. This is the average growth
+/// factor of compounding returns, e.g. `1.05` for a `5%` return. The geometric mean is only
+/// defined for positive values, `update` panics on values lower or equal to `0`, as well as on
+/// infinite and `NaN` values which would poison the sum, before they enter the window. `get`
+/// returns `0` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::gmean::RollingGeometricMean;
+/// use watermill::stats::Univariate;
+/// let growth_factors: Vec<f64> = vec![1.1, 0.9, 1.2, 1.05];
+/// let mut rolling_gmean: RollingGeometricMean<f64> = RollingGeometricMean::new(3).unwrap();
+/// for x in growth_factors.into_iter(){
+///     rolling_gmean.update(x);
+/// }
+/// assert!((rolling_gmean.get() - (0.9_f64 * 1.2 * 1.05).cbrt()).abs() < 1e-12);
+/// ```
+/// # Panics
+/// `update` panics on values that are not positive and finite.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingGeometricMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sum_log: NeumaierSum<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingGeometricMean<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            sum_log: NeumaierSum::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingGeometricMean<F> {
+    fn update(&mut self, x: F) {
+        if !(x > F::from_f64(0.).unwrap() && x.is_finite()) {
+            panic!("RollingGeometricMean only accepts positive values");
+        }
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.sum_log.add(-oldest.ln());
+        }
+        self.window.push_back(x);
+        self.sum_log.add(x.ln());
+    }
+    fn get(&self) -> F {
+        if self.window.is_empty() {
+            return F::from_f64(0.).unwrap();
+        }
+        (self.sum_log.value() / F::from_usize(self.window.len()).unwrap()).exp()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_skips_nan() {
+        let mut running_gmean: GeometricMean<f64> = GeometricMean::new(true);
+        let mut rolling_gmean = Rolling::new(&mut running_gmean, 2).unwrap();
+        for x in [2., f64::NAN, 8., 4.] {
+            rolling_gmean.update(x);
+        }
+        assert!((rolling_gmean.get() - 32_f64.sqrt()).abs() < 1e-12);
+    }
+    #[test]
+    #[should_panic(expected = "GeometricMean only accepts positive values")]
+    fn panic_on_non_positive() {
+        let mut running_gmean: GeometricMean<f64> = GeometricMean::default();
+        running_gmean.update(0.);
+    }
+    #[test]
+    fn rolling_gmean() {
+        let data: Vec<f64> = vec![1., 2., 4., 8., 16.];
+        let mut running_gmean: GeometricMean<f64> = GeometricMean::default();
+        let mut rolling_gmean = Rolling::new(&mut running_gmean, 2).unwrap();
+        for x in data.into_iter() {
+            rolling_gmean.update(x);
+        }
+        assert!((rolling_gmean.get() - 128_f64.sqrt()).abs() < 1e-12);
+    }
+    #[test]
+    fn rolling_gmean_matches_window() {
+        let mut state: u64 = 21;
+        let data: Vec<f64> = (0..500)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                0.8 + 0.4 * ((state >> 11) as f64 / (1u64 << 53) as f64)
+            })
+            .collect();
+        let mut rolling_gmean: RollingGeometricMean<f64> = RollingGeometricMean::new(20).unwrap();
+        assert_eq!(rolling_gmean.get(), 0.);
+        for (i, x) in data.iter().enumerate() {
+            rolling_gmean.update(*x);
+            let window = &data[(i + 1).saturating_sub(20)..=i];
+            let expected = window
+                .iter()
+                .product::<f64>()
+                .powf(1. / window.len() as f64);
+            assert!((rolling_gmean.get() - expected).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn rolling_gmean_evicts_extremes() {
+        let mut rolling_gmean: RollingGeometricMean<f64> = RollingGeometricMean::new(2).unwrap();
+        for x in [1000., 1e-3, 2., 8.] {
+            rolling_gmean.update(x);
+        }
+        // Only 2 and 8 are left
+        assert!((rolling_gmean.get() - 4.).abs() < 1e-12);
+        assert_eq!(rolling_gmean.n(), 2);
+        assert!(RollingGeometricMean::<f64>::new(0).is_err());
+    }
+    #[test]
+    #[should_panic(expected = "RollingGeometricMean only accepts positive values")]
+    fn rolling_gmean_panics_on_non_positive() {
+        let mut rolling_gmean: RollingGeometricMean<f64> = RollingGeometricMean::new(2).unwrap();
+        rolling_gmean.update(-1.);
+    }
+}
@@ -0,0 +1,305 @@
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Mergeable, Univariate};
+use serde::{Deserialize, Serialize};
+
+/// Streaming histogram with a bounded number of adaptive bins.
+///
+/// Each bin is a centroid and the number of values it holds. When a new value would exceed
+/// `max_bins`, the two closest bins are merged, so bins are denser where the values are.
+/// `get` returns the mean estimated from the bins, use `bins`, `cdf` and `quantile` to inspect the distribution.
+/// # Arguments
+/// * `max_bins` - Maximum number of bins, must be greater than 0. Defaults to `256`.
+/// # Examples
+/// ```
+/// use watermill::histogram::Histogram;
+/// use watermill::stats::Univariate;
+/// let mut histogram: Histogram<f64> = Histogram::new(8).unwrap();
+/// for i in 0..1000{
+///     histogram.update((i % 100) as f64);
+/// }
+/// assert_eq!(histogram.bins().len(), 8);
+/// assert!((histogram.get() - 49.5).abs() < 1e-9);
+/// assert!((histogram.cdf(25.) - 0.25).abs() < 0.02);
+/// ```
+/// # References
+/// [^1]: [Ben-Haim, Y. and Tom-Tov, E., 2010. A streaming parallel decision tree algorithm. Journal of Machine Learning Research, 11(2).](https://www.jmlr.org/papers/volume11/ben-haim10a/ben-haim10a.pdf)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Histogram<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    max_bins: usize,
+    bins: Vec<(F, usize)>,
+    count: usize,
+    min: F,
+    max: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Histogram<F> {
+    pub fn new(max_bins: usize) -> Result<Self, &'static str> {
+        if max_bins == 0 {
+            return Err("max_bins should not equal to 0");
+        }
+        Ok(Self {
+            max_bins,
+            bins: Vec::with_capacity(max_bins + 1),
+            count: 0,
+            min: F::infinity(),
+            max: F::neg_infinity(),
+        })
+    }
+    /// Centroids of the bins in increasing order, with the number of values they hold.
+    pub fn bins(&self) -> Vec<(F, usize)> {
+        self.bins.clone()
+    }
+    /// Number of values seen.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    /// Estimated fraction of values lower or equal to `x`. Returns `NaN` if no value was seen.
+    pub fn cdf(&self, x: F) -> F {
+        if self.count == 0 {
+            return F::nan();
+        }
+        if x < self.min {
+            return F::from_f64(0.).unwrap();
+        }
+        if x >= self.max {
+            return F::from_f64(1.).unwrap();
+        }
+        let half = F::from_f64(0.5).unwrap();
+        let n = F::from_usize(self.count).unwrap();
+        let (first, first_count) = self.bins[0];
+        if x < first {
+            // Half of the first bin lies between the minimum and its centroid
+            let first_count = F::from_usize(first_count).unwrap();
+            return first_count * half * (x - self.min) / (first - self.min) / n;
+        }
+        let mut below = F::from_f64(0.).unwrap();
+        for window in self.bins.windows(2) {
+            let ((left, left_count), (right, right_count)) = (window[0], window[1]);
+            let (left_count, right_count) = (
+                F::from_usize(left_count).unwrap(),
+                F::from_usize(right_count).unwrap(),
+            );
+            if x < right {
+                let z = (x - left) / (right - left);
+                let count_at_x = left_count + (right_count - left_count) * z;
+                return (below + left_count * half + (left_count + count_at_x) * half * z) / n;
+            }
+            below += left_count;
+        }
+        let (last, last_count) = self.bins[self.bins.len() - 1];
+        let last_count = F::from_usize(last_count).unwrap();
+        (below + last_count * half + last_count * half * (x - last) / (self.max - last)) / n
+    }
+    /// Estimated `q` quantile, `q` is clamped to `[0, 1]`. It is the inverse of `cdf`.
+    /// Returns `NaN` if no value was seen.
+    pub fn quantile(&self, q: F) -> F {
+        if self.count == 0 {
+            return F::nan();
+        }
+        let zero = F::from_f64(0.).unwrap();
+        let half = F::from_f64(0.5).unwrap();
+        let target = q.max(zero).min(F::from_f64(1.).unwrap()) * F::from_usize(self.count).unwrap();
+        let (first, first_count) = self.bins[0];
+        let first_count = F::from_usize(first_count).unwrap();
+        if target < first_count * half {
+            return self.min + (first - self.min) * target / (first_count * half);
+        }
+        let mut below = zero;
+        for window in self.bins.windows(2) {
+            let ((left, left_count), (right, right_count)) = (window[0], window[1]);
+            let (left_count, right_count) = (
+                F::from_usize(left_count).unwrap(),
+                F::from_usize(right_count).unwrap(),
+            );
+            let remaining = target - below - left_count * half;
+            if remaining < (left_count + right_count) * half {
+                // Solve the quadratic used by `cdf` on this segment for its fraction `z`
+                let a = (right_count - left_count) * half;
+                let z = if a == zero {
+                    remaining / left_count
+                } else {
+                    let b = left_count;
+                    (-b + (b * b + F::from_f64(4.).unwrap() * a * remaining).sqrt())
+                        / (F::from_f64(2.).unwrap() * a)
+                };
+                return left + (right - left) * z;
+            }
+            below += left_count;
+        }
+        let (last, last_count) = self.bins[self.bins.len() - 1];
+        let last_count = F::from_usize(last_count).unwrap();
+        let remaining = target - below - last_count * half;
+        if remaining <= zero {
+            return last;
+        }
+        (last + (self.max - last) * remaining / (last_count * half)).min(self.max)
+    }
+    fn insert(&mut self, x: F, count: usize) {
+        let position = self.bins.partition_point(|(centroid, _)| *centroid < x);
+        match self.bins.get_mut(position) {
+            Some((centroid, bin_count)) if *centroid == x => *bin_count += count,
+            _ => self.bins.insert(position, (x, count)),
+        }
+    }
+    fn shrink(&mut self) {
+        while self.bins.len() > self.max_bins {
+            let closest = (0..self.bins.len() - 1)
+                .min_by(|&i, &j| {
+                    let gap_i = self.bins[i + 1].0 - self.bins[i].0;
+                    let gap_j = self.bins[j + 1].0 - self.bins[j].0;
+                    gap_i.partial_cmp(&gap_j).unwrap()
+                })
+                .unwrap();
+            let (right, right_count) = self.bins.remove(closest + 1);
+            let (left, left_count) = self.bins[closest];
+            let total = left_count + right_count;
+            let centroid = left
+                + (right - left) * F::from_usize(right_count).unwrap()
+                    / F::from_usize(total).unwrap();
+            self.bins[closest] = (centroid, total);
+        }
+    }
+}
+
+impl<F> Default for Histogram<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(256).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Histogram<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("Histogram does not accept NaN values");
+        }
+        self.insert(x, 1);
+        self.count += 1;
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.shrink();
+    }
+    fn get(&self) -> F {
+        if self.count == 0 {
+            return F::nan();
+        }
+        let mut sum = F::from_f64(0.).unwrap();
+        for (centroid, count) in self.bins.iter() {
+            sum += *centroid * F::from_usize(*count).unwrap();
+        }
+        sum / F::from_usize(self.count).unwrap()
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.count
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Histogram<F> {
+    /// The `max_bins` of `self` is kept.
+    fn merge(&mut self, other: &Self) {
+        for (centroid, count) in other.bins.iter() {
+            self.insert(*centroid, *count);
+        }
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.shrink();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Exponentially distributed values, the right tail is long
+    fn exponential_sample(n: usize) -> Vec<f64> {
+        let mut state: u64 = 7;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+                -u.ln()
+            })
+            .collect()
+    }
+    fn rank_error(sorted: &[f64], value: f64, q: f64) -> f64 {
+        let rank = sorted.partition_point(|x| *x <= value) as f64;
+        (rank / sorted.len() as f64 - q).abs()
+    }
+    #[test]
+    fn median_of_exponential() {
+        let data = exponential_sample(50_000);
+        let mut histogram: Histogram<f64> = Histogram::new(64).unwrap();
+        for x in data.iter() {
+            histogram.update(*x);
+        }
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median = histogram.quantile(0.5);
+        assert!(rank_error(&sorted, median, 0.5) < 0.01);
+        assert!((median - sorted[25_000]).abs() < 0.02);
+        assert!((histogram.cdf(median) - 0.5).abs() < 1e-9);
+        let mean = data.iter().sum::<f64>() / data.len() as f64;
+        assert!((histogram.get() - mean).abs() < 1e-9);
+        let bins = histogram.bins();
+        assert_eq!(bins.len(), 64);
+        assert_eq!(bins.iter().map(|(_, count)| count).sum::<usize>(), 50_000);
+        assert!(bins.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+    #[test]
+    fn cdf_bounds() {
+        let mut histogram: Histogram<f64> = Histogram::new(4).unwrap();
+        for x in [3., 1., 4., 1., 5., 9., 2., 6.] {
+            histogram.update(x);
+        }
+        assert_eq!(histogram.cdf(0.), 0.);
+        assert_eq!(histogram.cdf(9.), 1.);
+        assert_eq!(histogram.quantile(0.), 1.);
+        assert_eq!(histogram.quantile(1.), 9.);
+        let mut previous = 0.;
+        for i in 0..=90 {
+            let cdf = histogram.cdf(i as f64 / 10.);
+            assert!(cdf >= previous);
+            previous = cdf;
+        }
+    }
+    #[test]
+    fn merge() {
+        let data = exponential_sample(20_000);
+        let mut left: Histogram<f64> = Histogram::new(64).unwrap();
+        let mut right: Histogram<f64> = Histogram::new(64).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            if i % 2 == 0 {
+                left.update(*x);
+            } else {
+                right.update(*x);
+            }
+        }
+        left.merge(&right);
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(left.count(), 20_000);
+        assert_eq!(left.bins().len(), 64);
+        assert!(rank_error(&sorted, left.quantile(0.5), 0.5) < 0.01);
+    }
+    #[test]
+    fn empty_and_invalid() {
+        let histogram: Histogram<f64> = Histogram::default();
+        assert!(histogram.get().is_nan());
+        assert!(histogram.cdf(0.).is_nan());
+        assert_eq!(histogram.try_get(), None);
+        assert!(Histogram::<f64>::new(0).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use alloc::{vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+use num::Float;
+
+use crate::stats::Mergeable;
+use serde::{Deserialize, Serialize};
+
+// FNV-1a followed by the splitmix64 finalizer, `core` has no hasher and FNV alone mixes the
+// high bits poorly
+struct RegisterHasher(u64);
+
+impl RegisterHasher {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for RegisterHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+    fn finish(&self) -> u64 {
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Approximate count of distinct values.
+///
+/// Values are hashed, the `precision` first bits of the hash select a register which keeps the
+/// longest run of leading zeros seen in the remaining bits. The relative standard error is about
+/// `1.04 / sqrt(2^precision)` and the memory used is `2^precision` bytes.
+/// Floats do not implement `Hash`, update with their bits instead.
+/// # Arguments
+/// * `precision` - Number of bits used to select a register, must be between 4 and 18. Defaults to `14`.
+/// # Examples
+/// ```
+/// use watermill::hll::HyperLogLog;
+/// let mut distinct: HyperLogLog = HyperLogLog::new(14).unwrap();
+/// for i in 0..10000_u64{
+///     distinct.update(&(i % 1000));
+/// }
+/// assert!((distinct.count() - 1000.).abs() < 20.);
+/// distinct.update(&3.5_f64.to_bits());
+/// ```
+/// # References
+/// [^1]: [Flajolet, P., Fusy, É., Gandouet, O. and Meunier, F., 2007. Hyperloglog: the analysis of a near-optimal cardinality estimation algorithm. Discrete Mathematics and Theoretical Computer Science, pp.137-156.](https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    precision: u32,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub fn new(precision: u32) -> Result<Self, &'static str> {
+        if !(4..=18).contains(&precision) {
+            return Err("precision should be between 4 and 18");
+        }
+        Ok(Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+    pub fn update<T: Hash + ?Sized>(&mut self, value: &T) {
+        let mut hasher = RegisterHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The guard bit bounds the rank when all the remaining bits are zeros
+        let remaining = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+    /// Estimated number of distinct values.
+    pub fn count(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1. + 1.079 / m),
+        };
+        let mut inverse_sum = 0.;
+        let mut zeros = 0;
+        for register in self.registers.iter() {
+            inverse_sum += 1. / (1_u64 << register) as f64;
+            if *register == 0 {
+                zeros += 1;
+            }
+        }
+        let estimate = alpha * m * m / inverse_sum;
+        // Linear counting is more accurate while many registers are still empty
+        if estimate <= 2.5 * m && zeros > 0 {
+            return m * Float::ln(m / zeros as f64);
+        }
+        estimate
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new(14).unwrap()
+    }
+}
+
+impl Mergeable for HyperLogLog {
+    /// # Panics
+    /// Panics if both sketches do not have the same precision.
+    fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "HyperLogLog sketches should have the same precision"
+        );
+        for (register, other_register) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other_register);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn relative_error(estimate: f64, truth: f64) -> f64 {
+        (estimate - truth).abs() / truth
+    }
+    #[test]
+    fn ten_thousand_distinct() {
+        let mut hll = HyperLogLog::default();
+        for i in 0..10_000_u64 {
+            hll.update(&i);
+            // Duplicates do not change the estimate
+            hll.update(&(i / 2));
+        }
+        assert!(relative_error(hll.count(), 10_000.) < 0.03);
+    }
+    #[test]
+    fn million_distinct() {
+        let mut hll = HyperLogLog::default();
+        for i in 0..1_000_000_u64 {
+            hll.update(&i);
+        }
+        assert!(relative_error(hll.count(), 1_000_000.) < 0.03);
+    }
+    #[test]
+    fn small_cardinalities() {
+        let mut hll = HyperLogLog::default();
+        assert_eq!(hll.count(), 0.);
+        for i in 0..100_u64 {
+            hll.update(&i);
+        }
+        assert!((hll.count() - 100.).abs() < 2.);
+        hll.update("a string");
+        assert!((hll.count() - 101.).abs() < 2.);
+    }
+    #[test]
+    fn merge() {
+        let mut left = HyperLogLog::default();
+        let mut right = HyperLogLog::default();
+        let mut union = HyperLogLog::default();
+        for i in 0..60_000_u64 {
+            if i < 40_000 {
+                left.update(&i);
+            }
+            if i >= 20_000 {
+                right.update(&i);
+            }
+            union.update(&i);
+        }
+        left.merge(&right);
+        assert_eq!(left.count(), union.count());
+        assert!(relative_error(left.count(), 60_000.) < 0.03);
+    }
+    #[test]
+    fn invalid_precision() {
+        assert!(HyperLogLog::new(3).is_err());
+        assert!(HyperLogLog::new(19).is_err());
+    }
+}
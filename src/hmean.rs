@@ -0,0 +1,230 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::count::Count;
+use crate::stats::{Revertable, Univariate};
+use crate::utils::NeumaierSum;
+use serde::{Deserialize, Serialize};
+/// Running harmonic mean.
+///
+/// The harmonic mean is only defined for positive values, it is computed as `n / sum(1 / x)`.
+/// # Arguments
+/// * `skip_non_positive` - If `true`, `NaN` and values lower or equal to `0` are ignored by `update` and
+///   `revert`, otherwise they make `update` panic. Defaults to `false`.
+/// # Examples
+/// ```
+/// use watermill::hmean::HarmonicMean;
+/// use watermill::stats::{Univariate, Revertable};
+/// let mut running_hmean: HarmonicMean<f64> = HarmonicMean::default();
+/// for x in vec![1., 2., 4.].into_iter(){
+///     running_hmean.update(x);
+/// }
+/// assert!((running_hmean.get() - 12. / 7.).abs() < 1e-12);
+///
+/// // You can revert the harmonic mean
+/// running_hmean.revert(4.);
+/// assert!((running_hmean.get() - 4. / 3.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct HarmonicMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub sum_recip: F,
+    pub n: Count<F>,
+    pub skip_non_positive: bool,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> HarmonicMean<F> {
+    pub fn new(skip_non_positive: bool) -> Self {
+        Self {
+            sum_recip: F::from_f64(0.).unwrap(),
+            n: Count::new(),
+            skip_non_positive,
+        }
+    }
+    // Returns `false` if `x` should be skipped
+    fn accept(&self, x: F) -> bool {
+        if x > F::from_f64(0.).unwrap() {
+            return true;
+        }
+        if !self.skip_non_positive {
+            panic!("HarmonicMean only accepts positive values");
+        }
+        false
+    }
+}
+
+impl<F> Default for HarmonicMean<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for HarmonicMean<F> {
+    fn update(&mut self, x: F) {
+        if self.accept(x) {
+            self.n.update(x);
+            self.sum_recip += x.recip();
+        }
+    }
+    fn get(&self) -> F {
+        if self.n.get() == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        self.n.get() / self.sum_recip
+    }
+    fn n(&self) -> usize {
+        self.n.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for HarmonicMean<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        // Same check as `accept`, `NaN` is skipped on both ends
+        if x.is_nan() || x <= F::from_f64(0.).unwrap() {
+            if self.skip_non_positive {
+                return Ok(());
+            }
+            return Err("HarmonicMean only accepts positive values");
+        }
+        self.n.revert(x)?;
+        if self.n.get() == F::from_f64(0.).unwrap() {
+            self.sum_recip = F::from_f64(0.).unwrap();
+        } else {
+            self.sum_recip -= x.recip();
+        }
+        Ok(())
+    }
+}
+
+/// Rolling harmonic mean, `n / sum(1 / x)` over the window.
+///
+/// Averages rates measured over equal amounts of work, e.g. speeds over equal distances.
+/// `get` returns `0` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::hmean::RollingHarmonicMean;
+/// use watermill::stats::Univariate;
+/// // Speeds in km/h over equal distances
+/// let speeds: Vec<f64> = vec![30., 60., 20., 40.];
+/// let mut rolling_hmean: RollingHarmonicMean<f64> = RollingHarmonicMean::new(3).unwrap();
+/// for x in speeds.into_iter(){
+///     rolling_hmean.update(x);
+/// }
+/// assert!((rolling_hmean.get() - 3. / (1. / 60. + 1. / 20. + 1. / 40.)).abs() < 1e-12);
+/// ```
+/// # Panics
+/// `update` panics on values that are not positive and finite.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingHarmonicMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sum_recip: NeumaierSum<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingHarmonicMean<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            sum_recip: NeumaierSum::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingHarmonicMean<F> {
+    fn update(&mut self, x: F) {
+        if !(x > F::from_f64(0.).unwrap() && x.is_finite()) {
+            panic!("RollingHarmonicMean only accepts positive values");
+        }
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.sum_recip.add(-oldest.recip());
+        }
+        self.window.push_back(x);
+        self.sum_recip.add(x.recip());
+    }
+    fn get(&self) -> F {
+        if self.window.is_empty() {
+            return F::from_f64(0.).unwrap();
+        }
+        F::from_usize(self.window.len()).unwrap() / self.sum_recip.value()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_skips_nan() {
+        let mut running_hmean: HarmonicMean<f64> = HarmonicMean::new(true);
+        let mut rolling_hmean = Rolling::new(&mut running_hmean, 2).unwrap();
+        for x in [2., f64::NAN, 8., 4.] {
+            rolling_hmean.update(x);
+        }
+        assert!((rolling_hmean.get() - 16. / 3.).abs() < 1e-12);
+    }
+    #[test]
+    #[should_panic(expected = "HarmonicMean only accepts positive values")]
+    fn panic_on_zero() {
+        let mut running_hmean: HarmonicMean<f64> = HarmonicMean::default();
+        running_hmean.update(0.);
+    }
+    #[test]
+    fn skip_zero() {
+        let mut running_hmean: HarmonicMean<f64> = HarmonicMean::new(true);
+        for x in vec![1., 0., 2., 4.].into_iter() {
+            running_hmean.update(x);
+        }
+        assert!((running_hmean.get() - 1.7142857142857142).abs() < 1e-12);
+        assert!(running_hmean.revert(0.).is_ok());
+    }
+    #[test]
+    fn rolling_hmean_matches_window() {
+        let mut state: u64 = 34;
+        let data: Vec<f64> = (0..500)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                1. + 99. * ((state >> 11) as f64 / (1u64 << 53) as f64)
+            })
+            .collect();
+        let mut rolling_hmean: RollingHarmonicMean<f64> = RollingHarmonicMean::new(25).unwrap();
+        assert_eq!(rolling_hmean.get(), 0.);
+        for (i, x) in data.iter().enumerate() {
+            rolling_hmean.update(*x);
+            let window = &data[(i + 1).saturating_sub(25)..=i];
+            let expected = window.len() as f64 / window.iter().map(|x| 1. / x).sum::<f64>();
+            assert!((rolling_hmean.get() - expected).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn rolling_hmean_evicts_extremes() {
+        let mut rolling_hmean: RollingHarmonicMean<f64> = RollingHarmonicMean::new(2).unwrap();
+        for x in [1e-6, 1e6, 3., 6.] {
+            rolling_hmean.update(x);
+        }
+        // Only 3 and 6 are left
+        assert!((rolling_hmean.get() - 4.).abs() < 1e-12);
+        assert_eq!(rolling_hmean.n(), 2);
+        assert!(RollingHarmonicMean::<f64>::new(0).is_err());
+    }
+    #[test]
+    #[should_panic(expected = "RollingHarmonicMean only accepts positive values")]
+    fn rolling_hmean_panics_on_zero() {
+        let mut rolling_hmean: RollingHarmonicMean<f64> = RollingHarmonicMean::new(2).unwrap();
+        rolling_hmean.update(1.);
+        rolling_hmean.update(0.);
+    }
+}
@@ -1,10 +1,10 @@
-use crate::quantile::Quantile;
+use crate::quantile::{interpolate, interpolation, Quantile};
 use crate::sorted_window::SortedWindow;
 
 use crate::stats::Univariate;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 /// Computes the interquartile range.
 /// # Arguments
 /// * `q_inf` - Desired inferior quantile, must be between 0 and 1. Defaults to `0.25`.
@@ -33,8 +33,8 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> IQR<F> {
         }
 
         Ok(Self {
-            q_inf: Quantile::new(q_inf).unwrap(),
-            q_sup: Quantile::new(q_sup).unwrap(),
+            q_inf: Quantile::new(q_inf)?,
+            q_sup: Quantile::new(q_sup)?,
         })
     }
 }
@@ -59,6 +59,12 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for IQR<F>
     fn get(&self) -> F {
         self.q_sup.get() - self.q_inf.get()
     }
+    fn try_get(&self) -> Option<F> {
+        Some(self.q_sup.try_get()? - self.q_inf.try_get()?)
+    }
+    fn n(&self) -> usize {
+        self.q_inf.n()
+    }
 }
 
 /// Rolling interquartile range.
@@ -78,9 +84,9 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for IQR<F>
 /// }
 /// assert_eq!(rolling_iqr.get(), 50.0);
 /// ```
-///
+/// Both quantiles share a single sorted window, so they always cover the same values.
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RollingIQR<F: Float + FromPrimitive + AddAssign + SubAssign> {
     sorted_window: SortedWindow<F>,
     q_inf: F,
@@ -96,36 +102,22 @@ pub struct RollingIQR<F: Float + FromPrimitive + AddAssign + SubAssign> {
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingIQR<F> {
     pub fn new(q_inf: F, q_sup: F, window_size: usize) -> Result<Self, &'static str> {
-        if F::from_f64(0.).unwrap() > q_inf && F::from_f64(1.).unwrap() < q_inf {
-            return Err("q_inf should be betweek 0 and 1");
+        if !(q_inf >= F::from_f64(0.).unwrap() && q_inf <= F::from_f64(1.).unwrap()) {
+            return Err("q_inf should be between 0 and 1");
         }
 
-        if F::from_f64(0.).unwrap() > q_sup && F::from_f64(1.).unwrap() < q_sup {
-            return Err("q_sup should be betweek 0 and 1");
+        if !(q_sup >= F::from_f64(0.).unwrap() && q_sup <= F::from_f64(1.).unwrap()) {
+            return Err("q_sup should be between 0 and 1");
         }
         if q_inf >= q_sup {
             return Err("q_inf must be strictly less than q_sup");
         }
+        let sorted_window = SortedWindow::new(window_size)?;
 
-        let idx_inf = q_inf * (F::from_usize(window_size).unwrap() - F::from_f64(1.).unwrap());
-        let lower_inf = idx_inf.floor().to_usize().unwrap();
-        let mut higher_inf = lower_inf + 1;
-        if higher_inf > window_size - 1 {
-            higher_inf = lower_inf.saturating_sub(1); // Avoid attempt to subtract with overflow
-        }
-
-        let frac_inf = idx_inf - F::from_usize(lower_inf).unwrap();
-
-        let idx_sup = q_sup * (F::from_usize(window_size).unwrap() - F::from_f64(1.).unwrap());
-        let lower_sup = idx_sup.floor().to_usize().unwrap();
-        let mut higher_sup = lower_sup + 1;
-        if higher_sup > window_size - 1 {
-            higher_sup = lower_sup.saturating_sub(1); // Avoid attempt to subtract with overflow
-        }
-
-        let frac_sup = idx_sup - F::from_usize(lower_sup).unwrap();
+        let (lower_inf, higher_inf, frac_inf) = interpolation(q_inf, window_size);
+        let (lower_sup, higher_sup, frac_sup) = interpolation(q_sup, window_size);
         Ok(Self {
-            sorted_window: SortedWindow::new(window_size),
+            sorted_window,
             q_inf,
             q_sup,
             window_size,
@@ -139,16 +131,7 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingIQR<F> {
     }
     fn prepare(&self, q: F, is_inf: bool) -> (usize, usize, F) {
         if self.sorted_window.len() < self.window_size {
-            let idx =
-                q * (F::from_usize(self.sorted_window.len()).unwrap() - F::from_f64(1.).unwrap());
-            let lower = idx.floor().to_usize().unwrap();
-            let mut higher = lower + 1;
-            if higher > self.sorted_window.len() - 1 {
-                higher = self.sorted_window.len().saturating_sub(1); // Avoid attempt to subtract with overflow
-            }
-
-            let frac = idx - F::from_usize(lower).unwrap();
-            return (lower, higher, frac);
+            return interpolation(q, self.sorted_window.len());
         }
         if is_inf {
             return (self.lower_inf, self.higher_inf, self.frac_inf);
@@ -162,19 +145,36 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
         self.sorted_window.push_back(x);
     }
     fn get(&self) -> F {
-        let (lower_inf, higher_inf, frac_inf) = self.prepare(self.q_inf, true);
-        let (lower_sup, higher_sup, frac_sup) = self.prepare(self.q_sup, false);
-
-        let quantile_inf = self.sorted_window[lower_inf]
-            + (self.sorted_window[higher_inf] - self.sorted_window[lower_inf]) * frac_inf;
-        let quantile_sup = self.sorted_window[lower_sup]
-            + (self.sorted_window[higher_sup] - self.sorted_window[lower_sup]) * frac_sup;
+        let quantile_inf = interpolate(&self.sorted_window, self.prepare(self.q_inf, true));
+        let quantile_sup = interpolate(&self.sorted_window, self.prepare(self.q_sup, false));
 
         quantile_sup - quantile_inf
     }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
 }
 #[cfg(test)]
 mod test {
+    #[test]
+    fn try_get_empty() {
+        use crate::iqr::{RollingIQR, IQR};
+        use crate::stats::Univariate;
+        let mut iqr: IQR<f64> = IQR::default();
+        let mut rolling_iqr: RollingIQR<f64> = RollingIQR::new(0.25_f64, 0.75_f64, 3).unwrap();
+        assert_eq!(iqr.try_get(), None);
+        assert_eq!(rolling_iqr.try_get(), None);
+        iqr.update(1.);
+        rolling_iqr.update(1.);
+        assert_eq!(iqr.try_get(), Some(0.));
+        assert_eq!(rolling_iqr.try_get(), Some(0.));
+    }
     #[test]
     fn rolling_iqr_edge_case() {
         use crate::iqr::RollingIQR;
@@ -187,4 +187,33 @@ mod test {
         }
         assert_eq!(rolling_iqr.get(), 0.0);
     }
+    #[test]
+    fn rolling_iqr_matches_quantiles() {
+        use crate::iqr::RollingIQR;
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4., 10., 0.];
+        let mut rolling_iqr: RollingIQR<f64> = RollingIQR::new(0.1_f64, 0.9_f64, 5).unwrap();
+        let mut rolling_inf: RollingQuantile<f64> = RollingQuantile::new(0.1_f64, 5).unwrap();
+        let mut rolling_sup: RollingQuantile<f64> = RollingQuantile::new(0.9_f64, 5).unwrap();
+        for x in data.into_iter() {
+            rolling_iqr.update(x);
+            rolling_inf.update(x);
+            rolling_sup.update(x);
+            assert_eq!(rolling_iqr.get(), rolling_sup.get() - rolling_inf.get());
+        }
+    }
+    #[test]
+    fn out_of_range() {
+        use crate::iqr::{RollingIQR, IQR};
+        assert!(IQR::new(-0.5_f64, 0.5_f64).is_err());
+        assert!(IQR::new(0.5_f64, 1.5_f64).is_err());
+        assert!(RollingIQR::new(-0.5_f64, 0.5_f64, 10).is_err());
+        assert!(RollingIQR::new(0.5_f64, 1.5_f64, 10).is_err());
+        assert!(RollingIQR::new(0.75_f64, 0.25_f64, 10).is_err());
+        assert!(RollingIQR::new(0.25_f64, 0.75_f64, 0).is_err());
+        assert!(IQR::new(f64::NAN, 0.75_f64).is_err());
+        assert!(RollingIQR::new(f64::NAN, 0.75_f64, 10).is_err());
+        assert!(RollingIQR::new(0.25_f64, f64::NAN, 10).is_err());
+    }
 }
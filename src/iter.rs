@@ -1,5 +1,5 @@
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::count::Count;
 use crate::ewmean::EWMean;
@@ -45,6 +45,29 @@ where
 }
 
 pub trait IterStatisticsExtend: Iterator {
+    /// Running value of any `Univariate` statistic, yielded after each update.
+    /// # Arguments
+    /// * `stat` - The statistic to update, usually freshly constructed.
+    /// # Examples
+    ///
+    /// ```
+    /// use watermill::iter::IterStatisticsExtend;
+    /// use watermill::mean::Mean;
+    /// let data: Vec<f64> = vec![1., 2., 3.];
+    /// let running_mean: Vec<f64> = data.into_iter().online(Mean::default()).collect();
+    /// assert_eq!(running_mean, vec![1., 1.5, 2.]);
+    /// ```
+    fn online<U>(self, stat: U) -> IterStat<U, Self>
+    where
+        U: Univariate<Self::Item>,
+        Self::Item: Float + FromPrimitive + AddAssign + SubAssign,
+        Self: Sized,
+    {
+        IterStat {
+            stat,
+            underlying: self,
+        }
+    }
     /// Running sum.
     /// # Examples
     ///
@@ -114,7 +137,7 @@ pub trait IterStatisticsExtend: Iterator {
 
     /// Running exponentially weighted mean.
     /// # Arguments
-    /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+    /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Must be in `(0, 1]`.
     /// # Examples
     ///
     /// ```
@@ -132,13 +155,13 @@ pub trait IterStatisticsExtend: Iterator {
         Self: Sized,
     {
         IterStat {
-            stat: EWMean::new(alpha),
+            stat: EWMean::new(alpha).expect("alpha should be in (0, 1]"),
             underlying: self,
         }
     }
     /// Running exponentially weighted variance.
     /// # Arguments
-    /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Default value is `0.5`.
+    /// * `alpha` - The closer `alpha` is to 1 the more the statistic will adapt to recent values. Must be in `(0, 1]`.
     /// # Examples
     ///
     /// ```
@@ -156,7 +179,7 @@ pub trait IterStatisticsExtend: Iterator {
         Self: Sized,
     {
         IterStat {
-            stat: EWVariance::new(alpha),
+            stat: EWVariance::new(alpha).expect("alpha should be in (0, 1]"),
             underlying: self,
         }
     }
@@ -373,4 +396,36 @@ pub trait IterStatisticsExtend: Iterator {
         }
     }
 }
-impl<I: Iterator> IterStatisticsExtend for I {}
\ No newline at end of file
+impl<I: Iterator> IterStatisticsExtend for I {}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn online_matches_manual_stepping() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut manual: Variance<f64> = Variance::default();
+        let expected: Vec<f64> = data
+            .iter()
+            .map(|x| {
+                manual.update(*x);
+                manual.get()
+            })
+            .collect();
+        let online: Vec<f64> = data.into_iter().online(Variance::default()).collect();
+        assert_eq!(online, expected);
+    }
+    #[test]
+    fn online_rolling() {
+        let data: Vec<f64> = vec![9., 7., 3., 2.];
+        let mut sum: Sum<f64> = Sum::new();
+        let rolling_sum = Rolling::new(&mut sum, 2).unwrap();
+        let online: Vec<f64> = data.into_iter().online(rolling_sum).collect();
+        assert_eq!(online, vec![9., 16., 10., 5.]);
+    }
+    #[test]
+    fn online_empty() {
+        let data: Vec<f64> = Vec::new();
+        assert_eq!(data.into_iter().online(Mean::default()).count(), 0);
+    }
+}
@@ -1,8 +1,9 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::moments::CentralMoments;
-use crate::stats::Univariate;
+use crate::stats::{Revertable, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running Kurtosis.
 /// # Arguments
@@ -86,4 +87,178 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Kurtosi
         }
         kurtosis - F::from_f64(3.).unwrap()
     }
+    fn n(&self) -> usize {
+        self.central_moments.count.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Kurtosis<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.central_moments.count.revert(x)?;
+        if self.central_moments.count.get() == F::from_f64(0.).unwrap() {
+            self.central_moments = CentralMoments::new();
+            return Ok(());
+        }
+        self.central_moments.revert_delta(x);
+        self.central_moments.revert_sum_delta();
+        self.central_moments.revert_m1(x);
+        self.central_moments.revert_m2();
+        self.central_moments.revert_m3();
+        self.central_moments.revert_m4();
+        Ok(())
+    }
+}
+
+/// Rolling kurtosis.
+///
+/// Unlike `Rolling<Kurtosis<F>>`, it owns its window and can be stored as a plain struct field.
+/// The oldest value is reverted from the central moments, and reverting the fourth moment lets
+/// rounding errors grow quickly. To bound them, the moments are recomputed from the window every
+/// `window_size` reverts, which keeps updates `O(1)` amortized.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `bias` - If `false`, then the calculations are corrected for statistical bias.
+/// # Examples
+/// ```
+/// use watermill::kurtosis::RollingKurtosis;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![ 0.49671415, -0.1382643 ,  0.64768854,  1.52302986, -0.23415337,-0.23413696];
+/// let mut rolling_kurtosis: RollingKurtosis<f64> = RollingKurtosis::new(4, true).unwrap();
+/// for x in data.into_iter(){
+///     rolling_kurtosis.update(x);
+/// }
+/// assert!((rolling_kurtosis.get() - -1.3761629753018245).abs() < 1e-9);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingKurtosis<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    kurtosis: Kurtosis<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+    reverts_since_resync: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingKurtosis<F> {
+    pub fn new(window_size: usize, bias: bool) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            kurtosis: Kurtosis::new(bias),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            reverts_since_resync: 0,
+        })
+    }
+    fn resync(&mut self) {
+        self.kurtosis = Kurtosis::new(self.kurtosis.bias);
+        for x in self.window.iter() {
+            self.kurtosis.update(*x);
+        }
+        self.reverts_since_resync = 0;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingKurtosis<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.kurtosis
+                .revert(oldest)
+                .expect("The window holds as many values as the kurtosis");
+            self.reverts_since_resync += 1;
+        }
+        self.window.push_back(x);
+        self.kurtosis.update(x);
+        if self.reverts_since_resync == self.window_size {
+            self.resync();
+        }
+    }
+    fn get(&self) -> F {
+        self.kurtosis.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    const DATA: [f64; 10] = [
+        0.49671415,
+        -0.1382643,
+        0.64768854,
+        1.52302986,
+        -0.23415337,
+        -0.23413696,
+        1.57921282,
+        0.76743473,
+        -0.46947439,
+        0.54256004,
+    ];
+    #[test]
+    fn rolling_kurtosis_matches_window() {
+        let mut running_kurtosis: Kurtosis<f64> = Kurtosis::default();
+        let mut rolling_kurtosis = Rolling::new(&mut running_kurtosis, 5).unwrap();
+        for (i, x) in DATA.iter().enumerate() {
+            rolling_kurtosis.update(*x);
+            let mut window_kurtosis: Kurtosis<f64> = Kurtosis::default();
+            for y in DATA[(i + 1).saturating_sub(5)..=i].iter() {
+                window_kurtosis.update(*y);
+            }
+            assert!((rolling_kurtosis.get() - window_kurtosis.get()).abs() < 1e-9);
+        }
+        // scipy.stats.kurtosis(DATA[-5:], bias=False)
+        assert!((rolling_kurtosis.get() - -0.8736044327449157).abs() < 1e-9);
+    }
+    // scipy.stats.kurtosis recomputed from the central moments of the window
+    fn brute_force(window: &[f64], bias: bool) -> f64 {
+        let n = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / n;
+        let m2 = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let m4 = window.iter().map(|x| (x - mean).powi(4)).sum::<f64>() / n;
+        let kurtosis = if m2 == 0. { 0. } else { m4 / (m2 * m2) };
+        if !bias && n > 3. {
+            return ((n * n - 1.) * kurtosis - 3. * (n - 1.).powi(2)) / ((n - 2.) * (n - 3.));
+        }
+        kurtosis - 3.
+    }
+    #[test]
+    fn standalone_rolling_kurtosis() {
+        let mut state: u64 = 11;
+        // Large values with a small spread, where reverting the moments alone drifts
+        let data: Vec<f64> = (0..5000)
+            .map(|i| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+                let burst = if (i / 250) % 2 == 0 { 1. } else { 20. };
+                1e4 + burst * (u - 0.5)
+            })
+            .collect();
+        for bias in [false, true] {
+            let mut rolling_kurtosis: RollingKurtosis<f64> =
+                RollingKurtosis::new(50, bias).unwrap();
+            for (i, x) in data.iter().enumerate() {
+                rolling_kurtosis.update(*x);
+                let window = &data[(i + 1).saturating_sub(50)..=i];
+                assert!((rolling_kurtosis.get() - brute_force(window, bias)).abs() < 1e-6);
+            }
+        }
+        assert!(RollingKurtosis::<f64>::new(0, false).is_err());
+    }
+    #[test]
+    fn revert_to_empty() {
+        let mut kurtosis: Kurtosis<f64> = Kurtosis::new(true);
+        for x in DATA.iter() {
+            kurtosis.update(*x);
+        }
+        for x in DATA.iter() {
+            kurtosis.revert(*x).unwrap();
+        }
+        assert_eq!(kurtosis.get(), -3.);
+        assert!(kurtosis.revert(1.).is_err());
+    }
 }
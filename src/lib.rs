@@ -29,42 +29,141 @@
 //![dependencies]
 //! watermill = "0.1.0"
 //!```
+//!## `no_std`
+//!The `std` feature is enabled by default. Without it the crate is `no_std` and only needs `alloc`,
+//!the float functions then come from [`libm`](https://crates.io/crates/libm) through the `libm`
+//!feature. One of `std` or `libm` has to be enabled:
+//!```bash
+//![dependencies]
+//! watermill = { version = "0.1.0", default-features = false, features = ["libm"] }
+//!```
+//!See `examples/no_std` for a crate built this way.
 //!## Statistics available
 //!| Statistics                      | Revertable ?|
 //!|---------------------------------|----------|
 //!| Mean                            | ✅        |
 //!| Variance                        | ✅        |
+//!| Standard deviation              | ✅        |
+//!| Standard error of the mean      | ✅        |
+//!| Coefficient of variation        | ✅        |
+//!| Root mean square                | ✅        |
 //!| Sum                             | ✅        |
 //!| Min                             | ✅        |
 //!| Max                             | ✅        |
-//!| Count                           | ❌        |
+//!| Absolute min                    | ✅        |
+//!| Absolute max                    | ✅        |
+//!| Count                           | ✅        |
 //!| Quantile                        | ✅        |
+//!| Median                          | ✅        |
 //!| Peak to peak                    | ✅        |
 //!| Exponentially weighted mean     | ❌        |
 //!| Exponentially weighted variance | ❌        |
+//!| Exponentially weighted std      | ❌        |
 //!| Interquartile range             | ✅        |
-//!| Kurtosis                        | ❌        |
-//!| Skewness                        | ❌        |
-//!| Covariance                      | ❌        |
+//!| Median absolute deviation       | ✅        |
+//!| Kurtosis                        | ✅        |
+//!| Skewness                        | ✅        |
+//!| Covariance                      | ✅        |
+//!| Pearson correlation             | ✅        |
+//!| Geometric mean                  | ✅        |
+//!| Harmonic mean                   | ✅        |
+//!| Mode                            | ✅        |
+//!| Entropy                         | ✅        |
+//!| T-digest                        | ❌        |
+//!| Greenwald-Khanna quantile       | ❌        |
+//!| Streaming histogram             | ❌        |
+//!| HyperLogLog distinct count      | ❌        |
+//!| Autocorrelation                 | ❌        |
+//!| Rolling autocovariance          | ✅        |
+//!| Gini coefficient                | ✅        |
+//!| Top-k / bottom-k                | ✅        |
+//!| ArgMin / ArgMax                 | ✅        |
+//!| Standard scaler                 | ❌        |
+//!| Min-max scaler                  | ✅        |
+//!| Reservoir sample                | ❌        |
+//!| Trimmed mean                    | ✅        |
+//!| Winsorized mean                 | ✅        |
+//!| Value counts                    | ✅        |
+//!| Population stability index      | ✅        |
+//!| ADWIN change detector           | ❌        |
+//!| Page-Hinkley change detector    | ❌        |
+//!| CUSUM change detector           | ❌        |
+//!| Linear regression               | ✅        |
+//!| EW linear regression            | ❌        |
+//!| Bollinger bands                 | ✅        |
+//!| Information ratio               | ✅        |
+//!| Multiple quantiles              | ❌        |
+//!| R² of predictions               | ✅        |
+//!| MAE / MSE / RMSE                | ✅        |
+//!| Z-score peak detector           | ❌        |
+//!| Frugal-1U quantile              | ❌        |
+//!| Theil-Sen regression            | ❌        |
 //!## Inspiration
 //!The `stats` module of the [`river`](https://github.com/online-ml/river) library in `Python` greatly inspired this crate.
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("watermill needs the `std` feature, or the `libm` feature for `no_std` builds");
+
+extern crate alloc;
+
+pub mod adwin;
+pub mod autocorr;
+pub mod autocov;
+pub mod bollinger;
+pub mod compose;
 pub mod count;
 pub mod covariance;
+pub mod cusum;
+pub mod cv;
+pub mod decaying;
+pub mod entropy;
 pub mod ewmean;
 pub mod ewvariance;
+pub mod finite;
+pub mod frugal;
+pub mod gini;
+pub mod gk;
+pub mod gmean;
+pub mod histogram;
+pub mod hll;
+pub mod hmean;
 pub mod iqr;
 pub mod iter;
 pub mod kurtosis;
+pub mod linreg;
+pub mod mad;
 pub mod maximum;
 pub mod mean;
+pub mod metrics;
 pub mod minimum;
+pub mod mode;
 pub mod moments;
+pub mod page_hinkley;
+pub mod peak;
+pub mod pearson;
+pub mod psi;
 pub mod ptp;
 pub mod quantile;
+pub mod r2;
+pub mod ratio;
+pub mod reservoir;
+pub mod rms;
 pub mod rolling;
+pub mod scaler;
+pub mod sem;
 pub mod skew;
+#[cfg(feature = "bincode")]
+pub mod snapshot;
 pub mod sorted_window;
 pub mod stats;
+pub mod std_dev;
 pub mod sum;
+pub mod tdigest;
+pub mod topk;
+pub mod trimmed;
+pub mod value_counts;
 pub mod variance;
+pub mod winsorized;
+
+mod utils;
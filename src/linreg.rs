@@ -0,0 +1,457 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::pearson::PearsonCorrelation;
+use crate::stats::{Bivariate, RevertableBivariate, Univariate};
+use crate::utils::SplitMix64;
+use serde::{Deserialize, Serialize};
+
+/// Rolling simple linear regression `y = slope * x + intercept`.
+///
+/// The least squares fit of the latest `window_size` pairs. The means, variances and covariance of
+/// the window are maintained by reverting the oldest pair, so an update is `O(1)`. `get` returns
+/// the slope, which is `NaN` while every `x` of the window is equal.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::linreg::RollingLinReg;
+/// use watermill::stats::Bivariate;
+/// let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(3).unwrap();
+/// let x: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let y: Vec<f64> = vec![5., 3., 1., 2., 3.];
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     rolling_linreg.update(*xi, *yi);
+/// }
+/// assert!((rolling_linreg.slope() - 1.).abs() < 1e-12);
+/// assert!((rolling_linreg.intercept() + 2.).abs() < 1e-12);
+/// assert!((rolling_linreg.r_squared() - 1.).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on simple linear regression](https://www.wikiwand.com/en/Simple_linear_regression)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingLinReg<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    corr: PearsonCorrelation<F>,
+    window: VecDeque<(F, F)>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingLinReg<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            corr: PearsonCorrelation::default(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+    /// Slope of the fitted line, `NaN` while every `x` of the window is equal.
+    pub fn slope(&self) -> F {
+        let var_x = self.corr.var_x.get();
+        if self.window.len() < 2 || var_x == F::from_f64(0.).unwrap() {
+            return F::nan();
+        }
+        self.corr.cov.get() / var_x
+    }
+    /// Intercept of the fitted line, `NaN` while every `x` of the window is equal.
+    pub fn intercept(&self) -> F {
+        self.corr.cov.mean_y.get() - self.slope() * self.corr.cov.mean_x.get()
+    }
+    /// Coefficient of determination of the fit, `1` when every `y` of the window is equal and
+    /// `NaN` while every `x` of the window is equal.
+    pub fn r_squared(&self) -> F {
+        let slope = self.slope();
+        if slope.is_nan() {
+            return slope;
+        }
+        if self.corr.var_y.get() == F::from_f64(0.).unwrap() {
+            return F::from_f64(1.).unwrap();
+        }
+        let corr = self.corr.get();
+        corr * corr
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RollingLinReg<F> {
+    fn update(&mut self, x: F, y: F) {
+        if self.window.len() == self.window_size {
+            let (oldest_x, oldest_y) = self.window.pop_front().expect("Window should not be empty");
+            // Every pair in the window has been seen by `corr`, so the revert cannot fail
+            self.corr
+                .revert(oldest_x, oldest_y)
+                .expect("Window pairs should be revertable");
+        }
+        self.window.push_back((x, y));
+        self.corr.update(x, y);
+    }
+    fn get(&self) -> F {
+        self.slope()
+    }
+}
+
+/// Exponentially weighted simple linear regression `y = slope * x + intercept`.
+///
+/// The least squares fit where the weight of a pair decays by `1 - alpha` at every update, so the
+/// line follows the recent trend. The weighted means, variance and covariance are updated
+/// incrementally, the first pair initialises the means. `get` returns the slope, which is `NaN`
+/// while every `x` seen is equal.
+/// # Arguments
+/// * `alpha` - The closer `alpha` is to 1 the more the fit will adapt to recent pairs. Must be in `(0, 1]`, default value is `0.5`.
+/// # Examples
+/// ```
+/// use watermill::linreg::EWRegression;
+/// use watermill::stats::Bivariate;
+/// let mut ew_regression: EWRegression<f64> = EWRegression::new(0.2).unwrap();
+/// for i in 0..50{
+///     let x = i as f64;
+///     ew_regression.update(x, 3. * x + 1.);
+/// }
+/// assert!((ew_regression.slope() - 3.).abs() < 1e-9);
+/// assert!((ew_regression.intercept() - 1.).abs() < 1e-9);
+/// ```
+/// # References
+/// [^1]: [Finch, T., 2009. Incremental calculation of weighted mean and variance. University of Cambridge, 4(11-5), pp.41-42.](https://fanf2.user.srcf.net/hermes/doc/antiforgery/stats.pdf)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EWRegression<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub alpha: F,
+    mean_x: F,
+    mean_y: F,
+    var_x: F,
+    cov: F,
+    n: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> EWRegression<F> {
+    pub fn new(alpha: F) -> Result<Self, &'static str> {
+        if !(alpha > F::from_f64(0.).unwrap() && alpha <= F::from_f64(1.).unwrap()) {
+            return Err("alpha should be in (0, 1]");
+        }
+        Ok(Self {
+            alpha,
+            mean_x: F::from_f64(0.).unwrap(),
+            mean_y: F::from_f64(0.).unwrap(),
+            var_x: F::from_f64(0.).unwrap(),
+            cov: F::from_f64(0.).unwrap(),
+            n: 0,
+        })
+    }
+    /// Slope of the fitted line, `NaN` while every `x` seen is equal.
+    pub fn slope(&self) -> F {
+        if self.var_x == F::from_f64(0.).unwrap() {
+            return F::nan();
+        }
+        self.cov / self.var_x
+    }
+    /// Intercept of the fitted line, `NaN` while every `x` seen is equal.
+    pub fn intercept(&self) -> F {
+        self.mean_y - self.slope() * self.mean_x
+    }
+}
+
+impl<F> Default for EWRegression<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(0.5).unwrap()).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for EWRegression<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.n += 1;
+        if self.n == 1 {
+            self.mean_x = x;
+            self.mean_y = y;
+            return;
+        }
+        let decay = F::from_f64(1.).unwrap() - self.alpha;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += self.alpha * dx;
+        self.mean_y += self.alpha * dy;
+        self.var_x = decay * (self.var_x + self.alpha * dx * dx);
+        self.cov = decay * (self.cov + self.alpha * dx * dy);
+    }
+    fn get(&self) -> F {
+        self.slope()
+    }
+}
+
+const DEFAULT_MAX_PAIRS: usize = 10_000;
+const THEIL_SEN_SEED: u64 = 0x7e11;
+
+/// Rolling Theil-Sen estimator, the median of the slopes between pairs of points of the window.
+///
+/// The slope ignores up to about 29% of arbitrary outliers, unlike least squares where a single
+/// outlier can drag the fit. Pairs of points sharing the same `x` are skipped. The window holds
+/// `window_size(window_size - 1) / 2` pairs of points, every one of them is used while there are
+/// at most `max_pairs`. Above that, the slope is the median of `max_pairs` pairs drawn at random,
+/// which approximates the exact estimator and bounds the cost of `slope` to
+/// `O(max_pairs log(max_pairs))`. The draws are seeded, so the same window always gives the same
+/// slope. `get` returns the slope, which is `NaN` while every `x` of the window is equal.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `max_pairs` - Number of pairs of points the median is computed on at most. Defaults to `10000`, all the pairs of a window of up to `141` points.
+/// # Examples
+/// ```
+/// use watermill::linreg::RollingTheilSen;
+/// use watermill::stats::Bivariate;
+/// let mut theil_sen: RollingTheilSen<f64> = RollingTheilSen::new(5).unwrap();
+/// let x: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let y: Vec<f64> = vec![2., 4., 60., 8., 10.];
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     theil_sen.update(*xi, *yi);
+/// }
+/// assert_eq!(theil_sen.slope(), 2.);
+/// assert_eq!(theil_sen.intercept(), 0.);
+/// ```
+/// # References
+/// [^1]: [Sen, P.K., 1968. Estimates of the regression coefficient based on Kendall's tau. Journal of the American Statistical Association, 63(324), pp.1379-1389.](https://www.tandfonline.com/doi/abs/10.1080/01621459.1968.10480934)
+///
+/// [^2]: [Wikipedia article on the Theil-Sen estimator](https://www.wikiwand.com/en/Theil%E2%80%93Sen_estimator)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingTheilSen<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    window: VecDeque<(F, F)>,
+    window_size: usize,
+    max_pairs: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingTheilSen<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_max_pairs(window_size, DEFAULT_MAX_PAIRS)
+    }
+    /// Same as `new`, with the number of pairs of points the median is computed on at most.
+    pub fn with_max_pairs(window_size: usize, max_pairs: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        if max_pairs == 0 {
+            return Err("max_pairs should not equal to 0");
+        }
+        Ok(Self {
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+            max_pairs,
+        })
+    }
+    /// Median of the slopes between pairs of points, `NaN` while every `x` of the window is equal.
+    pub fn slope(&self) -> F {
+        let len = self.window.len();
+        let mut slopes: Vec<F> = Vec::new();
+        if len * len.saturating_sub(1) / 2 <= self.max_pairs {
+            for i in 0..len {
+                for j in (i + 1)..len {
+                    push_slope(&mut slopes, self.window[i], self.window[j]);
+                }
+            }
+        } else {
+            let mut rng = SplitMix64::new(THEIL_SEN_SEED);
+            slopes.reserve(self.max_pairs);
+            for _ in 0..self.max_pairs {
+                let i = rng.next_below(len as u64) as usize;
+                // Any other point of the window
+                let j = (i + 1 + rng.next_below(len as u64 - 1) as usize) % len;
+                push_slope(&mut slopes, self.window[i], self.window[j]);
+            }
+        }
+        median(&mut slopes)
+    }
+    /// Median of `y - slope * x` over the window, `NaN` while every `x` of the window is equal.
+    pub fn intercept(&self) -> F {
+        let slope = self.slope();
+        if slope.is_nan() {
+            return slope;
+        }
+        let mut intercepts: Vec<F> = self.window.iter().map(|(x, y)| *y - slope * *x).collect();
+        median(&mut intercepts)
+    }
+}
+
+// Slope between two points, skipped if they share the same x
+fn push_slope<F: Float>(slopes: &mut Vec<F>, (x_i, y_i): (F, F), (x_j, y_j): (F, F)) {
+    if x_i != x_j {
+        slopes.push((y_j - y_i) / (x_j - x_i));
+    }
+}
+
+// Median of the values, averaging the middle ones for an even count, `NaN` if there are none
+fn median<F: Float + FromPrimitive>(values: &mut [F]) -> F {
+    if values.is_empty() {
+        return F::nan();
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        return (values[mid - 1] + values[mid]) / F::from_f64(2.).unwrap();
+    }
+    values[mid]
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RollingTheilSen<F> {
+    /// # Panics
+    /// Panics on `NaN` values.
+    fn update(&mut self, x: F, y: F) {
+        if x.is_nan() || y.is_nan() {
+            panic!("RollingTheilSen does not accept NaN values");
+        }
+        if self.window.len() == self.window_size {
+            self.window.pop_front();
+        }
+        self.window.push_back((x, y));
+    }
+    fn get(&self) -> F {
+        self.slope()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Least squares fit of the pairs, as `(slope, intercept, r_squared)`
+    fn least_squares(pairs: &[(f64, f64)]) -> (f64, f64, f64) {
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+        let sxx: f64 = pairs.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        let syy: f64 = pairs.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+        let sxy: f64 = pairs.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let slope = sxy / sxx;
+        (slope, mean_y - slope * mean_x, sxy * sxy / (sxx * syy))
+    }
+    #[test]
+    fn perfectly_linear() {
+        let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(10).unwrap();
+        for i in 0..100 {
+            let x = i as f64 * 0.5;
+            rolling_linreg.update(x, 2.5 * x - 3.);
+            if i > 0 {
+                assert!((rolling_linreg.slope() - 2.5).abs() < 1e-12);
+                assert!((rolling_linreg.intercept() + 3.).abs() < 1e-9);
+                assert!((rolling_linreg.r_squared() - 1.).abs() < 1e-12);
+            }
+        }
+        assert_eq!(rolling_linreg.get(), rolling_linreg.slope());
+    }
+    #[test]
+    fn noisy_matches_least_squares() {
+        let mut state: u64 = 42;
+        let mut pairs: Vec<(f64, f64)> = Vec::new();
+        for i in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let noise = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64 - 0.5;
+            let x = i as f64 + noise;
+            pairs.push((x, -0.7 * x + 4. + 3. * noise * noise));
+        }
+        let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(15).unwrap();
+        for (i, (x, y)) in pairs.iter().enumerate() {
+            rolling_linreg.update(*x, *y);
+            if i > 0 {
+                let (slope, intercept, r_squared) =
+                    least_squares(&pairs[(i + 1).saturating_sub(15)..=i]);
+                assert!((rolling_linreg.slope() - slope).abs() < 1e-9);
+                assert!((rolling_linreg.intercept() - intercept).abs() < 1e-7);
+                assert!((rolling_linreg.r_squared() - r_squared).abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn degenerate_windows() {
+        let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(3).unwrap();
+        assert!(rolling_linreg.slope().is_nan());
+        rolling_linreg.update(1., 2.);
+        assert!(rolling_linreg.slope().is_nan());
+        assert!(rolling_linreg.r_squared().is_nan());
+        // A horizontal line is a perfect fit
+        rolling_linreg.update(2., 2.);
+        assert_eq!(rolling_linreg.slope(), 0.);
+        assert_eq!(rolling_linreg.r_squared(), 1.);
+        assert!(RollingLinReg::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn ew_tracks_slope_change() {
+        let mut ew_regression: EWRegression<f64> = EWRegression::new(0.05).unwrap();
+        let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(500).unwrap();
+        assert!(ew_regression.slope().is_nan());
+        // The slope goes from 1 to -2 at x = 1000
+        for i in 0..1100 {
+            let x = i as f64;
+            let y = if i < 1000 { x } else { 3000. - 2. * x };
+            ew_regression.update(x, y);
+            rolling_linreg.update(x, y);
+            if i == 999 {
+                assert!((ew_regression.slope() - 1.).abs() < 1e-9);
+            }
+        }
+        assert!((ew_regression.slope() + 2.).abs() < 0.2);
+        assert!(rolling_linreg.slope() > 0.);
+        assert!(EWRegression::<f64>::new(0.).is_err());
+        assert!(EWRegression::<f64>::new(1.5).is_err());
+        assert!(EWRegression::<f64>::new(f64::NAN).is_err());
+    }
+    #[test]
+    fn theil_sen_ignores_outliers() {
+        let mut state: u64 = 8;
+        let mut pairs: Vec<(f64, f64)> = Vec::new();
+        for i in 0..100 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let noise = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64 - 0.5;
+            let x = i as f64;
+            // One point in ten is a gross outlier
+            let y = if i % 10 == 3 {
+                500.
+            } else {
+                1.5 * x - 2. + 0.1 * noise
+            };
+            pairs.push((x, y));
+        }
+        let mut theil_sen: RollingTheilSen<f64> = RollingTheilSen::new(50).unwrap();
+        let mut rolling_linreg: RollingLinReg<f64> = RollingLinReg::new(50).unwrap();
+        for (x, y) in pairs.iter() {
+            theil_sen.update(*x, *y);
+            rolling_linreg.update(*x, *y);
+        }
+        assert!((theil_sen.get() - 1.5).abs() < 0.01);
+        assert!((theil_sen.intercept() + 2.).abs() < 0.1);
+        assert!((rolling_linreg.slope() - 1.5).abs() > 0.3);
+        let (slope, _, _) = least_squares(&pairs[50..]);
+        assert!((rolling_linreg.slope() - slope).abs() < 1e-9);
+    }
+    #[test]
+    fn theil_sen_sampled_pairs() {
+        let mut exact: RollingTheilSen<f64> = RollingTheilSen::new(200).unwrap();
+        let mut sampled: RollingTheilSen<f64> = RollingTheilSen::with_max_pairs(200, 2000).unwrap();
+        for i in 0..300 {
+            let x = i as f64;
+            let y = if i % 7 == 0 {
+                -100.
+            } else {
+                0.5 * x + ((i * 31) % 11) as f64 * 0.1
+            };
+            exact.update(x, y);
+            sampled.update(x, y);
+        }
+        assert!((sampled.slope() - exact.slope()).abs() < 0.01);
+        assert_eq!(sampled.slope(), sampled.slope());
+    }
+    #[test]
+    fn theil_sen_degenerate_windows() {
+        let mut theil_sen: RollingTheilSen<f64> = RollingTheilSen::with_max_pairs(4, 1).unwrap();
+        assert!(theil_sen.slope().is_nan());
+        for y in [1., 2., 3., 4.] {
+            theil_sen.update(2., y);
+        }
+        assert!(theil_sen.slope().is_nan());
+        assert!(theil_sen.intercept().is_nan());
+        assert!(RollingTheilSen::<f64>::new(0).is_err());
+        assert!(RollingTheilSen::<f64>::with_max_pairs(10, 0).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::quantile::Quantile;
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+// Scale factor making the MAD a consistent estimator of the standard deviation of normal data
+const NORMAL_SCALE: f64 = 1.4826;
+
+/// Running median absolute deviation.
+///
+/// The median is tracked by a P² `Quantile` and every new value's absolute deviation from the
+/// current median is fed to a second one, the result is therefore an approximation. Use
+/// `RollingMAD` for an exact value over a window.
+/// # Arguments
+/// * `normal_consistent` - If `true`, the MAD is scaled by `1.4826` to estimate the standard deviation of normally distributed data. Defaults to `false`.
+/// # Examples
+/// ```
+/// use watermill::mad::MAD;
+/// use watermill::stats::Univariate;
+/// let mut running_mad: MAD<f64> = MAD::default();
+/// for i in 1..=100{
+///     running_mad.update(i as f64);
+/// }
+/// assert!((running_mad.get() - 25.).abs() < 2.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the median absolute deviation](https://www.wikiwand.com/en/Median_absolute_deviation)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MAD<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub median: Quantile<F>,
+    pub deviation_median: Quantile<F>,
+    pub normal_consistent: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MAD<F> {
+    pub fn new(normal_consistent: bool) -> Self {
+        Self {
+            median: Quantile::default(),
+            deviation_median: Quantile::default(),
+            normal_consistent,
+        }
+    }
+}
+
+impl<F> Default for MAD<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for MAD<F> {
+    fn update(&mut self, x: F) {
+        self.median.update(x);
+        self.deviation_median.update((x - self.median.get()).abs());
+    }
+    fn get(&self) -> F {
+        let mad = self.deviation_median.get();
+        if self.normal_consistent {
+            return mad * F::from_f64(NORMAL_SCALE).unwrap();
+        }
+        mad
+    }
+    fn n(&self) -> usize {
+        self.median.n()
+    }
+}
+
+/// Rolling median absolute deviation.
+///
+/// The MAD is computed exactly over the window, `get` returns `NaN` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `normal_consistent` - If `true`, the MAD is scaled by `1.4826` to estimate the standard deviation of normally distributed data.
+/// # Examples
+/// ```
+/// use watermill::mad::RollingMAD;
+/// use watermill::stats::Univariate;
+/// let mut rolling_mad: RollingMAD<f64> = RollingMAD::new(5, false).unwrap();
+/// for x in vec![50., 1., 2., 3., 4., 100.].into_iter(){
+///     rolling_mad.update(x);
+/// }
+/// assert_eq!(rolling_mad.get(), 1.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMAD<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    pub normal_consistent: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMAD<F> {
+    pub fn new(window_size: usize, normal_consistent: bool) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+            normal_consistent,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMAD<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        let sorted: Vec<F> = self.sorted_window.iter().copied().collect();
+        let n = sorted.len();
+        if n == 0 {
+            return F::nan();
+        }
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / F::from_f64(2.).unwrap()
+        };
+        // The deviations of the values below and above the median are both sorted when walking
+        // away from the median, merging them yields the smallest deviations in order.
+        let split = sorted.partition_point(|x| *x < median);
+        let (mut left, mut right) = (split, split);
+        let mut deviations: Vec<F> = Vec::with_capacity(n / 2 + 1);
+        while deviations.len() <= n / 2 {
+            let left_deviation = (left > 0).then(|| median - sorted[left - 1]);
+            let right_deviation = (right < n).then(|| sorted[right] - median);
+            match (left_deviation, right_deviation) {
+                (Some(l), Some(r)) if l <= r => {
+                    deviations.push(l);
+                    left -= 1;
+                }
+                (Some(l), None) => {
+                    deviations.push(l);
+                    left -= 1;
+                }
+                (_, Some(r)) => {
+                    deviations.push(r);
+                    right += 1;
+                }
+                (None, None) => unreachable!("The window holds n values"),
+            }
+        }
+        let mad = if n % 2 == 1 {
+            deviations[n / 2]
+        } else {
+            (deviations[n / 2 - 1] + deviations[n / 2]) / F::from_f64(2.).unwrap()
+        };
+        if self.normal_consistent {
+            return mad * F::from_f64(NORMAL_SCALE).unwrap();
+        }
+        mad
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn brute_force_mad(window: &[f64]) -> f64 {
+        fn median(values: &mut [f64]) -> f64 {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let n = values.len();
+            if n % 2 == 1 {
+                values[n / 2]
+            } else {
+                (values[n / 2 - 1] + values[n / 2]) / 2.
+            }
+        }
+        let m = median(&mut window.to_vec());
+        let mut deviations: Vec<f64> = window.iter().map(|x| (x - m).abs()).collect();
+        median(&mut deviations)
+    }
+    #[test]
+    fn rolling_mad_matches_window() {
+        let data: Vec<f64> = vec![1., 2., 3., 4., 100., -7., 3., 3., 8., 0.5, 2., 11.];
+        for window_size in 1..=6 {
+            let mut rolling_mad: RollingMAD<f64> = RollingMAD::new(window_size, false).unwrap();
+            for (i, x) in data.iter().enumerate() {
+                rolling_mad.update(*x);
+                let window = &data[(i + 1).saturating_sub(window_size)..=i];
+                assert_eq!(rolling_mad.get(), brute_force_mad(window));
+            }
+        }
+    }
+    #[test]
+    fn even_window() {
+        let mut rolling_mad: RollingMAD<f64> = RollingMAD::new(4, true).unwrap();
+        assert!(rolling_mad.get().is_nan());
+        for x in vec![1., 2., 3., 4.].into_iter() {
+            rolling_mad.update(x);
+        }
+        assert_eq!(rolling_mad.get(), 1.4826);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::sorted_window::SortedWindow;
-use crate::stats::Univariate;
+use crate::sorted_window::{NanPolicy, SortedWindow};
+use crate::stats::{Mergeable, Univariate};
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 /// Running max.
 /// # Examples
 /// ```
@@ -18,11 +19,14 @@ use std::ops::{AddAssign, SubAssign};
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Max<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub max: F,
+    #[serde(default)]
+    n: usize,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Max<F> {
     fn default() -> Self {
         Self {
             max: F::min_value(),
+            n: 0,
         }
     }
 }
@@ -32,15 +36,108 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Max<F> {
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Max<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Max<F> {
     fn update(&mut self, x: F) {
         if self.max < x {
             self.max = x;
         }
+        self.n += 1;
     }
     fn get(&self) -> F {
         self.max
     }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.max)
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Max<F> {
+    fn merge(&mut self, other: &Self) {
+        if other.max > self.max {
+            self.max = other.max;
+        }
+        self.n += other.n;
+    }
+}
+
+/// Running max along with the index of the update at which it was observed.
+///
+/// Updates are numbered from `0` on, the first occurrence of the max is kept on ties.
+/// `NaN` values are skipped like in `Max` but still count as an update.
+/// # Examples
+/// ```
+/// use watermill::maximum::ArgMax;
+/// use watermill::stats::Univariate;
+/// let mut running_arg_max: ArgMax<f64> = ArgMax::new();
+/// for x in vec![2., 9., 4., 9., 1.].into_iter(){
+///     running_arg_max.update(x);
+/// }
+/// assert_eq!(running_arg_max.get(), 9.);
+/// assert_eq!(running_arg_max.arg(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ArgMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    max: F,
+    arg: Option<usize>,
+    n_updates: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for ArgMax<F> {
+    fn default() -> Self {
+        Self {
+            max: F::min_value(),
+            arg: None,
+            n_updates: 0,
+        }
+    }
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ArgMax<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Index of the update holding the max.
+    /// # Panics
+    /// Panics if no value was seen yet, see `try_arg`.
+    pub fn arg(&self) -> usize {
+        self.try_arg().expect("ArgMax has not seen any value")
+    }
+    /// Index of the update holding the max, or `None` if no value was seen yet.
+    pub fn try_arg(&self) -> Option<usize> {
+        self.arg
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for ArgMax<F> {
+    fn update(&mut self, x: F) {
+        if self.arg.is_none() && !x.is_nan() || self.max < x {
+            self.max = x;
+            self.arg = Some(self.n_updates);
+        }
+        self.n_updates += 1;
+    }
+    fn get(&self) -> F {
+        self.max
+    }
+    fn try_get(&self) -> Option<F> {
+        self.arg.map(|_| self.max)
+    }
+    fn n(&self) -> usize {
+        self.n_updates
+    }
 }
 
 /// Running absolute max.
@@ -55,15 +152,18 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Max<F>
 /// assert_eq!(running_abs_max.get(), 17.0);
 /// ```
 ///
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct AbsMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
-    abs_max: F,
+    pub abs_max: F,
+    #[serde(default)]
+    n: usize,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for AbsMax<F> {
     fn default() -> Self {
         Self {
             abs_max: F::from_f64(0.0).unwrap(),
+            n: 0,
         }
     }
 }
@@ -78,10 +178,14 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for AbsMax<
         if self.abs_max < x.abs() {
             self.abs_max = x.abs();
         }
+        self.n += 1;
     }
     fn get(&self) -> F {
         self.abs_max
     }
+    fn n(&self) -> usize {
+        self.n
+    }
 }
 
 /// Rolling max.
@@ -91,23 +195,34 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for AbsMax<
 /// ```
 /// use watermill::maximum::RollingMax;
 /// use watermill::stats::Univariate;
-/// let mut rolling_max: RollingMax<f64> = RollingMax::new(3);
+/// let mut rolling_max: RollingMax<f64> = RollingMax::new(3).unwrap();
 /// for i in 1..10{
 ///     rolling_max.update(i as f64);
 /// }
 /// assert_eq!(rolling_max.get(), 9.0);
 /// ```
 ///
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RollingMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
     sorted_window: SortedWindow<F>,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMax<F> {
-    pub fn new(window_size: usize) -> Self {
-        Self {
-            sorted_window: SortedWindow::new(window_size),
-        }
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_nan_policy(window_size, NanPolicy::Panic)
+    }
+    /// Same as `new`, with the given handling of `NaN` values.
+    pub fn with_nan_policy(
+        window_size: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::with_nan_policy(window_size, nan_policy)?,
+        })
+    }
+    /// Change the size of the window, see `sorted_window::SortedWindow::resize`.
+    pub fn resize(&mut self, window_size: usize) -> Result<(), &'static str> {
+        self.sorted_window.resize(window_size)
     }
 }
 
@@ -118,4 +233,240 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.sorted_window.back()
     }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.sorted_window.back())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+/// Rolling max along with the index, in the whole stream, of the update at which it was observed.
+///
+/// The window keeps the candidates for the max in decreasing order, so an update costs `O(1)`
+/// amortized. When the update holding the max leaves the window, the index moves to the next
+/// candidate. The first occurrence of the max is kept on ties.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::maximum::RollingArgMax;
+/// use watermill::stats::Univariate;
+/// let mut rolling_arg_max: RollingArgMax<f64> = RollingArgMax::new(3).unwrap();
+/// for x in vec![2., 9., 4., 3., 1.].into_iter(){
+///     rolling_arg_max.update(x);
+/// }
+/// assert_eq!(rolling_arg_max.get(), 4.);
+/// assert_eq!(rolling_arg_max.arg(), 2);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingArgMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    // (update index, value), values decreasing from front to back
+    candidates: VecDeque<(usize, F)>,
+    window_size: usize,
+    n_updates: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingArgMax<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            candidates: VecDeque::new(),
+            window_size,
+            n_updates: 0,
+        })
+    }
+    /// Index of the update holding the max of the window.
+    /// # Panics
+    /// Panics if no value was seen yet, see `try_arg`.
+    pub fn arg(&self) -> usize {
+        self.try_arg()
+            .expect("RollingArgMax has not seen any value")
+    }
+    /// Index of the update holding the max of the window, or `None` if no value was seen yet.
+    pub fn try_arg(&self) -> Option<usize> {
+        self.candidates.front().map(|(index, _)| *index)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingArgMax<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("RollingArgMax does not accept NaN values");
+        }
+        while self.candidates.back().is_some_and(|(_, value)| *value < x) {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.n_updates, x));
+        if let Some((index, _)) = self.candidates.front() {
+            if index + self.window_size <= self.n_updates {
+                self.candidates.pop_front();
+            }
+        }
+        self.n_updates += 1;
+    }
+    fn get(&self) -> F {
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        self.candidates.front().map(|(_, value)| *value)
+    }
+    fn n(&self) -> usize {
+        self.n_updates.min(self.window_size)
+    }
+}
+
+/// Rolling absolute max.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::maximum::RollingAbsMax;
+/// use watermill::stats::Univariate;
+/// let mut rolling_abs_max: RollingAbsMax<f64> = RollingAbsMax::new(3).unwrap();
+/// for i in -10..-3{
+///     rolling_abs_max.update(i as f64);
+/// }
+/// assert_eq!(rolling_abs_max.get(), 6.0);
+/// ```
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingAbsMax<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingAbsMax<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingAbsMax<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x.abs());
+    }
+    fn get(&self) -> F {
+        self.sorted_window.back()
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.sorted_window.back())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn try_get_min_value() {
+        let mut running_max: Max<f64> = Max::new();
+        assert_eq!(running_max.try_get(), None);
+        running_max.update(f64::MIN);
+        assert_eq!(running_max.try_get(), Some(f64::MIN));
+    }
+    #[test]
+    fn arg_max_of_sequence() {
+        let data = [3., 1., 4., 1., 5., 9., 2., 6., 5., 3., 5., 9.];
+        let mut arg_max: ArgMax<f64> = ArgMax::new();
+        assert_eq!(arg_max.try_arg(), None);
+        assert_eq!(arg_max.try_get(), None);
+        let expected = [0, 0, 2, 2, 4, 5, 5, 5, 5, 5, 5, 5];
+        for (x, e) in data.iter().zip(expected) {
+            arg_max.update(*x);
+            assert_eq!(arg_max.arg(), e);
+            assert_eq!(arg_max.get(), data[e]);
+        }
+        arg_max.update(f64::NAN);
+        arg_max.update(10.);
+        assert_eq!(arg_max.arg(), 13);
+    }
+    #[test]
+    fn rolling_arg_max_leaves_window() {
+        let data = [3., 1., 4., 1., 5., 9., 2., 6., 5., 3., 5., 9.];
+        let mut rolling_arg_max: RollingArgMax<f64> = RollingArgMax::new(4).unwrap();
+        assert_eq!(rolling_arg_max.try_arg(), None);
+        assert!(rolling_arg_max.get().is_nan());
+        for i in 0..data.len() {
+            rolling_arg_max.update(data[i]);
+            // First index of the max of the window
+            let start = (i + 1).saturating_sub(4);
+            let mut expected = start;
+            for j in start..=i {
+                if data[j] > data[expected] {
+                    expected = j;
+                }
+            }
+            assert_eq!(rolling_arg_max.arg(), expected);
+            assert_eq!(rolling_arg_max.get(), data[expected]);
+        }
+        assert!(RollingArgMax::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn try_get_empty() {
+        let mut max: Max<f64> = Max::new();
+        let abs_max: AbsMax<f64> = AbsMax::new();
+        let mut rolling_max: RollingMax<f64> = RollingMax::new(2).unwrap();
+        let mut rolling_abs_max: RollingAbsMax<f64> = RollingAbsMax::new(2).unwrap();
+        assert_eq!(max.try_get(), None);
+        // The absolute max of an empty stream is 0
+        assert_eq!(abs_max.try_get(), Some(0.));
+        assert_eq!(rolling_max.try_get(), None);
+        assert_eq!(rolling_abs_max.try_get(), None);
+        max.update(-2.);
+        rolling_max.update(-2.);
+        rolling_abs_max.update(-2.);
+        assert_eq!(max.try_get(), Some(-2.));
+        assert_eq!(rolling_max.try_get(), Some(-2.));
+        assert_eq!(rolling_abs_max.try_get(), Some(2.));
+    }
+    #[test]
+    fn abs_max_negative_inputs() {
+        let mut abs_max: AbsMax<f64> = AbsMax::new();
+        for x in vec![1., -8., 3., 7.5].into_iter() {
+            abs_max.update(x);
+        }
+        assert_eq!(abs_max.get(), 8.);
+    }
+    #[test]
+    fn rolling_abs_max() {
+        let mut rolling_abs_max: RollingAbsMax<f64> = RollingAbsMax::new(3).unwrap();
+        let expected = [1., 8., 8., 8., 6., 6.];
+        for (x, e) in vec![1., -8., 3., 2., -6., 0.5].into_iter().zip(expected) {
+            rolling_abs_max.update(x);
+            assert_eq!(rolling_abs_max.get(), e);
+        }
+    }
+    #[test]
+    fn rolling_max_resize() {
+        let data: Vec<f64> = (0..50).map(|i| ((i * 17) % 23) as f64).collect();
+        for (filled, new_size) in [(3, 2), (3, 8), (20, 2), (20, 8)] {
+            let mut rolling_max: RollingMax<f64> = RollingMax::new(5).unwrap();
+            rolling_max.update_many(data[..filled].iter().copied());
+            rolling_max.resize(new_size).unwrap();
+            let retained = filled.min(5).min(new_size);
+            let mut fresh: RollingMax<f64> = RollingMax::new(new_size).unwrap();
+            fresh.update_many(data[filled - retained..filled].iter().copied());
+            for x in data[filled..].iter() {
+                assert_eq!(rolling_max.get(), fresh.get());
+                rolling_max.update(*x);
+                fresh.update(*x);
+            }
+            assert_eq!(rolling_max.n(), new_size);
+        }
+        assert!(RollingMax::<f64>::new(5).unwrap().resize(0).is_err());
+    }
 }
@@ -1,8 +1,10 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::count::Count;
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::stats::{Mergeable, Revertable, Scalable, Univariate, WeightedUnivariate};
+use crate::utils::compensated_add;
 use serde::{Deserialize, Serialize};
 
 /// Running mean.
@@ -22,6 +24,30 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_mean.get(), 0.);
 /// ```
+/// Rolling mean over the `3` latest values:
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::rolling::Rolling;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+/// let mut running_mean: Mean<f64> = Mean::new();
+/// let mut rolling_mean: Rolling<_, f64> = Rolling::new(&mut running_mean, 3).unwrap();
+/// for x in data.into_iter(){
+///     rolling_mean.update(x);
+/// }
+/// assert!((rolling_mean.get() - 17. / 3.).abs() < 1e-12);
+/// ```
+/// It can also be collected from an iterator:
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+/// let running_mean: Mean<f64> = data.iter().copied().collect();
+/// assert_eq!(running_mean.get(), 5.);
+/// ```
+/// `new_compensated` keeps the rounding error of every shift of the mean with Neumaier
+/// compensated summation, a few more floating point operations per update for a mean that does
+/// not drift over long streams.
 /// # References
 /// [^1]: [West, D. H. D. (1979). Updating mean and variance estimates: An improved method. Communications of the ACM, 22(9), 532-535.](https://dl.acm.org/doi/10.1145/359146.359153)
 ///
@@ -32,12 +58,16 @@ use serde::{Deserialize, Serialize};
 pub struct Mean<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub mean: F,
     pub n: Count<F>,
+    // Rounding error lost by `mean`, only tracked by compensated means
+    #[serde(default = "Option::default")]
+    compensation: Option<F>,
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Mean<F> {
     fn default() -> Self {
         Self {
             mean: F::from_f64(0.0).unwrap(),
             n: Count::new(),
+            compensation: None,
         }
     }
 }
@@ -45,15 +75,41 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mean<F> {
     pub fn new() -> Self {
         Self::default()
     }
+    pub fn new_compensated() -> Self {
+        Self {
+            compensation: Some(F::from_f64(0.0).unwrap()),
+            ..Self::default()
+        }
+    }
+    fn shift(&mut self, delta: F) {
+        match self.compensation.as_mut() {
+            Some(compensation) => compensated_add(&mut self.mean, compensation, delta),
+            None => self.mean += delta,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Mean<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Mean<F> {
     fn update(&mut self, x: F) {
         self.n.update(x);
-        self.mean += (F::from_f64(1.).unwrap() / self.n.get()) * (x - self.mean);
+        self.shift((F::from_f64(1.).unwrap() / self.n.get()) * (x - self.get()));
     }
     fn get(&self) -> F {
-        self.mean
+        match self.compensation {
+            Some(compensation) => self.mean + compensation,
+            None => self.mean,
+        }
+    }
+    fn n(&self) -> usize {
+        self.n.n()
     }
 }
 
@@ -64,11 +120,241 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Mean<F>
         let count = self.n.get();
         if count == F::from_f64(0.).unwrap() {
             self.mean = F::from_f64(0.0).unwrap();
+            if let Some(compensation) = self.compensation.as_mut() {
+                *compensation = F::from_f64(0.0).unwrap();
+            }
         } else {
-            self.mean -= (F::from_f64(1.0).unwrap() / count) * (x - self.mean);
+            self.shift(-(F::from_f64(1.0).unwrap() / count) * (x - self.get()));
         }
         Ok(())
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Mean<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Mean<F> {
+    fn merge(&mut self, other: &Self) {
+        self.n.merge(&other.n);
+        let n = self.n.get();
+        if n == F::from_f64(0.).unwrap() {
+            return;
+        }
+        // Weighting the shift by the other share keeps the result stable for very uneven sizes
+        self.shift((other.get() - self.get()) * (other.n.get() / n));
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WeightedUnivariate<F> for Mean<F> {
+    fn update_weighted(&mut self, x: F, w: F) {
+        if w == F::from_f64(0.).unwrap() {
+            return;
+        }
+        self.n.count += w;
+        self.shift((w / self.n.get()) * (x - self.get()));
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Scalable<F> for Mean<F> {
+    /// Only the weight of the past values shrinks, the mean itself is unchanged.
+    fn scale(&mut self, factor: F) {
+        self.n.scale(factor);
+    }
+}
+
+/// Rolling mean.
+///
+/// Unlike `Rolling<Mean<F>>`, it owns its window and can be stored as a plain struct field.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::mean::RollingMean;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+/// let mut rolling_mean: RollingMean<f64> = RollingMean::new(3).unwrap();
+/// for x in data.into_iter(){
+///     rolling_mean.update(x);
+/// }
+/// assert!((rolling_mean.get() - 17. / 3.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    mean: Mean<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMean<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            mean: Mean::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMean<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.mean
+                .revert(oldest)
+                .expect("The window holds as many values as the mean");
+        }
+        self.window.push_back(x);
+        self.mean.update(x);
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn mean_of_sequence() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut mean: Mean<f64> = Mean::new();
+        for x in data.into_iter() {
+            mean.update(x);
+        }
+        assert_eq!(mean.get(), 5.);
+    }
+    #[test]
+    fn rolling_mean() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut mean: Mean<f64> = Mean::new();
+        let mut rolling_mean = Rolling::new(&mut mean, 3).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_mean.update(*x);
+            let window = &data[(i + 1).saturating_sub(3)..=i];
+            let expected = window.iter().sum::<f64>() / window.len() as f64;
+            assert!((rolling_mean.get() - expected).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn rolling_mean_matches_rolling_wrapper() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut mean: Mean<f64> = Mean::new();
+        let mut rolling_mean = Rolling::new(&mut mean, 4).unwrap();
+        let mut standalone: RollingMean<f64> = RollingMean::new(4).unwrap();
+        for x in data.into_iter() {
+            rolling_mean.update(x);
+            standalone.update(x);
+            assert_eq!(standalone.get(), rolling_mean.get());
+        }
+        assert!(RollingMean::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn update_many() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut single: Mean<f64> = Mean::new();
+        let mut batch: Mean<f64> = Mean::new();
+        single.update(-3.);
+        batch.update(-3.);
+        for x in data.iter() {
+            single.update(*x);
+        }
+        batch.update_many(data.iter().copied());
+        batch.update_many(Vec::new());
+        assert_eq!(batch.n.get(), single.n.get());
+        assert!((batch.get() - single.get()).abs() < 1e-12);
+    }
+    #[test]
+    fn update_many_large_batch() {
+        let data: Vec<f32> = (0..1_000_000).map(|i| 1e4 + (i % 7) as f32).collect();
+        let mut single: Mean<f32> = Mean::new();
+        let mut batch: Mean<f32> = Mean::new();
+        for x in data.iter() {
+            single.update(*x);
+        }
+        batch.update_many(data.iter().copied());
+        assert_eq!(batch.get(), single.get());
+        assert!((batch.get() - 10003.).abs() < 0.01);
+    }
+    #[test]
+    fn update_many_compensated() {
+        let data: Vec<f32> = (0..10000).map(|i| 1e5 + (i % 3) as f32 * 0.1).collect();
+        let mut single: Mean<f32> = Mean::new_compensated();
+        let mut batch: Mean<f32> = Mean::new_compensated();
+        for x in data.iter() {
+            single.update(*x);
+        }
+        batch.update_many(data.iter().copied());
+        assert_eq!(batch.get(), single.get());
+    }
+    #[test]
+    fn update_if_finite() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut clean: Mean<f64> = Mean::new();
+        let mut skipping: Mean<f64> = Mean::new();
+        for (i, x) in data.iter().enumerate() {
+            clean.update(*x);
+            skipping.update_if_finite(*x);
+            if i % 2 == 0 {
+                skipping.update_if_finite(f64::NAN);
+            } else {
+                skipping.update_if_finite(f64::INFINITY);
+            }
+            assert_eq!(skipping.get(), clean.get());
+        }
+        assert_eq!(skipping.n.get(), 9.);
+    }
+    #[test]
+    fn update_weighted() {
+        let mut repeated: Mean<f64> = Mean::new();
+        let mut weighted: Mean<f64> = Mean::new();
+        for (x, w) in [(5., 1000), (-2.5, 3), (7., 1), (0.25, 40)] {
+            for _ in 0..w {
+                repeated.update(x);
+            }
+            weighted.update_weighted(x, w as f64);
+            assert_eq!(weighted.n.get(), repeated.n.get());
+            assert!((weighted.get() - repeated.get()).abs() < 1e-9);
+        }
+        weighted.update_weighted(100., 0.);
+        assert_eq!(weighted.n.get(), 1044.);
+    }
+    #[test]
+    fn compensated_mean_keeps_small_shifts() {
+        let mut naive_mean: Mean<f32> = Mean::new();
+        let mut compensated_mean: Mean<f32> = Mean::new_compensated();
+        // Once n is large the shifts of the second half are below the precision of f32 around 1e4
+        for i in 0..2_000_000 {
+            let x = if i < 1_000_000 { 1e4 } else { 1e4 + 1. };
+            naive_mean.update(x);
+            compensated_mean.update(x);
+        }
+        assert_eq!(naive_mean.get(), 1e4);
+        assert!((compensated_mean.get() - (1e4 + 0.5)).abs() < 1e-2);
+    }
+    #[test]
+    fn merge() {
+        let data: Vec<f64> = (0..1000)
+            .map(|i| 1e3 + ((i * 37) % 101) as f64 / 7.)
+            .collect();
+        for split in [0, 1, 10, 500, 999, 1000] {
+            let mut single_pass: Mean<f64> = Mean::new();
+            let mut left: Mean<f64> = Mean::new();
+            let mut right: Mean<f64> = Mean::new();
+            for (i, x) in data.iter().enumerate() {
+                single_pass.update(*x);
+                if i < split {
+                    left.update(*x);
+                } else {
+                    right.update(*x);
+                }
+            }
+            left.merge(&right);
+            assert_eq!(left.n.get(), 1000.);
+            assert!((left.get() - single_pass.get()).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::Mean;
+use crate::stats::{Bivariate, Revertable, RevertableBivariate, Univariate};
+use serde::{Deserialize, Serialize};
+
+/// Running mean absolute error of `(y_true, y_pred)` pairs.
+///
+/// `get` returns `0` before any update. Wrap it in a `rolling::RollingBivariate` for the error
+/// over a window.
+/// # Examples
+/// ```
+/// use watermill::metrics::MAE;
+/// use watermill::stats::Bivariate;
+/// let y_true: Vec<f64> = vec![3., -0.5, 2., 7.];
+/// let y_pred: Vec<f64> = vec![2.5, 0., 2., 8.];
+/// let mut mae: MAE<f64> = MAE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()){
+///     mae.update(*yt, *yp);
+/// }
+/// assert_eq!(mae.get(), 0.5);
+/// ```
+/// Over the `2` latest pairs:
+/// ```
+/// use watermill::metrics::MAE;
+/// use watermill::rolling::RollingBivariate;
+/// use watermill::stats::Bivariate;
+/// let y_true: Vec<f64> = vec![3., -0.5, 2., 7.];
+/// let y_pred: Vec<f64> = vec![2.5, 0., 2., 8.];
+/// let mut rolling_mae = RollingBivariate::new(MAE::new(), 2).unwrap();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()){
+///     rolling_mae.update(*yt, *yp);
+/// }
+/// assert_eq!(rolling_mae.get(), 0.5);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the mean absolute error](https://www.wikiwand.com/en/Mean_absolute_error)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MAE<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for MAE<F> {
+    fn default() -> Self {
+        Self { mean: Mean::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MAE<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for MAE<F> {
+    fn update(&mut self, y_true: F, y_pred: F) {
+        self.mean.update((y_true - y_pred).abs());
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for MAE<F> {
+    fn revert(&mut self, y_true: F, y_pred: F) -> Result<(), &'static str> {
+        self.mean.revert((y_true - y_pred).abs())
+    }
+}
+
+/// Running mean squared error of `(y_true, y_pred)` pairs.
+///
+/// `get` returns `0` before any update. Wrap it in a `rolling::RollingBivariate` for the error
+/// over a window.
+/// # Examples
+/// ```
+/// use watermill::metrics::MSE;
+/// use watermill::stats::Bivariate;
+/// let y_true: Vec<f64> = vec![3., -0.5, 2., 7.];
+/// let y_pred: Vec<f64> = vec![2.5, 0., 2., 8.];
+/// let mut mse: MSE<f64> = MSE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()){
+///     mse.update(*yt, *yp);
+/// }
+/// assert_eq!(mse.get(), 0.375);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the mean squared error](https://www.wikiwand.com/en/Mean_squared_error)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MSE<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub mean: Mean<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for MSE<F> {
+    fn default() -> Self {
+        Self { mean: Mean::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MSE<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for MSE<F> {
+    fn update(&mut self, y_true: F, y_pred: F) {
+        self.mean.update((y_true - y_pred).powi(2));
+    }
+    fn get(&self) -> F {
+        self.mean.get()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for MSE<F> {
+    fn revert(&mut self, y_true: F, y_pred: F) -> Result<(), &'static str> {
+        self.mean.revert((y_true - y_pred).powi(2))
+    }
+}
+
+/// Running root mean squared error of `(y_true, y_pred)` pairs, the square root of `MSE`.
+///
+/// `get` returns `0` before any update. Wrap it in a `rolling::RollingBivariate` for the error
+/// over a window.
+/// # Examples
+/// ```
+/// use watermill::metrics::RMSE;
+/// use watermill::stats::Bivariate;
+/// let y_true: Vec<f64> = vec![3., -0.5, 2., 7.];
+/// let y_pred: Vec<f64> = vec![2.5, 0., 2., 8.];
+/// let mut rmse: RMSE<f64> = RMSE::new();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()){
+///     rmse.update(*yt, *yp);
+/// }
+/// assert_eq!(rmse.get(), 0.375_f64.sqrt());
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the root mean square deviation](https://www.wikiwand.com/en/Root-mean-square_deviation)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct RMSE<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub mse: MSE<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for RMSE<F> {
+    fn default() -> Self {
+        Self { mse: MSE::new() }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RMSE<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RMSE<F> {
+    fn update(&mut self, y_true: F, y_pred: F) {
+        self.mse.update(y_true, y_pred);
+    }
+    fn get(&self) -> F {
+        // Reverting can leave a tiny negative residue
+        self.mse.get().max(F::from_f64(0.).unwrap()).sqrt()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F> for RMSE<F> {
+    fn revert(&mut self, y_true: F, y_pred: F) -> Result<(), &'static str> {
+        self.mse.revert(y_true, y_pred)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::RollingBivariate;
+    const Y_TRUE: [f64; 6] = [1., 4., -2., 0., 3., 5.];
+    const Y_PRED: [f64; 6] = [2., 4., 1., -1., 3., 1.];
+    #[test]
+    fn hand_computed_errors() {
+        let mut mae: MAE<f64> = MAE::new();
+        let mut mse: MSE<f64> = MSE::new();
+        let mut rmse: RMSE<f64> = RMSE::new();
+        for (yt, yp) in Y_TRUE.iter().zip(Y_PRED.iter()) {
+            mae.update(*yt, *yp);
+            mse.update(*yt, *yp);
+            rmse.update(*yt, *yp);
+        }
+        // Errors 1, 0, 3, 1, 0, 4
+        assert!((mae.get() - 9. / 6.).abs() < 1e-12);
+        assert!((mse.get() - 27. / 6.).abs() < 1e-12);
+        assert!((rmse.get() - 4.5_f64.sqrt()).abs() < 1e-12);
+    }
+    #[test]
+    fn rolling_errors() {
+        let mut rolling_mae = RollingBivariate::new(MAE::<f64>::new(), 3).unwrap();
+        let mut rolling_mse = RollingBivariate::new(MSE::<f64>::new(), 3).unwrap();
+        let mut rolling_rmse = RollingBivariate::new(RMSE::<f64>::new(), 3).unwrap();
+        for (yt, yp) in Y_TRUE.iter().zip(Y_PRED.iter()) {
+            rolling_mae.update(*yt, *yp);
+            rolling_mse.update(*yt, *yp);
+            rolling_rmse.update(*yt, *yp);
+        }
+        // Errors 1, 0, 4 in the window
+        assert!((rolling_mae.get() - 5. / 3.).abs() < 1e-12);
+        assert!((rolling_mse.get() - 17. / 3.).abs() < 1e-12);
+        assert!((rolling_rmse.get() - (17. / 3_f64).sqrt()).abs() < 1e-12);
+        assert_eq!(rolling_mae.window_len(), 3);
+        assert!(RollingBivariate::new(MAE::<f64>::new(), 0).is_err());
+    }
+    #[test]
+    fn serde_roundtrip() {
+        let mut mse: MSE<f64> = MSE::new();
+        mse.update(1., 3.);
+        let serialized = serde_json::to_string(&mse).unwrap();
+        let mut deserialized: MSE<f64> = serde_json::from_str(&serialized).unwrap();
+        deserialized.update(2., 2.);
+        assert_eq!(deserialized.get(), 2.);
+    }
+}
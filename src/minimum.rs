@@ -1,8 +1,9 @@
-use crate::sorted_window::SortedWindow;
-use crate::stats::Univariate;
+use crate::sorted_window::{NanPolicy, SortedWindow};
+use crate::stats::{Mergeable, Univariate};
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 /// Running min.
 /// # Examples
 /// ```
@@ -18,12 +19,15 @@ use std::ops::{AddAssign, SubAssign};
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Min<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub min: F,
+    #[serde(default)]
+    n: usize,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Min<F> {
     fn default() -> Self {
         Self {
             min: F::max_value(),
+            n: 0,
         }
     }
 }
@@ -33,15 +37,162 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Min<F> {
     }
 }
 
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Min<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Min<F> {
     fn update(&mut self, x: F) {
         if self.min > x {
             self.min = x;
         }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.min
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.min)
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Min<F> {
+    fn merge(&mut self, other: &Self) {
+        if other.min < self.min {
+            self.min = other.min;
+        }
+        self.n += other.n;
+    }
+}
+
+/// Running min along with the index of the update at which it was observed.
+///
+/// Updates are numbered from `0` on, the first occurrence of the min is kept on ties.
+/// `NaN` values are skipped like in `Min` but still count as an update.
+/// # Examples
+/// ```
+/// use watermill::minimum::ArgMin;
+/// use watermill::stats::Univariate;
+/// let mut running_arg_min: ArgMin<f64> = ArgMin::new();
+/// for x in vec![8., 1., 4., 1., 9.].into_iter(){
+///     running_arg_min.update(x);
+/// }
+/// assert_eq!(running_arg_min.get(), 1.);
+/// assert_eq!(running_arg_min.arg(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ArgMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    min: F,
+    arg: Option<usize>,
+    n_updates: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for ArgMin<F> {
+    fn default() -> Self {
+        Self {
+            min: F::max_value(),
+            arg: None,
+            n_updates: 0,
+        }
+    }
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ArgMin<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Index of the update holding the min.
+    /// # Panics
+    /// Panics if no value was seen yet, see `try_arg`.
+    pub fn arg(&self) -> usize {
+        self.try_arg().expect("ArgMin has not seen any value")
+    }
+    /// Index of the update holding the min, or `None` if no value was seen yet.
+    pub fn try_arg(&self) -> Option<usize> {
+        self.arg
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for ArgMin<F> {
+    fn update(&mut self, x: F) {
+        if self.arg.is_none() && !x.is_nan() || x < self.min {
+            self.min = x;
+            self.arg = Some(self.n_updates);
+        }
+        self.n_updates += 1;
     }
     fn get(&self) -> F {
         self.min
     }
+    fn try_get(&self) -> Option<F> {
+        self.arg.map(|_| self.min)
+    }
+    fn n(&self) -> usize {
+        self.n_updates
+    }
+}
+
+/// Running absolute min.
+/// # Examples
+/// ```
+/// use watermill::minimum::AbsMin;
+/// use watermill::stats::Univariate;
+/// let mut running_abs_min: AbsMin<f64> = AbsMin::new();
+/// for i in -17..-3{
+///     running_abs_min.update(i as f64);
+/// }
+/// assert_eq!(running_abs_min.get(), 4.0);
+/// ```
+///
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AbsMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub abs_min: F,
+    #[serde(default)]
+    n: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for AbsMin<F> {
+    fn default() -> Self {
+        Self {
+            abs_min: F::max_value(),
+            n: 0,
+        }
+    }
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> AbsMin<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for AbsMin<F> {
+    fn update(&mut self, x: F) {
+        if self.abs_min > x.abs() {
+            self.abs_min = x.abs();
+        }
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        self.abs_min
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.abs_min)
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
 }
 
 /// Rolling min.
@@ -51,23 +202,34 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Min<F>
 /// ```
 /// use watermill::minimum::RollingMin;
 /// use watermill::stats::Univariate;
-/// let mut rolling_min: RollingMin<f64> = RollingMin::new(3);
+/// let mut rolling_min: RollingMin<f64> = RollingMin::new(3).unwrap();
 /// for i in 1..10{
 ///     rolling_min.update(i as f64);
 /// }
 /// assert_eq!(rolling_min.get(), 7.0);
 /// ```
 ///
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RollingMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
     sorted_window: SortedWindow<F>,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMin<F> {
-    pub fn new(window_size: usize) -> Self {
-        Self {
-            sorted_window: SortedWindow::new(window_size),
-        }
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_nan_policy(window_size, NanPolicy::Panic)
+    }
+    /// Same as `new`, with the given handling of `NaN` values.
+    pub fn with_nan_policy(
+        window_size: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::with_nan_policy(window_size, nan_policy)?,
+        })
+    }
+    /// Change the size of the window, see `sorted_window::SortedWindow::resize`.
+    pub fn resize(&mut self, window_size: usize) -> Result<(), &'static str> {
+        self.sorted_window.resize(window_size)
     }
 }
 
@@ -78,15 +240,254 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.sorted_window.front()
     }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.sorted_window.front())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+/// Rolling min along with the index, in the whole stream, of the update at which it was observed.
+///
+/// The window keeps the candidates for the min in increasing order, so an update costs `O(1)`
+/// amortized. When the update holding the min leaves the window, the index moves to the next
+/// candidate. The first occurrence of the min is kept on ties.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::minimum::RollingArgMin;
+/// use watermill::stats::Univariate;
+/// let mut rolling_arg_min: RollingArgMin<f64> = RollingArgMin::new(3).unwrap();
+/// for x in vec![2., 9., 1., 3., 5.].into_iter(){
+///     rolling_arg_min.update(x);
+/// }
+/// assert_eq!(rolling_arg_min.get(), 1.);
+/// assert_eq!(rolling_arg_min.arg(), 2);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingArgMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    // (update index, value), values increasing from front to back
+    candidates: VecDeque<(usize, F)>,
+    window_size: usize,
+    n_updates: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingArgMin<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            candidates: VecDeque::new(),
+            window_size,
+            n_updates: 0,
+        })
+    }
+    /// Index of the update holding the min of the window.
+    /// # Panics
+    /// Panics if no value was seen yet, see `try_arg`.
+    pub fn arg(&self) -> usize {
+        self.try_arg()
+            .expect("RollingArgMin has not seen any value")
+    }
+    /// Index of the update holding the min of the window, or `None` if no value was seen yet.
+    pub fn try_arg(&self) -> Option<usize> {
+        self.candidates.front().map(|(index, _)| *index)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingArgMin<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("RollingArgMin does not accept NaN values");
+        }
+        while self.candidates.back().is_some_and(|(_, value)| x < *value) {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.n_updates, x));
+        if let Some((index, _)) = self.candidates.front() {
+            if index + self.window_size <= self.n_updates {
+                self.candidates.pop_front();
+            }
+        }
+        self.n_updates += 1;
+    }
+    fn get(&self) -> F {
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        self.candidates.front().map(|(_, value)| *value)
+    }
+    fn n(&self) -> usize {
+        self.n_updates.min(self.window_size)
+    }
+}
+
+/// Rolling absolute min.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::minimum::RollingAbsMin;
+/// use watermill::stats::Univariate;
+/// let mut rolling_abs_min: RollingAbsMin<f64> = RollingAbsMin::new(3).unwrap();
+/// for i in -10..-3{
+///     rolling_abs_min.update(i as f64);
+/// }
+/// assert_eq!(rolling_abs_min.get(), 4.0);
+/// ```
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingAbsMin<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingAbsMin<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingAbsMin<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x.abs());
+    }
+    fn get(&self) -> F {
+        self.sorted_window.front()
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
+        Some(self.sorted_window.front())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     #[test]
+    fn try_get_max_value() {
+        let mut running_min: Min<f64> = Min::new();
+        assert_eq!(running_min.try_get(), None);
+        running_min.update(f64::MAX);
+        assert_eq!(running_min.try_get(), Some(f64::MAX));
+    }
+    #[test]
+    fn abs_min_try_get_max_value() {
+        let mut running_abs_min: AbsMin<f64> = AbsMin::new();
+        assert_eq!(running_abs_min.try_get(), None);
+        running_abs_min.update(f64::MAX);
+        assert_eq!(running_abs_min.try_get(), Some(f64::MAX));
+    }
+    #[test]
+    fn arg_min_of_sequence() {
+        let data = [3., 1., 4., 1., 5., 9., 2., 6., 5., 3., 5., 9.];
+        let mut arg_min: ArgMin<f64> = ArgMin::new();
+        assert_eq!(arg_min.try_arg(), None);
+        assert_eq!(arg_min.try_get(), None);
+        let expected = [0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        for (x, e) in data.iter().zip(expected) {
+            arg_min.update(*x);
+            assert_eq!(arg_min.arg(), e);
+            assert_eq!(arg_min.get(), data[e]);
+        }
+        arg_min.update(f64::NAN);
+        arg_min.update(0.);
+        assert_eq!(arg_min.arg(), 13);
+    }
+    #[test]
+    fn rolling_arg_min_leaves_window() {
+        let data = [3., 1., 4., 1., 5., 9., 2., 6., 5., 3., 5., 9.];
+        let mut rolling_arg_min: RollingArgMin<f64> = RollingArgMin::new(4).unwrap();
+        assert_eq!(rolling_arg_min.try_arg(), None);
+        assert!(rolling_arg_min.get().is_nan());
+        for i in 0..data.len() {
+            rolling_arg_min.update(data[i]);
+            // First index of the min of the window
+            let start = (i + 1).saturating_sub(4);
+            let mut expected = start;
+            for j in start..=i {
+                if data[j] < data[expected] {
+                    expected = j;
+                }
+            }
+            assert_eq!(rolling_arg_min.arg(), expected);
+            assert_eq!(rolling_arg_min.get(), data[expected]);
+        }
+        assert!(RollingArgMin::<f64>::new(0).is_err());
+    }
+    #[test]
     fn min_default() {
         let mut min: Min<f64> = Min::default();
         min.update(1.0);
         assert_eq!(min.get(), 1.0);
     }
+    #[test]
+    fn try_get_empty() {
+        let mut min: Min<f64> = Min::new();
+        let mut abs_min: AbsMin<f64> = AbsMin::new();
+        let mut rolling_min: RollingMin<f64> = RollingMin::new(2).unwrap();
+        let mut rolling_abs_min: RollingAbsMin<f64> = RollingAbsMin::new(2).unwrap();
+        assert_eq!(min.try_get(), None);
+        assert_eq!(abs_min.try_get(), None);
+        assert_eq!(rolling_min.try_get(), None);
+        assert_eq!(rolling_abs_min.try_get(), None);
+        min.update(-2.);
+        abs_min.update(-2.);
+        rolling_min.update(-2.);
+        rolling_abs_min.update(-2.);
+        assert_eq!(min.try_get(), Some(-2.));
+        assert_eq!(abs_min.try_get(), Some(2.));
+        assert_eq!(rolling_min.try_get(), Some(-2.));
+        assert_eq!(rolling_abs_min.try_get(), Some(2.));
+    }
+    #[test]
+    fn abs_min_negative_inputs() {
+        let mut abs_min: AbsMin<f64> = AbsMin::new();
+        for x in vec![-5., 8., -0.5, 7.5].into_iter() {
+            abs_min.update(x);
+        }
+        assert_eq!(abs_min.get(), 0.5);
+    }
+    #[test]
+    fn rolling_abs_min() {
+        let mut rolling_abs_min: RollingAbsMin<f64> = RollingAbsMin::new(2).unwrap();
+        let expected = [5., 5., 0.5, 0.5, 2.];
+        for (x, e) in vec![-5., 8., -0.5, 3., -2.].into_iter().zip(expected) {
+            rolling_abs_min.update(x);
+            assert_eq!(rolling_abs_min.get(), e);
+        }
+    }
+    #[test]
+    fn rolling_min_resize() {
+        let data: Vec<f64> = (0..50).map(|i| ((i * 17) % 23) as f64).collect();
+        for (filled, new_size) in [(3, 2), (3, 8), (20, 2), (20, 8)] {
+            let mut rolling_min: RollingMin<f64> = RollingMin::new(5).unwrap();
+            rolling_min.update_many(data[..filled].iter().copied());
+            rolling_min.resize(new_size).unwrap();
+            let retained = filled.min(5).min(new_size);
+            let mut fresh: RollingMin<f64> = RollingMin::new(new_size).unwrap();
+            fresh.update_many(data[filled - retained..filled].iter().copied());
+            for x in data[filled..].iter() {
+                assert_eq!(rolling_min.get(), fresh.get());
+                rolling_min.update(*x);
+                fresh.update(*x);
+            }
+            assert_eq!(rolling_min.n(), new_size);
+        }
+        assert!(RollingMin::<f64>::new(5).unwrap().resize(0).is_err());
+    }
 }
@@ -0,0 +1,236 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use crate::utils::{from_key, to_key};
+use serde::{Deserialize, Serialize};
+/// Running mode, the most frequently seen value.
+///
+/// Values are compared by their exact bit pattern, which makes `Mode` suited for discrete
+/// (e.g. categorical-coded) streams. When several values are tied, the earliest seen wins.
+/// `get` returns `NaN` if no value has been seen yet.
+/// # Examples
+/// ```
+/// use watermill::mode::Mode;
+/// use watermill::stats::Univariate;
+/// let mut running_mode: Mode<f64> = Mode::new();
+/// for x in vec![1., 2., 2., 3., 1., 2.].into_iter(){
+///     running_mode.update(x);
+/// }
+/// assert_eq!(running_mode.get(), 2.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Mode<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    // value key -> (count, order of first appearance)
+    counts: BTreeMap<u64, (usize, usize)>,
+    mode: F,
+    mode_count: usize,
+    mode_order: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Mode<F> {
+    fn default() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            mode: F::nan(),
+            mode_count: 0,
+            mode_order: 0,
+        }
+    }
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mode<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Mode<F> {
+    fn update(&mut self, x: F) {
+        let order = self.counts.len();
+        let (count, first_seen) = self.counts.entry(to_key(x)).or_insert((0, order));
+        *count += 1;
+        if *count > self.mode_count || (*count == self.mode_count && *first_seen < self.mode_order)
+        {
+            self.mode = x;
+            self.mode_count = *count;
+            self.mode_order = *first_seen;
+        }
+    }
+    fn get(&self) -> F {
+        self.mode
+    }
+    fn n(&self) -> usize {
+        self.counts.values().map(|(count, _)| count).sum()
+    }
+}
+
+/// Rolling mode, the most frequently seen value over a window.
+///
+/// The count of every distinct value of the window is kept next to the window, the count of the
+/// oldest value is decremented when it leaves the window and dropped once it reaches `0`, so the
+/// memory is bounded by `window_size`. `get` scans the counts, in `O(window_size)`. Values are
+/// compared by their exact bit pattern. When several values are tied, the smallest wins, which
+/// makes the mode independent of the order of the window. `get` returns `NaN` while the window
+/// is empty, use `try_get` to tell this case apart.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::mode::RollingMode;
+/// use watermill::stats::Univariate;
+/// let mut rolling_mode: RollingMode<f64> = RollingMode::new(3).unwrap();
+/// for x in vec![1., 1., 2., 3., 3.].into_iter(){
+///     rolling_mode.update(x);
+/// }
+/// assert_eq!(rolling_mode.get(), 3.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMode<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counts: BTreeMap<u64, usize>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMode<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            counts: BTreeMap::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMode<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = to_key(self.window.pop_front().expect("Window should not be empty"));
+            let count = self
+                .counts
+                .get_mut(&oldest)
+                .expect("Window values should be counted");
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&oldest);
+            }
+        }
+        self.window.push_back(x);
+        *self.counts.entry(to_key(x)).or_insert(0) += 1;
+    }
+    fn get(&self) -> F {
+        let mut mode = F::nan();
+        let mut mode_count = 0;
+        for (key, count) in self.counts.iter() {
+            let value: F = from_key(*key);
+            if *count > mode_count || (*count == mode_count && value < mode) {
+                mode = value;
+                mode_count = *count;
+            }
+        }
+        mode
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.window.is_empty() {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn earliest_seen_wins() {
+        let mut running_mode: Mode<f64> = Mode::new();
+        assert!(running_mode.get().is_nan());
+        for x in vec![3., 1., 1., 3.].into_iter() {
+            running_mode.update(x);
+        }
+        assert_eq!(running_mode.get(), 3.);
+        running_mode.update(1.);
+        assert_eq!(running_mode.get(), 1.);
+    }
+    #[test]
+    fn rolling_smallest_wins() {
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(4).unwrap();
+        assert!(rolling_mode.get().is_nan());
+        for x in vec![5., 2., 5., 2.].into_iter() {
+            rolling_mode.update(x);
+        }
+        assert_eq!(rolling_mode.get(), 2.);
+        // The first 5 leaves the window
+        rolling_mode.update(-1.);
+        assert_eq!(rolling_mode.get(), 2.);
+        assert_eq!(rolling_mode.counts.len(), 3);
+    }
+    #[test]
+    fn rolling_clear_mode() {
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(6).unwrap();
+        assert_eq!(rolling_mode.try_get(), None);
+        for x in vec![4., 7., 4., 1., 4., 7.].into_iter() {
+            rolling_mode.update(x);
+        }
+        assert_eq!(rolling_mode.try_get(), Some(4.));
+        assert_eq!(rolling_mode.n(), 6);
+    }
+    #[test]
+    fn rolling_eviction_changes_mode() {
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(4).unwrap();
+        for x in vec![9., 9., 9., 3.].into_iter() {
+            rolling_mode.update(x);
+        }
+        assert_eq!(rolling_mode.get(), 9.);
+        rolling_mode.update(5.);
+        assert_eq!(rolling_mode.get(), 9.);
+        // A 9 leaves the window, the mode switches to 3
+        rolling_mode.update(3.);
+        assert_eq!(rolling_mode.get(), 3.);
+        // Two 3s and two 5s, the smallest wins
+        rolling_mode.update(5.);
+        assert_eq!(rolling_mode.get(), 3.);
+        rolling_mode.update(5.);
+        assert_eq!(rolling_mode.get(), 5.);
+        // 9 has left the window and its count is dropped
+        assert_eq!(rolling_mode.counts.len(), 2);
+        assert!(!rolling_mode.counts.contains_key(&to_key(9.)));
+    }
+    #[test]
+    fn rolling_matches_window() {
+        let data: Vec<f64> = (0..300).map(|i| ((i * i + 3 * i) % 7) as f64).collect();
+        let mut rolling_mode: RollingMode<f64> = RollingMode::new(10).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_mode.update(*x);
+            let window = &data[(i + 1).saturating_sub(10)..=i];
+            let count = |v: &f64| window.iter().filter(|w| *w == v).count();
+            let expected = window
+                .iter()
+                .copied()
+                .reduce(|a, b| {
+                    if count(&b) > count(&a) || (count(&b) == count(&a) && b < a) {
+                        b
+                    } else {
+                        a
+                    }
+                })
+                .unwrap();
+            assert_eq!(rolling_mode.get(), expected);
+            assert_eq!(rolling_mode.counts.values().sum::<usize>(), window.len());
+        }
+    }
+    #[test]
+    fn signed_zero() {
+        let mut running_mode: Mode<f64> = Mode::new();
+        for x in vec![1., -0., 0.].into_iter() {
+            running_mode.update(x);
+        }
+        assert_eq!(running_mode.get(), 0.);
+    }
+}
@@ -1,5 +1,5 @@
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::count::Count;
 use crate::stats::Univariate;
@@ -65,4 +65,32 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> CentralMoments<F> {
             + F::from_f64(6.).unwrap() * delta_square * self.m2
             - F::from_f64(4.).unwrap() * self.delta * self.m3
     }
+    // The revert steps undo the update steps above, they expect `count` to be already reverted
+    // and must be called in the reverse order: delta, sum_delta, m1, m2, m3 and finally m4.
+    pub fn revert_delta(&mut self, x: F) {
+        self.delta = (x - self.sum_delta) / self.count.get()
+    }
+    pub fn revert_sum_delta(&mut self) {
+        self.sum_delta -= self.delta
+    }
+    pub fn revert_m1(&mut self, x: F) {
+        self.m1 = (x - self.sum_delta) * self.delta * self.count.get()
+    }
+    pub fn revert_m2(&mut self) {
+        self.m2 -= self.m1
+    }
+    pub fn revert_m3(&mut self) {
+        self.m3 -= self.m1 * self.delta * (self.count.get() - F::from_f64(1.).unwrap())
+            - F::from_f64(3.).unwrap() * self.delta * self.m2
+    }
+    pub fn revert_m4(&mut self) {
+        let delta_square = self.delta.powf(F::from_f64(2.).unwrap());
+        let n = self.count.get() + F::from_f64(1.).unwrap();
+        self.m4 -= self.m1
+            * delta_square
+            * (n.powf(F::from_f64(2.).unwrap()) - F::from_f64(3.).unwrap() * n
+                + F::from_f64(3.).unwrap())
+            + F::from_f64(6.).unwrap() * delta_square * self.m2
+            - F::from_f64(4.).unwrap() * self.delta * self.m3
+    }
 }
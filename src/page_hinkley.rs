@@ -0,0 +1,154 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::Mean;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Page-Hinkley change detector.
+///
+/// Accumulates the deviations of the values from their running mean, minus the tolerance `delta`.
+/// A change is signaled as soon as the cumulative deviation departs from its extremum by more than
+/// `lambda`, upwards or downwards. A detection does not reset the test, call `reset` to start
+/// monitoring the stream after the change.
+/// # Arguments
+/// * `lambda` - Detection threshold, must be positive. Defaults to `50`.
+/// * `delta` - Magnitude of the changes that are tolerated, must not be negative. Defaults to `0.005`.
+/// # Examples
+/// ```
+/// use watermill::page_hinkley::PageHinkley;
+/// let mut page_hinkley: PageHinkley<f64> = PageHinkley::new(10., 0.05).unwrap();
+/// let mut detection = None;
+/// for i in 0..200{
+///     let x = if i < 100 { (i % 2) as f64 } else { 2. + (i % 2) as f64 };
+///     if page_hinkley.update(x) && detection.is_none(){
+///         detection = Some(i);
+///     }
+/// }
+/// let detection = detection.unwrap();
+/// assert!(detection >= 100 && detection < 120);
+/// page_hinkley.reset();
+/// assert!(!page_hinkley.update(2.));
+/// ```
+/// # References
+/// [^1]: [Page, E.S., 1954. Continuous inspection schemes. Biometrika, 41(1/2), pp.100-115.](https://www.jstor.org/stable/2333009)
+///
+/// [^2]: [Gama, J., Sebastiao, R. and Rodrigues, P.P., 2013. On evaluating stream learning algorithms. Machine learning, 90(3), pp.317-346.](https://link.springer.com/article/10.1007/s10994-012-5320-9)
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct PageHinkley<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub lambda: F,
+    pub delta: F,
+    mean: Mean<F>,
+    // Cumulative deviations of the test for an increase and for a decrease of the mean
+    sum_increase: F,
+    min_increase: F,
+    sum_decrease: F,
+    max_decrease: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PageHinkley<F> {
+    pub fn new(lambda: F, delta: F) -> Result<Self, &'static str> {
+        if !(lambda > F::from_f64(0.).unwrap() && lambda.is_finite()) {
+            return Err("lambda should be positive and finite");
+        }
+        if !(delta >= F::from_f64(0.).unwrap() && delta.is_finite()) {
+            return Err("delta should be finite and not negative");
+        }
+        Ok(Self {
+            lambda,
+            delta,
+            mean: Mean::new(),
+            sum_increase: F::from_f64(0.).unwrap(),
+            min_increase: F::from_f64(0.).unwrap(),
+            sum_decrease: F::from_f64(0.).unwrap(),
+            max_decrease: F::from_f64(0.).unwrap(),
+        })
+    }
+    /// Add `x` to the test and return whether a change was detected.
+    /// # Panics
+    /// Panics on `NaN` values.
+    pub fn update(&mut self, x: F) -> bool {
+        if x.is_nan() {
+            panic!("PageHinkley does not accept NaN values");
+        }
+        self.mean.update(x);
+        let deviation = x - self.mean.get();
+        self.sum_increase += deviation - self.delta;
+        self.min_increase = self.min_increase.min(self.sum_increase);
+        self.sum_decrease += deviation + self.delta;
+        self.max_decrease = self.max_decrease.max(self.sum_decrease);
+        self.sum_increase - self.min_increase > self.lambda
+            || self.max_decrease - self.sum_decrease > self.lambda
+    }
+    /// Forget every value seen, typically after a detection.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.lambda, self.delta).unwrap();
+    }
+}
+
+impl<F> Default for PageHinkley<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(50.).unwrap(), F::from_f64(0.005).unwrap()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Uniform values in [0, 1)
+    fn uniform_sample(n: usize, seed: u64) -> Vec<f64> {
+        let mut state: u64 = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+    // Index of the first detection
+    fn first_detection(page_hinkley: &mut PageHinkley<f64>, data: Vec<f64>) -> Option<usize> {
+        let mut detection = None;
+        for (i, x) in data.into_iter().enumerate() {
+            if page_hinkley.update(x) && detection.is_none() {
+                detection = Some(i);
+            }
+        }
+        detection
+    }
+    #[test]
+    fn known_changepoint() {
+        for shift in [1., -1.] {
+            let mut page_hinkley: PageHinkley<f64> = PageHinkley::default();
+            let mut data = uniform_sample(1000, 1);
+            data.extend(uniform_sample(1000, 2).into_iter().map(|x| x + shift));
+            let detection = first_detection(&mut page_hinkley, data).unwrap();
+            assert!((1000..1100).contains(&detection));
+            // The detection holds until the reset
+            assert!(page_hinkley.update(0.5 + shift));
+            page_hinkley.reset();
+            assert!(!page_hinkley.update(0.5 + shift));
+        }
+    }
+    #[test]
+    fn stationary_stream() {
+        let mut page_hinkley: PageHinkley<f64> = PageHinkley::default();
+        assert_eq!(
+            first_detection(&mut page_hinkley, uniform_sample(20_000, 3)),
+            None
+        );
+        // A lower threshold is more sensitive to the noise
+        let mut sensitive: PageHinkley<f64> = PageHinkley::new(1., 0.).unwrap();
+        assert!(first_detection(&mut sensitive, uniform_sample(20_000, 3)).is_some());
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(PageHinkley::<f64>::new(0., 0.005).is_err());
+        assert!(PageHinkley::<f64>::new(f64::NAN, 0.005).is_err());
+        assert!(PageHinkley::<f64>::new(50., -1.).is_err());
+    }
+}
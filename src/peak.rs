@@ -0,0 +1,153 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::RollingMean;
+use crate::stats::Univariate;
+use crate::std_dev::RollingStandardDeviation;
+use serde::{Deserialize, Serialize};
+
+/// Smoothed z-score peak detector.
+///
+/// A value is a peak when it deviates from the rolling mean of the window by more than
+/// `threshold` rolling population standard deviations. `update` signals `1` for a peak above the
+/// mean, `-1` for a peak below it and `0` otherwise. A flagged value enters the window as
+/// `influence * x + (1 - influence) * previous`, where `previous` is the latest value of the
+/// window, so that peaks do not inflate the statistics they are compared to. With `influence = 0`
+/// peaks are ignored entirely, with `influence = 1` they enter the window unchanged and a
+/// sustained shift is absorbed as fast as possible. Nothing is signaled until the window is full.
+/// # Arguments
+/// * `window_size` - Number of values the mean and the standard deviation are computed on.
+/// * `threshold` - Number of standard deviations a peak deviates from the mean by, must be positive.
+/// * `influence` - Weight of a flagged value in the window, must be in `[0, 1]`.
+/// # Examples
+/// ```
+/// use watermill::peak::ZScorePeakDetector;
+/// let mut detector: ZScorePeakDetector<f64> = ZScorePeakDetector::new(5, 3., 0.).unwrap();
+/// let mut signals = Vec::new();
+/// for x in vec![1., 1.1, 0.9, 1., 1.1, 5., 1., 0.9, -3., 1.].into_iter(){
+///     signals.push(detector.update(x));
+/// }
+/// assert_eq!(signals, vec![0, 0, 0, 0, 0, 1, 0, 0, -1, 0]);
+/// ```
+/// # References
+/// [^1]: [van Brakel, J.P.G., 2014. Robust peak detection algorithm using z-scores. Stack Overflow.](https://stackoverflow.com/questions/22583391/peak-signal-detection-in-realtime-timeseries-data/22640362#22640362)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZScorePeakDetector<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub threshold: F,
+    pub influence: F,
+    mean: RollingMean<F>,
+    std: RollingStandardDeviation<F>,
+    window_size: usize,
+    n: usize,
+    // Latest value entered in the window, after dampening
+    previous: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ZScorePeakDetector<F> {
+    pub fn new(window_size: usize, threshold: F, influence: F) -> Result<Self, &'static str> {
+        if !(threshold > F::from_f64(0.).unwrap() && threshold.is_finite()) {
+            return Err("threshold should be positive and finite");
+        }
+        if !(influence >= F::from_f64(0.).unwrap() && influence <= F::from_f64(1.).unwrap()) {
+            return Err("influence should be between 0 and 1");
+        }
+        Ok(Self {
+            threshold,
+            influence,
+            mean: RollingMean::new(window_size)?,
+            std: RollingStandardDeviation::new(window_size, 0)?,
+            window_size,
+            n: 0,
+            previous: F::from_f64(0.).unwrap(),
+        })
+    }
+    /// Add `x` to the window and return its signal, `1`, `-1` or `0`.
+    /// # Panics
+    /// Panics on `NaN` values.
+    pub fn update(&mut self, x: F) -> i8 {
+        if x.is_nan() {
+            panic!("ZScorePeakDetector does not accept NaN values");
+        }
+        let mut signal = 0;
+        let mut filtered = x;
+        if self.n == self.window_size {
+            let deviation = x - self.mean.get();
+            if deviation.abs() > self.threshold * self.std.get() {
+                signal = if deviation > F::from_f64(0.).unwrap() {
+                    1
+                } else {
+                    -1
+                };
+                filtered = self.influence * x
+                    + (F::from_f64(1.).unwrap() - self.influence) * self.previous;
+            }
+        } else {
+            self.n += 1;
+        }
+        self.mean.update(filtered);
+        self.std.update(filtered);
+        self.previous = filtered;
+        signal
+    }
+    /// Rolling mean of the window the next value is compared to.
+    pub fn mean(&self) -> F {
+        self.mean.get()
+    }
+    /// Rolling population standard deviation of the window the next value is compared to.
+    pub fn std(&self) -> F {
+        self.std.get()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn noise(n: usize) -> Vec<f64> {
+        let mut state: u64 = 11;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64 - 0.5
+            })
+            .collect()
+    }
+    #[test]
+    fn injected_spikes() {
+        let mut signal = noise(500);
+        let spikes = [(100, 8.), (230, -6.), (231, -7.), (420, 10.)];
+        for (i, height) in spikes.iter() {
+            signal[*i] += height;
+        }
+        let mut detector: ZScorePeakDetector<f64> = ZScorePeakDetector::new(30, 5., 0.).unwrap();
+        for (i, x) in signal.iter().enumerate() {
+            let expected = spikes
+                .iter()
+                .find(|(j, _)| *j == i)
+                .map_or(0, |(_, height)| if *height > 0. { 1 } else { -1 });
+            assert_eq!(detector.update(*x), expected, "sample {}", i);
+        }
+    }
+    #[test]
+    fn sustained_shift_is_absorbed() {
+        let signal: Vec<f64> = noise(600)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| if i < 200 { *x } else { x + 10. })
+            .collect();
+        let mut detector: ZScorePeakDetector<f64> = ZScorePeakDetector::new(30, 4., 0.5).unwrap();
+        let signals: Vec<i8> = signal.iter().map(|x| detector.update(*x)).collect();
+        assert!(signals[..200].iter().all(|s| *s == 0));
+        assert_eq!(signals[200], 1);
+        assert!(signals[400..].iter().all(|s| *s == 0));
+        assert!((detector.mean() - 10.).abs() < 0.2);
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(ZScorePeakDetector::<f64>::new(0, 3., 0.5).is_err());
+        assert!(ZScorePeakDetector::<f64>::new(10, 0., 0.5).is_err());
+        assert!(ZScorePeakDetector::<f64>::new(10, 3., 1.5).is_err());
+        assert!(ZScorePeakDetector::<f64>::new(10, 3., f64::NAN).is_err());
+    }
+}
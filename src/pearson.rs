@@ -0,0 +1,200 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::covariance::Covariance;
+use crate::stats::{Bivariate, Revertable, RevertableBivariate, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running Pearson correlation.
+///
+/// When one of the variables has a zero variance the correlation is undefined, `get` returns `0` in that case.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom used by the underlying covariance and variances.
+/// # Examples
+/// ```
+/// use watermill::pearson::PearsonCorrelation;
+/// use watermill::stats::Bivariate;
+/// let x: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let y: Vec<f64> = vec![2.1, 3.9, 6.2, 7.8, 10.1];
+/// let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+/// let mut running_anti_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     running_corr.update(*xi, *yi);
+///     running_anti_corr.update(*xi, -*yi);
+/// }
+/// assert!(running_corr.get() > 0.99);
+/// assert!(running_anti_corr.get() < -0.99);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Pearson correlation coefficient](https://www.wikiwand.com/en/Pearson_correlation_coefficient)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PearsonCorrelation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub cov: Covariance<F>,
+    pub var_x: Variance<F>,
+    pub var_y: Variance<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PearsonCorrelation<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            cov: Covariance::new(ddof),
+            var_x: Variance::new(ddof),
+            var_y: Variance::new(ddof),
+        }
+    }
+}
+
+impl<F> Default for PearsonCorrelation<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            cov: Covariance::default(),
+            var_x: Variance::default(),
+            var_y: Variance::default(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for PearsonCorrelation<F> {
+    fn update(&mut self, x: F, y: F) {
+        self.cov.update(x, y);
+        self.var_x.update(x);
+        self.var_y.update(y);
+    }
+    fn get(&self) -> F {
+        let var_x = self.var_x.get();
+        let var_y = self.var_y.get();
+        // Reverts can leave a constant series with a tiny negative variance
+        if var_x <= F::from_f64(0.).unwrap() || var_y <= F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        self.cov.get() / (var_x * var_y).sqrt()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RevertableBivariate<F>
+    for PearsonCorrelation<F>
+{
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str> {
+        self.cov.revert(x, y)?;
+        self.var_x.revert(x)?;
+        self.var_y.revert(y)?;
+        Ok(())
+    }
+}
+
+/// Rolling Pearson correlation.
+///
+/// The oldest `(x, y)` pair is reverted once more than `window_size` pairs have been seen.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::pearson::RollingPearsonCorrelation;
+/// use watermill::stats::Bivariate;
+/// let mut rolling_corr: RollingPearsonCorrelation<f64> = RollingPearsonCorrelation::new(3).unwrap();
+/// let x: Vec<f64> = vec![1., 2., 3., 4., 5.];
+/// let y: Vec<f64> = vec![1., 2., 3., 2., 1.];
+/// for (xi, yi) in x.iter().zip(y.iter()){
+///     rolling_corr.update(*xi, *yi);
+/// }
+/// assert!((rolling_corr.get() + 1.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingPearsonCorrelation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    corr: PearsonCorrelation<F>,
+    window: VecDeque<(F, F)>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingPearsonCorrelation<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            corr: PearsonCorrelation::default(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F>
+    for RollingPearsonCorrelation<F>
+{
+    fn update(&mut self, x: F, y: F) {
+        if self.window.len() == self.window_size {
+            let (oldest_x, oldest_y) = self.window.pop_front().expect("Window should not be empty");
+            // Every pair in the window has been seen by `corr`, so the revert cannot fail
+            self.corr
+                .revert(oldest_x, oldest_y)
+                .expect("Window pairs should be revertable");
+        }
+        self.window.push_back((x, y));
+        self.corr.update(x, y);
+    }
+    fn get(&self) -> F {
+        self.corr.get()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn zero_variance() {
+        let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+        for i in 0..10 {
+            running_corr.update(i as f64, 3.);
+        }
+        assert_eq!(running_corr.get(), 0.);
+    }
+    #[test]
+    fn matches_numpy_corrcoef() {
+        let x: Vec<f64> = vec![0.5, -1.2, 3.3, 2.0, 0.0, 4.1, -0.7];
+        let y: Vec<f64> = vec![1.0, 0.4, 2.2, 2.5, -0.3, 3.9, 0.1];
+        let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::new(0);
+        for (xi, yi) in x.iter().zip(y.iter()) {
+            running_corr.update(*xi, *yi);
+        }
+        // numpy.corrcoef(x, y)[0][1]
+        assert!((running_corr.get() - 0.9187685227906205).abs() < 1e-12);
+    }
+    #[test]
+    fn rolling_tracks_reversal() {
+        let mut rolling_corr: RollingPearsonCorrelation<f64> =
+            RollingPearsonCorrelation::new(5).unwrap();
+        let mut running_corr: PearsonCorrelation<f64> = PearsonCorrelation::default();
+        // An upward trend followed by a short reversal
+        let y: Vec<f64> = (0..20).chain((14..20).rev()).map(|v| v as f64).collect();
+        for (x, y) in y.iter().enumerate() {
+            rolling_corr.update(x as f64, *y);
+            running_corr.update(x as f64, *y);
+            if x == 19 {
+                assert!((rolling_corr.get() - 1.).abs() < 1e-9);
+            }
+        }
+        assert!((rolling_corr.get() + 1.).abs() < 1e-9);
+        assert!(running_corr.get() > 0.);
+    }
+    #[test]
+    fn rolling_constant_after_trend() {
+        let mut rolling_corr: RollingPearsonCorrelation<f64> =
+            RollingPearsonCorrelation::new(4).unwrap();
+        for i in 0..10 {
+            rolling_corr.update(i as f64, 0.3 * i as f64);
+        }
+        for i in 10..20 {
+            rolling_corr.update(i as f64, 0.7);
+            assert!(!rolling_corr.get().is_nan());
+        }
+        assert_eq!(rolling_corr.get(), 0.);
+    }
+    #[test]
+    fn rolling_window_size_zero() {
+        assert!(RollingPearsonCorrelation::<f64>::new(0).is_err());
+    }
+}
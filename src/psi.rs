@@ -0,0 +1,246 @@
+use alloc::{vec, vec::Vec};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::histogram::Histogram;
+use crate::stats::{Revertable, Univariate};
+use serde::{Deserialize, Serialize};
+
+// Floor of the proportions, an empty bin would make the logarithm infinite
+const EPSILON: f64 = 1e-4;
+
+/// Population stability index of the stream against a reference distribution.
+///
+/// The values are grouped in bins delimited by `edges`, `k` increasing edges give the `k + 1`
+/// bins `(-inf, e_0), [e_0, e_1), ..., [e_{k-1}, +inf)`. With `p_ref` the proportions of the
+/// reference in each bin and `p_cur` those of the stream, `get` returns
+/// `Σ (p_cur - p_ref) * ln(p_cur / p_ref)`. Proportions are floored at `1e-4` so empty bins
+/// stay finite. A PSI below `0.1` is usually read as stable and above `0.25` as a significant shift.
+/// `get` returns `NaN` until a value is seen.
+/// # Examples
+/// ```
+/// use watermill::psi::PSI;
+/// use watermill::stats::Univariate;
+/// let reference: Vec<f64> = (0..1000).map(|i| (i % 100) as f64).collect();
+/// let mut psi: PSI<f64> = PSI::from_reference(vec![25., 50., 75.], reference).unwrap();
+/// for i in 0..1000{
+///     psi.update((i % 100) as f64);
+/// }
+/// assert!(psi.get().abs() < 1e-12);
+/// for _ in 0..1000{
+///     psi.update(99.);
+/// }
+/// assert!(psi.get() > 0.25);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+/// # References
+/// [^1]: [Yurdakul, B., 2018. Statistical properties of population stability index. Western Michigan University.](https://scholarworks.wmich.edu/dissertations/3208/)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PSI<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    edges: Vec<F>,
+    reference: Vec<F>,
+    counts: Vec<usize>,
+    n: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> PSI<F> {
+    /// PSI against the proportions of the `reference` values in the bins delimited by `edges`.
+    pub fn from_reference<I: IntoIterator<Item = F>>(
+        edges: Vec<F>,
+        reference: I,
+    ) -> Result<Self, &'static str> {
+        let mut psi = Self::with_edges(edges)?;
+        for x in reference {
+            psi.update(x);
+        }
+        if psi.n == 0 {
+            return Err("reference should not be empty");
+        }
+        psi.reference = psi.proportions();
+        psi.counts.iter_mut().for_each(|count| *count = 0);
+        psi.n = 0;
+        Ok(psi)
+    }
+    /// PSI against a reference `Histogram`, binned in `bin_count` bins of equal estimated mass.
+    pub fn from_histogram(
+        histogram: &Histogram<F>,
+        bin_count: usize,
+    ) -> Result<Self, &'static str> {
+        if bin_count < 2 {
+            return Err("bin_count should be greater or equal to 2");
+        }
+        if histogram.count() == 0 {
+            return Err("reference should not be empty");
+        }
+        let mut edges: Vec<F> = Vec::with_capacity(bin_count - 1);
+        for i in 1..bin_count {
+            let edge =
+                histogram.quantile(F::from_usize(i).unwrap() / F::from_usize(bin_count).unwrap());
+            // Edges collapse where the reference has ties
+            if edges.last().is_none_or(|last| *last < edge) {
+                edges.push(edge);
+            }
+        }
+        let mut psi = Self::with_edges(edges)?;
+        let mut previous = F::from_f64(0.).unwrap();
+        for (i, edge) in psi.edges.iter().enumerate() {
+            let cdf = histogram.cdf(*edge);
+            psi.reference[i] = cdf - previous;
+            previous = cdf;
+        }
+        let last = psi.edges.len();
+        psi.reference[last] = F::from_f64(1.).unwrap() - previous;
+        Ok(psi)
+    }
+    fn with_edges(edges: Vec<F>) -> Result<Self, &'static str> {
+        if edges.is_empty() {
+            return Err("edges should not be empty");
+        }
+        if !edges.iter().all(|edge| edge.is_finite())
+            || !edges.windows(2).all(|pair| pair[0] < pair[1])
+        {
+            return Err("edges should be finite and strictly increasing");
+        }
+        let bin_count = edges.len() + 1;
+        Ok(Self {
+            edges,
+            reference: vec![F::from_f64(0.).unwrap(); bin_count],
+            counts: vec![0; bin_count],
+            n: 0,
+        })
+    }
+    fn bin(&self, x: F) -> usize {
+        self.edges.partition_point(|edge| *edge <= x)
+    }
+    fn proportions(&self) -> Vec<F> {
+        let n = F::from_usize(self.n).unwrap();
+        self.counts
+            .iter()
+            .map(|count| F::from_usize(*count).unwrap() / n)
+            .collect()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PSI<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("PSI does not accept NaN values");
+        }
+        let bin = self.bin(x);
+        self.counts[bin] += 1;
+        self.n += 1;
+    }
+    fn get(&self) -> F {
+        if self.n == 0 {
+            return F::nan();
+        }
+        let epsilon = F::from_f64(EPSILON).unwrap();
+        let mut psi = F::from_f64(0.).unwrap();
+        for (current, reference) in self.proportions().into_iter().zip(self.reference.iter()) {
+            let current = current.max(epsilon);
+            let reference = reference.max(epsilon);
+            psi += (current - reference) * (current / reference).ln();
+        }
+        psi
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for PSI<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        let bin = self.bin(x);
+        if self.counts[bin] == 0 {
+            return Err("The value was not counted");
+        }
+        self.counts[bin] -= 1;
+        self.n -= 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    // Normally distributed values from the Box-Muller transform
+    fn normal_sample(n: usize, seed: u64, shift: f64) -> Vec<f64> {
+        let mut state: u64 = seed;
+        let mut uniform = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        };
+        (0..n)
+            .map(|_| {
+                let (u, v) = (uniform(), uniform());
+                shift + (-2. * u.ln()).sqrt() * (2. * std::f64::consts::PI * v).cos()
+            })
+            .collect()
+    }
+    #[test]
+    fn identical_and_shifted_distributions() {
+        let edges: Vec<f64> = (-4..=4).map(|i| i as f64 * 0.5).collect();
+        let reference = normal_sample(20_000, 1, 0.);
+        let mut same: PSI<f64> = PSI::from_reference(edges.clone(), reference.clone()).unwrap();
+        let mut shifted: PSI<f64> = PSI::from_reference(edges, reference.clone()).unwrap();
+        assert_eq!(same.try_get(), None);
+        for (x, y) in normal_sample(20_000, 2, 0.)
+            .into_iter()
+            .zip(normal_sample(20_000, 3, 1.))
+        {
+            same.update(x);
+            shifted.update(y);
+        }
+        assert!(same.get() < 0.01);
+        assert!(shifted.get() > 0.5);
+        // Binning on the quantiles of a reference histogram
+        let mut histogram: Histogram<f64> = Histogram::new(64).unwrap();
+        for x in reference.into_iter() {
+            histogram.update(x);
+        }
+        let mut same: PSI<f64> = PSI::from_histogram(&histogram, 10).unwrap();
+        let mut shifted: PSI<f64> = PSI::from_histogram(&histogram, 10).unwrap();
+        for (x, y) in normal_sample(20_000, 4, 0.)
+            .into_iter()
+            .zip(normal_sample(20_000, 5, 1.))
+        {
+            same.update(x);
+            shifted.update(y);
+        }
+        assert!(same.get() < 0.01);
+        assert!(shifted.get() > 0.5);
+    }
+    #[test]
+    fn rolling_psi_recovers() {
+        let reference = normal_sample(10_000, 6, 0.);
+        let mut psi: PSI<f64> = PSI::from_reference(vec![-1., 0., 1.], reference).unwrap();
+        let mut rolling_psi = Rolling::new(&mut psi, 5000).unwrap();
+        for x in normal_sample(5000, 7, 2.) {
+            rolling_psi.update(x);
+        }
+        assert!(rolling_psi.get() > 0.5);
+        // The shifted values leave the window
+        for x in normal_sample(5000, 8, 0.) {
+            rolling_psi.update(x);
+        }
+        assert!(rolling_psi.get() < 0.01);
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(PSI::<f64>::from_reference(vec![], vec![1.]).is_err());
+        assert!(PSI::<f64>::from_reference(vec![1., 1.], vec![1.]).is_err());
+        assert!(PSI::<f64>::from_reference(vec![1.], vec![]).is_err());
+        let histogram: Histogram<f64> = Histogram::new(8).unwrap();
+        assert!(PSI::<f64>::from_histogram(&histogram, 4).is_err());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::maximum::{Max, RollingMax};
 use crate::minimum::{Min, RollingMin};
-use crate::stats::Univariate;
+use crate::sorted_window::{NanPolicy, DEFAULT_WINDOW_SIZE};
+use crate::stats::{Mergeable, Univariate};
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 /// Running peak to peak (max - min).
 /// # Examples
 /// ```
@@ -34,6 +35,28 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> PeakToPeak<F> {
     pub fn new() -> Self {
         Self::default()
     }
+    /// The `(min, max)` values seen so far.
+    /// # Examples
+    /// ```
+    /// use watermill::ptp::PeakToPeak;
+    /// use watermill::stats::Univariate;
+    /// let mut running_peak_to_peak: PeakToPeak<f64> = PeakToPeak::new();
+    /// for x in vec![3., -2., 8., 5.].into_iter(){
+    ///     running_peak_to_peak.update(x);
+    /// }
+    /// assert_eq!(running_peak_to_peak.bounds(), (-2., 8.));
+    /// ```
+    pub fn bounds(&self) -> (F, F) {
+        (self.min.get(), self.max.get())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for PeakToPeak<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PeakToPeak<F> {
@@ -44,34 +67,74 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for PeakToP
     fn get(&self) -> F {
         self.max.get() - self.min.get()
     }
+    fn try_get(&self) -> Option<F> {
+        Some(self.max.try_get()? - self.min.try_get()?)
+    }
+    fn n(&self) -> usize {
+        self.max.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for PeakToPeak<F> {
+    fn merge(&mut self, other: &Self) {
+        self.min.merge(&other.min);
+        self.max.merge(&other.max);
+    }
 }
 
 /// Rolling peak to peak (max - min).
 /// # Arguments
-/// * `window_size` - Size of the rolling window.
+/// * `window_size` - Size of the rolling window. Defaults to `sorted_window::DEFAULT_WINDOW_SIZE` (`100`).
 /// # Examples
 /// ```
 /// use watermill::ptp::RollingPeakToPeak;
 /// use watermill::stats::Univariate;
-/// let mut rolling_peak_to_peak: RollingPeakToPeak<f64> = RollingPeakToPeak::new(3);
+/// let mut rolling_peak_to_peak: RollingPeakToPeak<f64> = RollingPeakToPeak::new(3).unwrap();
 /// for i in 1..10{
 ///     rolling_peak_to_peak.update(i as f64);
 /// }
 /// assert_eq!(rolling_peak_to_peak.get(), 2.0);
 /// ```
 ///
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RollingPeakToPeak<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub min: RollingMin<F>,
     pub max: RollingMax<F>,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingPeakToPeak<F> {
-    pub fn new(window_size: usize) -> Self {
-        Self {
-            min: RollingMin::new(window_size),
-            max: RollingMax::new(window_size),
-        }
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_nan_policy(window_size, NanPolicy::Panic)
+    }
+    /// Same as `new`, with the given handling of `NaN` values.
+    pub fn with_nan_policy(
+        window_size: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Self, &'static str> {
+        Ok(Self {
+            min: RollingMin::with_nan_policy(window_size, nan_policy)?,
+            max: RollingMax::with_nan_policy(window_size, nan_policy)?,
+        })
+    }
+    /// The `(min, max)` values of the window.
+    /// # Examples
+    /// ```
+    /// use watermill::ptp::RollingPeakToPeak;
+    /// use watermill::stats::Univariate;
+    /// let mut rolling_peak_to_peak: RollingPeakToPeak<f64> = RollingPeakToPeak::new(2).unwrap();
+    /// for x in vec![3., -2., 8., 5.].into_iter(){
+    ///     rolling_peak_to_peak.update(x);
+    /// }
+    /// assert_eq!(rolling_peak_to_peak.bounds(), (5., 8.));
+    /// ```
+    pub fn bounds(&self) -> (F, F) {
+        (self.min.get(), self.max.get())
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for RollingPeakToPeak<F> {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_SIZE).unwrap()
     }
 }
 
@@ -83,4 +146,93 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
     fn get(&self) -> F {
         self.max.get() - self.min.get()
     }
+    fn try_get(&self) -> Option<F> {
+        Some(self.max.try_get()? - self.min.try_get()?)
+    }
+    fn n(&self) -> usize {
+        self.max.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn merge() {
+        let data: Vec<f64> = vec![3., -2., 8., 5., -4., 1., 7.];
+        for split in 0..=data.len() {
+            let mut single_pass: PeakToPeak<f64> = PeakToPeak::new();
+            let mut left: PeakToPeak<f64> = PeakToPeak::new();
+            let mut right: PeakToPeak<f64> = PeakToPeak::new();
+            for (i, x) in data.iter().enumerate() {
+                single_pass.update(*x);
+                if i < split {
+                    left.update(*x);
+                } else {
+                    right.update(*x);
+                }
+            }
+            left.merge(&right);
+            assert_eq!(left.bounds(), (-4., 8.));
+            assert_eq!(left.get(), single_pass.get());
+        }
+    }
+    #[test]
+    fn try_get_empty() {
+        let mut peak_to_peak: PeakToPeak<f64> = PeakToPeak::new();
+        let mut rolling_peak_to_peak: RollingPeakToPeak<f64> = RollingPeakToPeak::new(2).unwrap();
+        assert_eq!(peak_to_peak.try_get(), None);
+        assert_eq!(rolling_peak_to_peak.try_get(), None);
+        peak_to_peak.update(1.);
+        rolling_peak_to_peak.update(1.);
+        assert_eq!(peak_to_peak.try_get(), Some(0.));
+        assert_eq!(rolling_peak_to_peak.try_get(), Some(0.));
+    }
+    #[test]
+    fn rolling_nan_policy() {
+        let mut skip: RollingPeakToPeak<f64> =
+            RollingPeakToPeak::with_nan_policy(2, NanPolicy::Skip).unwrap();
+        let mut propagate: RollingPeakToPeak<f64> =
+            RollingPeakToPeak::with_nan_policy(2, NanPolicy::Propagate).unwrap();
+        for x in [1., 4., f64::NAN] {
+            skip.update(x);
+            propagate.update(x);
+        }
+        assert_eq!(skip.get(), 3.);
+        assert_eq!(skip.min.get(), 1.);
+        // The max of a window holding NaN is NaN
+        assert!(propagate.get().is_nan());
+        assert_eq!(propagate.min.get(), 4.);
+        propagate.update(2.);
+        propagate.update(5.);
+        assert_eq!(propagate.get(), 3.);
+    }
+    #[test]
+    fn window_size_zero() {
+        assert!(RollingMin::<f64>::new(0).is_err());
+        assert!(RollingMax::<f64>::new(0).is_err());
+        assert_eq!(
+            RollingPeakToPeak::<f64>::new(0).err(),
+            Some("Window size should not equal to 0")
+        );
+        assert!(RollingPeakToPeak::<f64>::with_nan_policy(0, NanPolicy::Skip).is_err());
+    }
+    #[test]
+    fn rolling_default() {
+        let mut rolling_peak_to_peak: RollingPeakToPeak<f64> = RollingPeakToPeak::default();
+        for i in 0..=200 {
+            rolling_peak_to_peak.update(i as f64);
+        }
+        assert_eq!(rolling_peak_to_peak.get(), 99.);
+    }
+    #[test]
+    fn collect() {
+        let data: Vec<f64> = vec![3., -2., 8., 5., -4., 1., 7.];
+        let peak_to_peak: PeakToPeak<f64> = data.iter().copied().collect();
+        assert_eq!(peak_to_peak.get(), 12.);
+        let min: Min<f64> = data.iter().copied().collect();
+        assert_eq!(min.get(), -4.);
+        let max: Max<f64> = data.iter().copied().collect();
+        assert_eq!(max.get(), 8.);
+    }
 }
@@ -1,8 +1,9 @@
-use crate::sorted_window::SortedWindow;
+use crate::sorted_window::{NanPolicy, SortedWindow, DEFAULT_WINDOW_SIZE};
+use alloc::{vec, vec::Vec};
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive, ToPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
-use crate::stats::Univariate;
+use crate::stats::{Mergeable, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running quantile estimator using P-square Algorithm.
 /// # Arguments
@@ -35,8 +36,8 @@ pub struct Quantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Quantile<F> {
     pub fn new(q: F) -> Result<Self, &'static str> {
-        if F::from_f64(0.).unwrap() > q && F::from_f64(1.).unwrap() < q {
-            return Err("q should be betweek 0 and 1");
+        if !(q >= F::from_f64(0.).unwrap() && q <= F::from_f64(1.).unwrap()) {
+            return Err("q should be between 0 and 1");
         }
         Ok(Self {
             q,
@@ -59,6 +60,10 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Quantile<F> {
             heights_sorted: false,
         })
     }
+    /// Builder of a `Quantile`, starting from the median.
+    pub fn builder() -> QuantileBuilder<F> {
+        QuantileBuilder::default()
+    }
     fn find_k(&mut self, x: F) -> usize {
         let mut k: Option<usize> = None;
         if x < self.heights[0] {
@@ -80,6 +85,58 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Quantile<F> {
         }
         k.unwrap_or(4)
     }
+    /// The five `(position, height)` markers of the P² algorithm, from the min to the max.
+    ///
+    /// Positions are `1`-based ranks among the values seen so far. Until five values are seen, the
+    /// heights are the sorted values and the missing ones are `NaN`.
+    pub fn markers(&self) -> [(F, F); 5] {
+        let mut markers = [(F::nan(), F::nan()); 5];
+        for (i, marker) in markers.iter_mut().enumerate() {
+            *marker = (
+                self.position[i],
+                self.heights.get(i).copied().unwrap_or_else(F::nan),
+            );
+        }
+        markers
+    }
+    /// Estimate any quantile `q` by linear interpolation between the markers surrounding its rank.
+    ///
+    /// Only the middle marker tracks the configured quantile, the other ones track `q / 2` and
+    /// `(1 + q) / 2` besides the min and the max. The estimate is as accurate as `get` for the
+    /// configured quantile and degrades further away from the markers, especially in the tails of
+    /// skewed distributions where the density changes a lot between two markers. Use `MultiQuantile`
+    /// when several quantiles need accurate estimates. Returns `NaN` if `q` is not in `[0, 1]` or
+    /// if no value was seen yet.
+    pub fn quantile(&self, q: F) -> F {
+        let zero = F::from_f64(0.).unwrap();
+        let one = F::from_f64(1.).unwrap();
+        if !(q >= zero && q <= one) || self.heights.is_empty() {
+            return F::nan();
+        }
+        // Until five values are seen, the positions are the ranks of the sorted values
+        let positions = &self.position[..self.heights.len()];
+        let n = positions[positions.len() - 1];
+        let rank = one + q * (n - one);
+        for i in 1..positions.len() {
+            if rank <= positions[i] {
+                let frac = (rank - positions[i - 1]) / (positions[i] - positions[i - 1]);
+                return self.heights[i - 1] + frac * (self.heights[i] - self.heights[i - 1]);
+            }
+        }
+        self.heights[self.heights.len() - 1]
+    }
+    // Approximate number of values lower or equal to `x`, interpolated linearly between the markers
+    fn rank(&self, x: F) -> F {
+        let positions = &self.position[..self.heights.len()];
+        match self.heights.iter().rposition(|height| *height <= x) {
+            None => F::from_f64(0.).unwrap(),
+            Some(i) if i == positions.len() - 1 => positions[i],
+            Some(i) => {
+                let frac = (x - self.heights[i]) / (self.heights[i + 1] - self.heights[i]);
+                positions[i] + frac * (positions[i + 1] - positions[i])
+            }
+        }
+    }
     fn compute_p2(qp1: F, q: F, qm1: F, d: F, np1: F, n: F, nm1: F) -> F {
         let outer = d / (np1 - nm1);
         let inner_left = (n - nm1 + d) * (qp1 - q) / (np1 - n);
@@ -151,16 +208,54 @@ where
     }
 }
 
+/// Builder of a `Quantile`, the options are validated by `build`.
+/// # Examples
+/// ```
+/// use watermill::quantile::Quantile;
+/// use watermill::stats::Univariate;
+/// let mut running_quantile: Quantile<f64> = Quantile::builder().q(0.9).build().unwrap();
+/// for i in 0..1000{
+///     running_quantile.update(i as f64);
+/// }
+/// assert!((running_quantile.get() - 900.).abs() < 10.);
+/// assert!(Quantile::<f64>::builder().q(1.5).build().is_err());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct QuantileBuilder<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    q: F,
+}
+
+impl<F> Default for QuantileBuilder<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            q: F::from_f64(0.5).unwrap(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> QuantileBuilder<F> {
+    /// Quantile value, must be between `0` and `1`.
+    pub fn q(mut self, q: F) -> Self {
+        self.q = q;
+        self
+    }
+    pub fn build(self) -> Result<Quantile<F>, &'static str> {
+        Quantile::new(self.q)
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantile<F> {
     fn update(&mut self, x: F) {
-        // Initialisation
+        // Initialisation, the heights are only sorted while the first five values are collected
         if self.heights.len() != 5 {
             self.heights.push(x);
+            self.heights.sort_by(|x, y| x.partial_cmp(y).unwrap());
         } else {
-            if !self.heights_sorted {
-                self.heights.sort_by(|x, y| x.partial_cmp(y).unwrap());
-                self.heights_sorted = true;
-            }
+            // From now on `adjust` keeps the heights ordered and aligned with `position`
+            self.heights_sorted = true;
             // Find cell k such that qk < Xj <= qk+i and adjust extreme values (q1 and q) if necessary
             let k = self.find_k(x);
 
@@ -180,7 +275,6 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantil
             }
             self.adjust();
         }
-        self.heights.sort_by(|x, y| x.partial_cmp(y).unwrap());
     }
     fn get(&self) -> F {
         if self.heights_sorted {
@@ -196,12 +290,116 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantil
             self.heights[index]
         }
     }
+    fn try_get(&self) -> Option<F> {
+        if self.heights.is_empty() {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        if self.heights_sorted {
+            // The last marker sits at the position of the largest value
+            self.position[4].to_usize().unwrap()
+        } else {
+            self.heights.len()
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Quantile<F> {
+    /// Approximate merge, the quantile of `self` is kept.
+    ///
+    /// The P² markers do not hold enough information for an exact merge. Each estimator is seen as
+    /// the piecewise linear approximation of the distribution its markers draw, the ranks of both
+    /// are added up and the five markers are placed again on the combined ranks. The min and the
+    /// max are exact, and the values of an estimator that saw at most five values are replayed
+    /// exactly. The other markers carry the interpolation error of both estimators, which grows
+    /// with the curvature of the distribution between markers. On a normal distribution split in
+    /// up to `10` shards, the rank of the merged estimate is within `0.5%` of the single pass one.
+    /// Use `tdigest::TDigest` when shards have to be merged accurately.
+    fn merge(&mut self, other: &Self) {
+        if !other.heights_sorted {
+            // Markers are still the raw values
+            for x in other.heights.iter() {
+                self.update(*x);
+            }
+            return;
+        }
+        let one = F::from_f64(1.).unwrap();
+        let mut breakpoints: Vec<F> = self
+            .heights
+            .iter()
+            .chain(other.heights.iter())
+            .copied()
+            .collect();
+        breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let ranks: Vec<F> = breakpoints
+            .iter()
+            .map(|x| self.rank(*x) + other.rank(*x))
+            .collect();
+        // Value whose combined rank is `rank`, the ranks are linear between breakpoints
+        let value_at = |rank: F| -> F {
+            let i = ranks.partition_point(|r| *r < rank);
+            if i == 0 {
+                return breakpoints[0];
+            }
+            if i == ranks.len() {
+                return breakpoints[i - 1];
+            }
+            let frac = (rank - ranks[i - 1]) / (ranks[i] - ranks[i - 1]);
+            breakpoints[i - 1] + frac * (breakpoints[i] - breakpoints[i - 1])
+        };
+        let n = F::from_usize(self.n() + other.n()).unwrap();
+        let mut position = vec![one; 5];
+        let mut heights = vec![breakpoints[0]; 5];
+        for i in 0..5 {
+            self.marker_position[i] = one + self.desired_marker_position[i] * (n - one);
+            // Positions are distinct ranks, leaving room for the markers above
+            let lowest = if i == 0 { one } else { position[i - 1] + one };
+            let highest = n - F::from_usize(4 - i).unwrap();
+            position[i] = self.marker_position[i].round().max(lowest).min(highest);
+            heights[i] = value_at(position[i]);
+        }
+        heights[0] = breakpoints[0];
+        heights[4] = breakpoints[breakpoints.len() - 1];
+        self.position = position;
+        self.heights = heights;
+        self.heights_sorted = true;
+    }
+}
+
+// Positions in a sorted window of `len` values that the linear interpolation of the quantile `q`
+// uses, as `(lower, higher, frac)`: the quantile is `window[lower]` plus `frac` times the step
+// to `window[higher]`. `higher` stays within the window, where it equals `lower` and `frac` is 0.
+pub(crate) fn interpolation<F: Float + FromPrimitive>(q: F, len: usize) -> (usize, usize, F) {
+    let last = len.saturating_sub(1);
+    let idx = q * F::from_usize(last).unwrap();
+    let lower = idx.floor().to_usize().unwrap().min(last);
+    let higher = (lower + 1).min(last);
+    if higher == lower {
+        return (lower, higher, F::from_f64(0.).unwrap());
+    }
+    (lower, higher, idx - F::from_usize(lower).unwrap())
+}
+
+// Quantile of a non empty sorted window at the positions given by `interpolation`
+pub(crate) fn interpolate<F: Float + FromPrimitive + AddAssign + SubAssign>(
+    sorted_window: &SortedWindow<F>,
+    (lower, higher, frac): (usize, usize, F),
+) -> F {
+    // Skipping the step avoids `inf - inf` when the values are infinite
+    if frac == F::from_f64(0.).unwrap() {
+        return sorted_window[lower];
+    }
+    sorted_window[lower] + (sorted_window[higher] - sorted_window[lower]) * frac
 }
 
 /// Rolling quantile.
 /// # Arguments
 /// * `q` - quantile value. **WARNING** Should between `0` and `1`.
 /// * `window_size` - Size of the rolling window.
+///
+/// The default is the median over a window of `sorted_window::DEFAULT_WINDOW_SIZE` (`100`) values.
 /// # Examples
 /// ```
 /// use watermill::quantile::RollingQuantile;
@@ -214,9 +412,11 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Quantil
 /// }
 /// assert_eq!(rolling_quantile.get(), 50.0);
 /// ```
+/// `get` returns `NaN` while the window is empty, use `try_get` to tell this case apart.
 ///
+/// `NaN` values panic by default, `with_nan_policy` allows skipping or storing them, see `sorted_window::NanPolicy`.
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RollingQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
     sorted_window: SortedWindow<F>,
     q: F,
@@ -228,19 +428,26 @@ pub struct RollingQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingQuantile<F> {
     pub fn new(q: F, window_size: usize) -> Result<Self, &'static str> {
-        if F::from_f64(0.).unwrap() > q && F::from_f64(1.).unwrap() < q {
-            return Err("q should be betweek 0 and 1");
-        }
-        let idx = q * (F::from_usize(window_size).unwrap() - F::from_f64(1.).unwrap());
-        let lower = idx.floor().to_usize().unwrap();
-        let mut higher = lower + 1;
-        if higher > window_size - 1 {
-            higher = lower.saturating_sub(1); // Avoid attempt to subtract with overflow
+        Self::with_nan_policy(q, window_size, NanPolicy::Panic)
+    }
+    /// Builder of a `RollingQuantile`, starting from the default median over
+    /// `sorted_window::DEFAULT_WINDOW_SIZE` values that panics on `NaN` values.
+    pub fn builder() -> RollingQuantileBuilder<F> {
+        RollingQuantileBuilder::default()
+    }
+    /// Same as `new`, with the given handling of `NaN` values.
+    pub fn with_nan_policy(
+        q: F,
+        window_size: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Self, &'static str> {
+        if !(q >= F::from_f64(0.).unwrap() && q <= F::from_f64(1.).unwrap()) {
+            return Err("q should be between 0 and 1");
         }
-
-        let frac = idx - F::from_usize(lower).unwrap();
+        let sorted_window = SortedWindow::with_nan_policy(window_size, nan_policy)?;
+        let (lower, higher, frac) = interpolation(q, window_size);
         Ok(Self {
-            sorted_window: SortedWindow::new(window_size),
+            sorted_window,
             q,
             window_size,
             lower,
@@ -248,20 +455,121 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingQuantile<F> {
             frac,
         })
     }
-    fn prepare(&self) -> (usize, usize, F) {
+    /// Change the size of the window. Growing keeps every value, shrinking evicts the oldest
+    /// values until at most `window_size` are left.
+    pub fn resize(&mut self, window_size: usize) -> Result<(), &'static str> {
+        self.sorted_window.resize(window_size)?;
+        (self.lower, self.higher, self.frac) = interpolation(self.q, window_size);
+        self.window_size = window_size;
+        Ok(())
+    }
+    /// Interpolation behind the value `get` returns for the current window, as
+    /// `(lower_index, higher_index, frac, lower_value, higher_value)`.
+    ///
+    /// The indices are positions in the sorted window, the quantile is
+    /// `lower_value + frac * (higher_value - lower_value)`. While the window is partially filled,
+    /// they are computed on the values seen so far. When the rank of the quantile falls exactly on
+    /// a value, `frac` is `0` and `higher_value` is not used. Returns `None` while the window is empty.
+    /// # Examples
+    /// ```
+    /// use watermill::quantile::RollingQuantile;
+    /// use watermill::stats::Univariate;
+    /// let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.3, 5).unwrap();
+    /// for x in vec![40., 10., 30., 20., 50.].into_iter(){
+    ///     rolling_quantile.update(x);
+    /// }
+    /// // The rank is 0.3 * 4 = 1.2 in the sorted window 10, 20, 30, 40, 50
+    /// let (lower, higher, frac, lower_value, higher_value) = rolling_quantile.last_interpolation().unwrap();
+    /// assert_eq!((lower, higher, lower_value, higher_value), (1, 2, 20., 30.));
+    /// assert!((frac - 0.2).abs() < 1e-12);
+    /// assert!((rolling_quantile.get() - 22.).abs() < 1e-12);
+    /// ```
+    pub fn last_interpolation(&self) -> Option<(usize, usize, F, F, F)> {
+        let (lower, higher, frac) = self.prepare()?;
+        Some((
+            lower,
+            higher,
+            frac,
+            self.sorted_window[lower],
+            self.sorted_window[higher],
+        ))
+    }
+    fn prepare(&self) -> Option<(usize, usize, F)> {
+        if self.sorted_window.is_empty() {
+            return None;
+        }
         if self.sorted_window.len() < self.window_size {
-            let idx = self.q
-                * (F::from_usize(self.sorted_window.len()).unwrap() - F::from_f64(1.).unwrap());
-            let lower = idx.floor().to_usize().unwrap();
-            let mut higher = lower + 1;
-            if higher > self.sorted_window.len() - 1 {
-                higher = self.sorted_window.len().saturating_sub(1); // Avoid attempt to subtract with overflow
-            }
+            return Some(interpolation(self.q, self.sorted_window.len()));
+        }
+        Some((self.lower, self.higher, self.frac))
+    }
+}
 
-            let frac = idx - F::from_usize(lower).unwrap();
-            return (lower, higher, frac);
+impl<F> Default for RollingQuantile<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(0.5).unwrap(), DEFAULT_WINDOW_SIZE).unwrap()
+    }
+}
+
+/// Builder of a `RollingQuantile`, the options are validated by `build`.
+/// # Examples
+/// ```
+/// use watermill::quantile::RollingQuantile;
+/// use watermill::sorted_window::NanPolicy;
+/// use watermill::stats::Univariate;
+/// let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::builder()
+///     .q(0.9)
+///     .window_size(11)
+///     .nan_policy(NanPolicy::Skip)
+///     .build()
+///     .unwrap();
+/// for i in 0..=10{
+///     rolling_quantile.update(i as f64);
+///     rolling_quantile.update(f64::NAN);
+/// }
+/// assert_eq!(rolling_quantile.get(), 9.);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RollingQuantileBuilder<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    q: F,
+    window_size: usize,
+    nan_policy: NanPolicy,
+}
+
+impl<F> Default for RollingQuantileBuilder<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            q: F::from_f64(0.5).unwrap(),
+            window_size: DEFAULT_WINDOW_SIZE,
+            nan_policy: NanPolicy::default(),
         }
-        (self.lower, self.higher, self.frac)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingQuantileBuilder<F> {
+    /// Quantile value, must be between `0` and `1`.
+    pub fn q(mut self, q: F) -> Self {
+        self.q = q;
+        self
+    }
+    /// Size of the rolling window, must not be `0`.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+    /// Handling of `NaN` values, see `sorted_window::NanPolicy`.
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+    pub fn build(self) -> Result<RollingQuantile<F>, &'static str> {
+        RollingQuantile::with_nan_policy(self.q, self.window_size, self.nan_policy)
     }
 }
 
@@ -270,12 +578,569 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Rolling
         self.sorted_window.push_back(x);
     }
     fn get(&self) -> F {
-        let (lower, higher, frac) = self.prepare();
-        self.sorted_window[lower] + (self.sorted_window[higher] - self.sorted_window[lower]) * frac
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        Some(interpolate(&self.sorted_window, self.prepare()?))
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+/// Rolling median, the `0.5` `RollingQuantile` under a clearer name.
+///
+/// When the window holds an even number of values the median is the average of the two middle ones.
+/// `get` returns `NaN` while the window is empty, use `try_get` to tell this case apart.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::quantile::RollingMedian;
+/// use watermill::stats::Univariate;
+/// let mut rolling_median: RollingMedian<f64> = RollingMedian::new(3).unwrap();
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     rolling_median.update(x);
+/// }
+/// // The window holds 8, 5 and 4
+/// assert_eq!(rolling_median.get(), 5.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMedian<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    rolling_quantile: RollingQuantile<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMedian<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            rolling_quantile: RollingQuantile::new(F::from_f64(0.5).unwrap(), window_size)?,
+        })
+    }
+    /// Change the size of the window, see `RollingQuantile::resize`.
+    pub fn resize(&mut self, window_size: usize) -> Result<(), &'static str> {
+        self.rolling_quantile.resize(window_size)
+    }
+}
+
+impl<F> Default for RollingMedian<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_SIZE).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMedian<F> {
+    fn update(&mut self, x: F) {
+        self.rolling_quantile.update(x);
+    }
+    fn get(&self) -> F {
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        let sorted_window = &self.rolling_quantile.sorted_window;
+        let len = sorted_window.len();
+        if len == 0 {
+            return None;
+        }
+        if len.is_multiple_of(2) {
+            return Some(
+                (sorted_window[len / 2 - 1] + sorted_window[len / 2]) / F::from_f64(2.).unwrap(),
+            );
+        }
+        Some(sorted_window[len / 2])
+    }
+    fn n(&self) -> usize {
+        self.rolling_quantile.n()
+    }
+}
+
+/// Running estimator of several quantiles sharing a single pass, using the extended P-square Algorithm.
+///
+/// The markers of every quantile are kept in one structure: for `m` inner quantiles `2m + 3`
+/// markers track the minimum, each quantile, the midpoints between consecutive quantiles and the
+/// maximum. Each update costs `O(m)`, and with a single quantile the estimates are the ones of
+/// `Quantile`. The quantiles `0` and `1` are the minimum and the maximum.
+/// # Arguments
+/// * `quantiles` - quantile values, between `0` and `1`. Duplicates are ignored.
+/// # Examples
+/// ```
+/// use watermill::quantile::MultiQuantile;
+/// let mut running_quantiles: MultiQuantile<f64> = MultiQuantile::new(&[0.25, 0.5, 0.75, 0.99]).unwrap();
+/// for i in 0..1000{
+///     running_quantiles.update(((i * 37) % 1000) as f64);
+/// }
+/// assert!((running_quantiles.get(0.5) - 500.).abs() < 10.);
+/// assert!((running_quantiles.get(0.99) - 990.).abs() < 10.);
+/// assert_eq!(running_quantiles.get_all().len(), 4);
+/// assert!(running_quantiles.get(0.1).is_nan());
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+/// # References
+/// [^1]: [Raatikainen, K.E.E., 1987. Simultaneous estimation of several percentiles. Simulation, 49(4), pp.159-163.](https://journals.sagepub.com/doi/10.1177/003754978704900405)
+///
+/// [^2]: [The P² Algorithm for Dynamic Calculation of Quantiles and Histograms Without Storing Observations](https://www.cse.wustl.edu/~jain/papers/ftp/psqr.pdf)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MultiQuantile<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    quantiles: Vec<F>,
+    // Probability tracked by each marker
+    probabilities: Vec<F>,
+    desired_marker_position: Vec<F>,
+    position: Vec<F>,
+    heights: Vec<F>,
+    heights_sorted: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MultiQuantile<F> {
+    pub fn new(quantiles: &[F]) -> Result<Self, &'static str> {
+        if quantiles.is_empty() {
+            return Err("quantiles should not be empty");
+        }
+        let zero = F::from_f64(0.).unwrap();
+        let one = F::from_f64(1.).unwrap();
+        let two = F::from_f64(2.).unwrap();
+        if !quantiles.iter().all(|q| *q >= zero && *q <= one) {
+            return Err("q should be between 0 and 1");
+        }
+        let mut quantiles = quantiles.to_vec();
+        quantiles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        quantiles.dedup();
+        let mut probabilities = vec![zero];
+        let mut previous = zero;
+        for q in quantiles.iter().filter(|q| **q > zero && **q < one) {
+            probabilities.push((previous + *q) / two);
+            probabilities.push(*q);
+            previous = *q;
+        }
+        probabilities.push((previous + one) / two);
+        probabilities.push(one);
+        let last = F::from_usize(probabilities.len() - 1).unwrap();
+        Ok(Self {
+            quantiles,
+            desired_marker_position: probabilities.iter().map(|p| one + last * *p).collect(),
+            position: (1..=probabilities.len())
+                .map(|x| F::from_usize(x).unwrap())
+                .collect(),
+            probabilities,
+            heights: Vec::new(),
+            heights_sorted: false,
+        })
+    }
+    /// Add `x` to every quantile estimate.
+    pub fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("MultiQuantile does not accept NaN values");
+        }
+        let markers = self.probabilities.len();
+        // Initialisation, the heights are only sorted while the first values are collected
+        if self.heights.len() != markers {
+            self.heights.push(x);
+            self.heights.sort_by(|x, y| x.partial_cmp(y).unwrap());
+            return;
+        }
+        self.heights_sorted = true;
+        // Find cell k such that q_{k-1} <= x < q_k and adjust the extreme values if necessary
+        let k = if x < self.heights[0] {
+            self.heights[0] = x;
+            1
+        } else {
+            match (1..markers).find(|i| self.heights[i - 1] <= x && x < self.heights[*i]) {
+                Some(k) => k,
+                None => {
+                    self.heights[markers - 1] = self.heights[markers - 1].max(x);
+                    markers - 1
+                }
+            }
+        };
+        for value in self.position.iter_mut().skip(k) {
+            *value += F::from_f64(1.).unwrap();
+        }
+        for (marker, probability) in self
+            .desired_marker_position
+            .iter_mut()
+            .zip(self.probabilities.iter())
+        {
+            *marker += *probability;
+        }
+        self.adjust();
+    }
+    fn adjust(&mut self) {
+        let one = F::from_f64(1.).unwrap();
+        for i in 1..self.probabilities.len() - 1 {
+            let n = self.position[i];
+            let q = self.heights[i];
+            let mut d = self.desired_marker_position[i] - n;
+            if (d >= one && self.position[i + 1] - n > one)
+                || (d <= -one && self.position[i - 1] - n < -one)
+            {
+                d = one.copysign(d);
+                let qp1 = self.heights[i + 1];
+                let qm1 = self.heights[i - 1];
+                let np1 = self.position[i + 1];
+                let nm1 = self.position[i - 1];
+                let qn = Quantile::compute_p2(qp1, q, qm1, d, np1, n, nm1);
+                if qm1 < qn && qn < qp1 {
+                    self.heights[i] = qn;
+                } else {
+                    let linear_index = if d > F::from_f64(0.).unwrap() {
+                        i + 1
+                    } else {
+                        i - 1
+                    };
+                    self.heights[i] = q + d * (self.heights[linear_index] - q)
+                        / (self.position[linear_index] - n);
+                }
+                self.position[i] = n + d;
+            }
+        }
+    }
+    /// Estimated `q` quantile, `NaN` if `q` is not one of the estimated quantiles or no value was
+    /// seen.
+    pub fn get(&self, q: F) -> F {
+        if self.heights.is_empty() || !self.quantiles.contains(&q) {
+            return F::nan();
+        }
+        if !self.heights_sorted {
+            let length = F::from_usize(self.heights.len()).unwrap();
+            let index = (length - F::from_f64(1.).unwrap())
+                .max(F::from_f64(0.).unwrap())
+                .min(length * q)
+                .to_usize()
+                .unwrap();
+            return self.heights[index];
+        }
+        let marker = self
+            .probabilities
+            .iter()
+            .rposition(|p| *p == q)
+            .expect("Every quantile has a marker");
+        self.heights[marker]
+    }
+    /// Every `(q, estimate)` pair, in increasing order of `q`.
+    pub fn get_all(&self) -> Vec<(F, F)> {
+        self.quantiles.iter().map(|q| (*q, self.get(*q))).collect()
     }
 }
+
 #[cfg(test)]
 mod test {
+    #[test]
+    fn rolling_median_even_window() {
+        use crate::quantile::{RollingMedian, RollingQuantile};
+        use crate::stats::Univariate;
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut rolling_median: RollingMedian<f64> = RollingMedian::new(4).unwrap();
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.5_f64, 4).unwrap();
+        assert_eq!(rolling_median.try_get(), None);
+        for (i, x) in data.iter().enumerate() {
+            rolling_median.update(*x);
+            rolling_quantile.update(*x);
+            let mut window = data[(i + 1).saturating_sub(4)..=i].to_vec();
+            window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let len = window.len();
+            let expected = if len.is_multiple_of(2) {
+                (window[len / 2 - 1] + window[len / 2]) / 2.
+            } else {
+                window[len / 2]
+            };
+            assert_eq!(rolling_median.get(), expected);
+            assert_eq!(rolling_median.get(), rolling_quantile.get());
+        }
+        // The window holds 1, 8, 5 and 4
+        assert_eq!(rolling_median.get(), 4.5);
+        assert!(RollingMedian::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn multi_quantile_matches_quantile() {
+        use crate::quantile::{MultiQuantile, Quantile};
+        use crate::stats::Univariate;
+        let mut state: u64 = 11;
+        let data: Vec<f64> = (0..5000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+            })
+            .collect();
+        let quantiles = [0.25, 0.5, 0.75, 0.99];
+        let mut multi_quantile: MultiQuantile<f64> = MultiQuantile::new(&quantiles).unwrap();
+        let mut single: MultiQuantile<f64> = MultiQuantile::new(&[0.75]).unwrap();
+        let mut individual: Vec<Quantile<f64>> = quantiles
+            .iter()
+            .map(|q| Quantile::new(*q).unwrap())
+            .collect();
+        assert!(multi_quantile.get(0.5).is_nan());
+        for x in data.iter() {
+            multi_quantile.update(*x);
+            single.update(*x);
+            for quantile in individual.iter_mut() {
+                quantile.update(*x);
+            }
+            // A single quantile is the P-square estimate
+            assert!((single.get(0.75) - individual[2].get()).abs() < 1e-12);
+        }
+        for ((q, estimate), quantile) in multi_quantile.get_all().into_iter().zip(individual.iter())
+        {
+            assert!((estimate - quantile.get()).abs() < 0.01);
+            assert!((estimate - q).abs() < 0.02);
+        }
+    }
+    #[test]
+    fn multi_quantile_extremes() {
+        use crate::quantile::MultiQuantile;
+        let mut multi_quantile: MultiQuantile<f64> =
+            MultiQuantile::new(&[1., 0.5, 0., 0.5]).unwrap();
+        for i in 0..100 {
+            multi_quantile.update(((i * 7) % 100) as f64);
+        }
+        assert_eq!(
+            multi_quantile
+                .get_all()
+                .iter()
+                .map(|p| p.0)
+                .collect::<Vec<f64>>(),
+            vec![0., 0.5, 1.]
+        );
+        assert_eq!(multi_quantile.get(0.), 0.);
+        assert_eq!(multi_quantile.get(1.), 99.);
+        assert!(MultiQuantile::<f64>::new(&[]).is_err());
+        assert!(MultiQuantile::<f64>::new(&[0.5, 1.5]).is_err());
+    }
+    #[test]
+    fn rolling_quantile_partially_filled() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        // Linear interpolation between the closest ranks of the sorted values
+        fn percentile(values: &[f64], q: f64) -> f64 {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let rank = q * (sorted.len() - 1) as f64;
+            let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+        }
+        let data: Vec<f64> = (0..40).map(|i| ((i * 17) % 23) as f64 - 5.).collect();
+        for window_size in [1, 2, 3, 7, 40] {
+            for q in [0., 0.1, 0.25, 0.5, 0.75, 0.9, 1.] {
+                let mut rolling_quantile: RollingQuantile<f64> =
+                    RollingQuantile::new(q, window_size).unwrap();
+                for (i, x) in data.iter().enumerate() {
+                    rolling_quantile.update(*x);
+                    let window = &data[(i + 1).saturating_sub(window_size)..=i];
+                    let expected = percentile(window, q);
+                    assert!(
+                        (rolling_quantile.get() - expected).abs() < 1e-12,
+                        "q = {}, window_size = {}, {} values",
+                        q,
+                        window_size,
+                        i + 1
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn rolling_quantile_infinite_values() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut rolling_max: RollingQuantile<f64> = RollingQuantile::new(1., 3).unwrap();
+        let mut rolling_min: RollingQuantile<f64> = RollingQuantile::new(0., 3).unwrap();
+        for x in [
+            f64::INFINITY,
+            1.,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            rolling_max.update(x);
+            rolling_min.update(x);
+        }
+        // The window holds inf, -inf and -inf
+        assert_eq!(rolling_max.get(), f64::INFINITY);
+        assert_eq!(rolling_min.get(), f64::NEG_INFINITY);
+    }
+    #[test]
+    fn builders_match_constructors() {
+        use crate::quantile::{Quantile, RollingQuantile};
+        use crate::sorted_window::NanPolicy;
+        use crate::stats::Univariate;
+        let mut running_quantile: Quantile<f64> = Quantile::new(0.25).unwrap();
+        let mut built_quantile: Quantile<f64> = Quantile::builder().q(0.25).build().unwrap();
+        let mut rolling_quantile: RollingQuantile<f64> =
+            RollingQuantile::with_nan_policy(0.75, 20, NanPolicy::Propagate).unwrap();
+        let mut built_rolling_quantile: RollingQuantile<f64> = RollingQuantile::builder()
+            .nan_policy(NanPolicy::Propagate)
+            .window_size(20)
+            .q(0.75)
+            .build()
+            .unwrap();
+        let mut default_rolling_quantile: RollingQuantile<f64> = RollingQuantile::default();
+        let mut built_default: RollingQuantile<f64> = RollingQuantile::builder().build().unwrap();
+        for i in 0..300 {
+            let x = if i % 50 == 7 {
+                f64::NAN
+            } else {
+                ((i * 31) % 97) as f64
+            };
+            running_quantile.update_if_finite(x);
+            built_quantile.update_if_finite(x);
+            rolling_quantile.update(x);
+            built_rolling_quantile.update(x);
+            default_rolling_quantile.update_if_finite(x);
+            built_default.update_if_finite(x);
+            assert_eq!(running_quantile.get(), built_quantile.get());
+            assert_eq!(
+                rolling_quantile.get().to_bits(),
+                built_rolling_quantile.get().to_bits()
+            );
+            assert_eq!(default_rolling_quantile.get(), built_default.get());
+        }
+        assert!(Quantile::<f64>::builder().q(-0.1).build().is_err());
+        assert!(RollingQuantile::<f64>::builder()
+            .window_size(0)
+            .build()
+            .is_err());
+        assert!(RollingQuantile::<f64>::builder().q(2.).build().is_err());
+    }
+    #[test]
+    fn merge_shards() {
+        use crate::quantile::Quantile;
+        use crate::stats::{Mergeable, Univariate};
+        let mut state: u64 = 17;
+        // Normally distributed values, from the sum of uniform ones
+        let data: Vec<f64> = (0..40000)
+            .map(|_| {
+                (0..12)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+                    })
+                    .sum::<f64>()
+                    - 6.
+            })
+            .collect();
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let rank = |x: f64| sorted.partition_point(|y| *y <= x) as f64 / sorted.len() as f64;
+        for q in [0.1, 0.5, 0.9] {
+            let mut single_pass: Quantile<f64> = Quantile::new(q).unwrap();
+            single_pass.update_many(data.iter().copied());
+            for shards in [2, 4, 10] {
+                let mut merged: Quantile<f64> = Quantile::new(q).unwrap();
+                for shard in data.chunks(data.len() / shards) {
+                    let mut partial: Quantile<f64> = Quantile::new(q).unwrap();
+                    partial.update_many(shard.iter().copied());
+                    merged.merge(&partial);
+                }
+                assert_eq!(merged.n(), data.len());
+                assert!((rank(merged.get()) - rank(single_pass.get())).abs() < 0.005);
+                assert!((rank(merged.get()) - q).abs() < 0.005);
+                // The merged estimator keeps running
+                let mut resumed = merged.clone();
+                resumed.update_many(data.iter().take(1000).copied());
+                assert!((rank(resumed.get()) - q).abs() < 0.01);
+            }
+        }
+    }
+    #[test]
+    fn merge_small_estimators() {
+        use crate::quantile::Quantile;
+        use crate::stats::{Mergeable, Univariate};
+        let data: Vec<f64> = (0..50).map(|i| ((i * 7) % 50) as f64).collect();
+        // Three raw values are replayed exactly
+        let mut merged: Quantile<f64> = Quantile::new(0.5).unwrap();
+        merged.update_many(data[3..].iter().copied());
+        let mut small: Quantile<f64> = Quantile::new(0.5).unwrap();
+        small.update_many(data[..3].iter().copied());
+        merged.merge(&small);
+        let mut single_pass: Quantile<f64> = Quantile::new(0.5).unwrap();
+        single_pass.update_many(data[3..].iter().chain(data[..3].iter()).copied());
+        assert_eq!(merged.get(), single_pass.get());
+        // Into an estimator holding raw values
+        let mut small: Quantile<f64> = Quantile::new(0.5).unwrap();
+        small.update_many(data[..3].iter().copied());
+        let mut large: Quantile<f64> = Quantile::new(0.5).unwrap();
+        large.update_many(data[3..].iter().copied());
+        small.merge(&large);
+        assert_eq!(small.n(), 50);
+        assert!((small.get() - 24.5).abs() < 2.);
+        assert_eq!(small.quantile(0.), 0.);
+        assert_eq!(small.quantile(1.), 49.);
+        // Merging an empty estimator is a no-op
+        let before = small.get();
+        small.merge(&Quantile::new(0.5).unwrap());
+        assert_eq!(small.get(), before);
+    }
+    #[test]
+    fn rolling_quantile_resize() {
+        use crate::quantile::{RollingMedian, RollingQuantile};
+        use crate::stats::Univariate;
+        let data: Vec<f64> = (0..60).map(|i| ((i * 29) % 41) as f64).collect();
+        // Partially filled, then full, windows
+        for (filled, first_size, new_size) in [(6, 10, 3), (6, 10, 20), (30, 10, 4), (30, 10, 25)] {
+            let mut rolling_quantile: RollingQuantile<f64> =
+                RollingQuantile::new(0.8, first_size).unwrap();
+            let mut rolling_median: RollingMedian<f64> = RollingMedian::new(first_size).unwrap();
+            rolling_quantile.update_many(data[..filled].iter().copied());
+            rolling_median.update_many(data[..filled].iter().copied());
+            rolling_quantile.resize(new_size).unwrap();
+            rolling_median.resize(new_size).unwrap();
+            // A fresh window fed with the retained tail, then the rest of the stream
+            let retained = filled.min(first_size).min(new_size);
+            let mut fresh_quantile: RollingQuantile<f64> =
+                RollingQuantile::new(0.8, new_size).unwrap();
+            let mut fresh_median: RollingMedian<f64> = RollingMedian::new(new_size).unwrap();
+            fresh_quantile.update_many(data[filled - retained..filled].iter().copied());
+            fresh_median.update_many(data[filled - retained..filled].iter().copied());
+            for x in data[filled..].iter() {
+                assert_eq!(rolling_quantile.n(), fresh_quantile.n());
+                assert_eq!(rolling_quantile.get(), fresh_quantile.get());
+                assert_eq!(rolling_median.get(), fresh_median.get());
+                rolling_quantile.update(*x);
+                rolling_median.update(*x);
+                fresh_quantile.update(*x);
+                fresh_median.update(*x);
+            }
+            assert_eq!(rolling_quantile.n(), new_size);
+            assert_eq!(rolling_quantile.get(), fresh_quantile.get());
+        }
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.5, 3).unwrap();
+        assert!(rolling_quantile.resize(0).is_err());
+        rolling_quantile.update_many([1., 2., 3., 4.]);
+        assert_eq!(rolling_quantile.get(), 3.);
+    }
+    #[test]
+    fn rolling_quantile_last_interpolation() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.75, 6).unwrap();
+        assert_eq!(rolling_quantile.last_interpolation(), None);
+        // Partially filled, the rank is 0.75 * 3 = 2.25 in 1, 3, 7, 9
+        rolling_quantile.update_many([7., 1., 9., 3.]);
+        assert_eq!(
+            rolling_quantile.last_interpolation(),
+            Some((2, 3, 0.25, 7., 9.))
+        );
+        assert_eq!(rolling_quantile.get(), 7.5);
+        // Full after evicting 7, the rank is 0.75 * 5 = 3.75 in 1, 2, 3, 4, 8, 9
+        rolling_quantile.update_many([4., 2., 8.]);
+        let (lower, higher, frac, lower_value, higher_value) =
+            rolling_quantile.last_interpolation().unwrap();
+        assert_eq!((lower, higher, lower_value, higher_value), (3, 4, 4., 8.));
+        assert!((frac - 0.75).abs() < 1e-12);
+        assert_eq!(
+            rolling_quantile.get(),
+            lower_value + frac * (higher_value - lower_value)
+        );
+        // The maximum falls exactly on the last value
+        let mut rolling_max: RollingQuantile<f64> = RollingQuantile::new(1., 3).unwrap();
+        rolling_max.update_many([5., 2., 6., 1.]);
+        assert_eq!(rolling_max.last_interpolation(), Some((2, 2, 0., 6., 6.)));
+    }
     #[test]
     fn rolling_quantile_edge_case() {
         use crate::quantile::RollingQuantile;
@@ -325,18 +1190,206 @@ mod test {
             0.8275462962962963,
         ];
         let mut quantile = Quantile::new(0.01_f64).unwrap();
-        for (d, gt) in data
-            .clone()
-            .into_iter()
-            .zip(good_value_001_quantile.into_iter())
-        {
+        for (d, gt) in data.clone().into_iter().zip(good_value_001_quantile) {
             quantile.update(d);
             assert_eq!(quantile.get(), gt);
         }
         let mut quantile = Quantile::new(0.99_f64).unwrap();
-        for (d, gt) in data.into_iter().zip(good_value_099_quantile.into_iter()) {
+        for (d, gt) in data.into_iter().zip(good_value_099_quantile) {
             quantile.update(d);
             assert_eq!(quantile.get(), gt);
         }
     }
+    #[test]
+    fn quantile_p2_reference() {
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        // Reference values of the P² estimator on a deterministic pseudo-random sequence
+        let reference: Vec<(usize, f64, f64)> = vec![
+            (5, 56.82303266439076, 68.01478072421156),
+            (10, 44.80852196982341, 56.82303266439076),
+            (100, 47.387837493587504, 89.39391812133401),
+            (500, 53.04369013500254, 90.38009466509197),
+            (1000, 50.98797500431668, 90.50391950044609),
+        ];
+        let mut state: u64 = 42;
+        let mut median = Quantile::new(0.5_f64).unwrap();
+        let mut q90 = Quantile::new(0.9_f64).unwrap();
+        let mut checkpoints = reference.into_iter().peekable();
+        for i in 1..=1000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let x = (state >> 11) as f64 / (1u64 << 53) as f64 * 100.;
+            median.update(x);
+            q90.update(x);
+            assert!(median.heights.windows(2).all(|w| w[0] <= w[1]));
+            assert!(q90.heights.windows(2).all(|w| w[0] <= w[1]));
+            if let Some((_, m, q)) = checkpoints.next_if(|(n, _, _)| *n == i) {
+                assert_eq!(median.get(), m);
+                assert_eq!(q90.get(), q);
+            }
+        }
+        assert!(checkpoints.next().is_none());
+    }
+    #[test]
+    fn quantile_out_of_range() {
+        use crate::quantile::Quantile;
+        assert_eq!(
+            Quantile::new(-0.1_f64).unwrap_err(),
+            "q should be between 0 and 1"
+        );
+        assert_eq!(
+            Quantile::new(1.1_f64).unwrap_err(),
+            "q should be between 0 and 1"
+        );
+        assert!(Quantile::new(1.5_f64).is_err());
+        assert!(Quantile::new(f64::NAN).is_err());
+    }
+    #[test]
+    fn rolling_quantile_out_of_range() {
+        use crate::quantile::RollingQuantile;
+        assert!(RollingQuantile::new(-0.2_f64, 10).is_err());
+        assert!(RollingQuantile::new(1.3_f64, 10).is_err());
+        assert!(RollingQuantile::new(f64::NAN, 10).is_err());
+        assert!(RollingQuantile::new(0.0_f64, 10).is_ok());
+        assert!(RollingQuantile::new(0.5_f64, 10).is_ok());
+        assert!(RollingQuantile::new(1.0_f64, 10).is_ok());
+    }
+    #[test]
+    fn rolling_quantile_empty() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.5_f64, 3).unwrap();
+        assert_eq!(rolling_quantile.try_get(), None);
+        assert!(rolling_quantile.get().is_nan());
+        rolling_quantile.update(2.);
+        assert_eq!(rolling_quantile.try_get(), Some(2.));
+    }
+    #[test]
+    fn quantile_boundaries() {
+        use crate::quantile::Quantile;
+        assert!(Quantile::new(0.0_f64).is_ok());
+        assert!(Quantile::new(1.0_f64).is_ok());
+    }
+    #[test]
+    fn quantile_try_get_empty() {
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        let mut quantile: Quantile<f64> = Quantile::default();
+        assert_eq!(quantile.try_get(), None);
+        quantile.update(3.);
+        assert_eq!(quantile.try_get(), Some(3.));
+    }
+    #[test]
+    fn rolling_quantile_nan_policy() {
+        use crate::quantile::RollingQuantile;
+        use crate::sorted_window::NanPolicy;
+        use crate::stats::Univariate;
+        let mut skip: RollingQuantile<f64> =
+            RollingQuantile::with_nan_policy(0.5_f64, 3, NanPolicy::Skip).unwrap();
+        let mut propagate: RollingQuantile<f64> =
+            RollingQuantile::with_nan_policy(1.0_f64, 3, NanPolicy::Propagate).unwrap();
+        for x in [1., f64::NAN, 2., 3.] {
+            skip.update(x);
+            propagate.update(x);
+        }
+        assert_eq!(skip.get(), 2.);
+        assert!(propagate.get().is_nan());
+    }
+    #[test]
+    fn rolling_quantile_window_size_zero() {
+        use crate::quantile::RollingQuantile;
+        let rolling_quantile: Result<RollingQuantile<f64>, _> = RollingQuantile::new(0.5_f64, 0);
+        assert_eq!(
+            rolling_quantile.err(),
+            Some("Window size should not equal to 0")
+        );
+    }
+    #[test]
+    fn rolling_quantile_clone_is_independent() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.5_f64, 3).unwrap();
+        rolling_quantile.update(1.);
+        rolling_quantile.update(2.);
+        let mut cloned = rolling_quantile.clone();
+        rolling_quantile.update(10.);
+        cloned.update(-10.);
+        cloned.update(-20.);
+        assert_eq!(rolling_quantile.get(), 2.);
+        assert_eq!(cloned.get(), -10.);
+        assert!(format!("{:?}", cloned).starts_with("RollingQuantile"));
+    }
+    #[test]
+    fn rolling_quantile_default() {
+        use crate::quantile::RollingQuantile;
+        use crate::stats::Univariate;
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::default();
+        for i in 0..=200 {
+            rolling_quantile.update(i as f64);
+        }
+        // Median of 101..=200
+        assert_eq!(rolling_quantile.get(), 150.5);
+    }
+    #[test]
+    fn p2_sample_size() {
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        let mut running_quantile: Quantile<f64> = Quantile::new(0.9).unwrap();
+        for i in 0..100 {
+            assert_eq!(running_quantile.n(), i);
+            running_quantile.update(((i * 37) % 101) as f64);
+        }
+        assert_eq!(running_quantile.n(), 100);
+    }
+    #[test]
+    fn interpolated_quantiles() {
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        let mut state: u64 = 3;
+        let data: Vec<f64> = (0..2001)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+            })
+            .collect();
+        let mut median: Quantile<f64> = Quantile::new(0.5).unwrap();
+        let mut decile: Quantile<f64> = Quantile::new(0.9).unwrap();
+        assert!(median.quantile(0.5).is_nan());
+        for x in data.iter() {
+            median.update(*x);
+            decile.update(*x);
+        }
+        // The rank of the median is at most one position away from the middle marker
+        assert!((median.quantile(0.5) - median.get()).abs() < 1e-2);
+        // Far from the tracked quantile the estimate is rougher
+        assert!((decile.quantile(0.5) - 0.5).abs() < 0.1);
+        let markers = median.markers();
+        let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(markers[0], (1., min));
+        assert_eq!(markers[4], (2001., max));
+        assert_eq!(median.quantile(0.), min);
+        assert_eq!(median.quantile(1.), max);
+        assert!(markers
+            .windows(2)
+            .all(|w| w[0].0 < w[1].0 && w[0].1 <= w[1].1));
+        assert!(median.quantile(1.5).is_nan());
+    }
+    #[test]
+    fn interpolated_quantiles_before_initialization() {
+        use crate::quantile::Quantile;
+        use crate::stats::Univariate;
+        let mut running_quantile: Quantile<f64> = Quantile::default();
+        for x in [4., 1., 3.] {
+            running_quantile.update(x);
+        }
+        assert_eq!(running_quantile.quantile(0.5), 3.);
+        assert_eq!(running_quantile.quantile(0.25), 2.);
+        assert_eq!(running_quantile.markers()[2], (3., 4.));
+        assert!(running_quantile.markers()[3].1.is_nan());
+    }
 }
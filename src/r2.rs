@@ -0,0 +1,130 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Bivariate, Revertable, Univariate};
+use crate::sum::Sum;
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+
+/// Rolling coefficient of determination of predictions, `1 - SS_res / SS_tot`.
+///
+/// `update` takes `(y_true, y_pred)` pairs. `SS_res` is the sum of the squared residuals and
+/// `SS_tot` the sum of the squared deviations of `y_true` from its mean, both over the latest
+/// `window_size` pairs and reverted as pairs leave the window. A model predicting the mean of the
+/// window scores `0`, worse models score negative values. When every `y_true` of the window is
+/// equal, `get` returns `1` for perfect predictions and `0` otherwise. `get` returns `NaN` while
+/// the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::r2::RollingR2;
+/// use watermill::stats::Bivariate;
+/// let y_true: Vec<f64> = vec![3., -0.5, 2., 7., 4.2];
+/// let y_pred: Vec<f64> = vec![2.5, 0., 2., 8., 4.2];
+/// let mut rolling_r2: RollingR2<f64> = RollingR2::new(4).unwrap();
+/// for (yt, yp) in y_true.iter().zip(y_pred.iter()){
+///     rolling_r2.update(*yt, *yp);
+/// }
+/// // Window of the 4 latest pairs, SS_res = 1.25 and SS_tot = 30.5675
+/// assert!((rolling_r2.get() - (1. - 1.25 / 30.5675)).abs() < 1e-12);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the coefficient of determination](https://www.wikiwand.com/en/Coefficient_of_determination)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingR2<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    y_true: Variance<F>,
+    squared_residuals: Sum<F>,
+    window: VecDeque<(F, F)>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingR2<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            y_true: Variance::new(0),
+            squared_residuals: Sum::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Bivariate<F> for RollingR2<F> {
+    fn update(&mut self, y_true: F, y_pred: F) {
+        if self.window.len() == self.window_size {
+            let (oldest_true, oldest_pred) =
+                self.window.pop_front().expect("Window should not be empty");
+            self.y_true
+                .revert(oldest_true)
+                .expect("The window holds as many values as the variance");
+            self.squared_residuals
+                .revert((oldest_true - oldest_pred).powi(2))
+                .expect("The window holds as many values as the sum");
+        }
+        self.window.push_back((y_true, y_pred));
+        self.y_true.update(y_true);
+        self.squared_residuals.update((y_true - y_pred).powi(2));
+    }
+    fn get(&self) -> F {
+        if self.window.is_empty() {
+            return F::nan();
+        }
+        let zero = F::from_f64(0.).unwrap();
+        let one = F::from_f64(1.).unwrap();
+        // Reverting can leave a tiny negative residue
+        let ss_res = self.squared_residuals.get().max(zero);
+        let ss_tot = self.y_true.state.max(zero);
+        if ss_tot == zero {
+            return if ss_res == zero { one } else { zero };
+        }
+        one - ss_res / ss_tot
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn targets() -> Vec<f64> {
+        (0..50).map(|i| ((i * 17) % 23) as f64 * 0.5 - 3.).collect()
+    }
+    #[test]
+    fn perfect_predictions() {
+        let mut rolling_r2: RollingR2<f64> = RollingR2::new(10).unwrap();
+        assert!(rolling_r2.get().is_nan());
+        for y in targets() {
+            rolling_r2.update(y, y);
+            assert_eq!(rolling_r2.get(), 1.);
+        }
+    }
+    #[test]
+    fn mean_and_worse_predictors() {
+        let y_true = targets();
+        let mut mean_r2: RollingR2<f64> = RollingR2::new(10).unwrap();
+        let mut bad_r2: RollingR2<f64> = RollingR2::new(10).unwrap();
+        // Predict the mean of the last 10 targets, which is the mean of the final window
+        let mean = y_true[40..].iter().sum::<f64>() / 10.;
+        for y in y_true.iter() {
+            mean_r2.update(*y, mean);
+            bad_r2.update(*y, -*y);
+        }
+        assert!(mean_r2.get().abs() < 1e-12);
+        assert!(bad_r2.get() < 0.);
+    }
+    #[test]
+    fn constant_targets() {
+        let mut perfect: RollingR2<f64> = RollingR2::new(5).unwrap();
+        let mut wrong: RollingR2<f64> = RollingR2::new(5).unwrap();
+        for _ in 0..20 {
+            perfect.update(2., 2.);
+            wrong.update(2., 3.);
+        }
+        assert_eq!(perfect.get(), 1.);
+        assert_eq!(wrong.get(), 0.);
+        assert!(RollingR2::<f64>::new(0).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::mean::RollingMean;
+use crate::stats::Univariate;
+use crate::std_dev::RollingStandardDeviation;
+use serde::{Deserialize, Serialize};
+
+/// Rolling information ratio, the mean of the window divided by its sample standard deviation.
+///
+/// With a `risk_free` rate, every value is transformed into the excess return `x - risk_free`
+/// before entering the window, which gives the Sharpe ratio. The mean and the standard deviation
+/// share the same window. The ratio is undefined on a constant window, `get` then returns `0`, or
+/// `NaN` when `nan_if_constant` is set.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `risk_free` - Rate subtracted from every value. Defaults to `0`.
+/// # Examples
+/// ```
+/// use watermill::ratio::RollingInformationRatio;
+/// use watermill::stats::Univariate;
+/// let returns: Vec<f64> = vec![0.01, -0.02, 0.03, 0.015, 0.005];
+/// let mut ratio: RollingInformationRatio<f64> = RollingInformationRatio::new(4).unwrap();
+/// let mut sharpe: RollingInformationRatio<f64> = RollingInformationRatio::with_risk_free(4, 0.005).unwrap();
+/// for x in returns.into_iter(){
+///     ratio.update(x);
+///     sharpe.update(x);
+/// }
+/// // Window [-0.02, 0.03, 0.015, 0.005], of mean 0.0075 and sample std 0.02101586702...
+/// assert!((ratio.get() - 0.0075 / 0.02101586702153082).abs() < 1e-9);
+/// assert!((sharpe.get() - 0.0025 / 0.02101586702153082).abs() < 1e-9);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the Sharpe ratio](https://www.wikiwand.com/en/Sharpe_ratio)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingInformationRatio<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    mean: RollingMean<F>,
+    std: RollingStandardDeviation<F>,
+    pub risk_free: F,
+    pub nan_if_constant: bool,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingInformationRatio<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_risk_free(window_size, F::from_f64(0.).unwrap())
+    }
+    /// Same as `new`, `risk_free` is subtracted from every value.
+    pub fn with_risk_free(window_size: usize, risk_free: F) -> Result<Self, &'static str> {
+        if !risk_free.is_finite() {
+            return Err("risk_free should be finite");
+        }
+        Ok(Self {
+            mean: RollingMean::new(window_size)?,
+            std: RollingStandardDeviation::new(window_size, 1)?,
+            risk_free,
+            nan_if_constant: false,
+        })
+    }
+    /// The excess return `x - risk_free` entering the window.
+    pub fn transform(&self, x: F) -> F {
+        x - self.risk_free
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F>
+    for RollingInformationRatio<F>
+{
+    fn update(&mut self, x: F) {
+        let excess = self.transform(x);
+        self.mean.update(excess);
+        self.std.update(excess);
+    }
+    fn get(&self) -> F {
+        let std = self.std.get();
+        if std == F::from_f64(0.).unwrap() {
+            if self.nan_if_constant {
+                return F::nan();
+            }
+            return F::from_f64(0.).unwrap();
+        }
+        self.mean.get() / std
+    }
+    fn n(&self) -> usize {
+        self.mean.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn constant_returns() {
+        let mut ratio: RollingInformationRatio<f64> = RollingInformationRatio::new(5).unwrap();
+        let mut nan_ratio: RollingInformationRatio<f64> =
+            RollingInformationRatio::with_risk_free(5, 0.01).unwrap();
+        nan_ratio.nan_if_constant = true;
+        for _ in 0..20 {
+            ratio.update(0.01);
+            nan_ratio.update(0.01);
+            assert_eq!(ratio.get(), 0.);
+            assert!(nan_ratio.get().is_nan());
+        }
+        assert!(RollingInformationRatio::<f64>::new(0).is_err());
+        assert!(RollingInformationRatio::<f64>::with_risk_free(5, f64::NAN).is_err());
+    }
+    #[test]
+    fn volatile_returns() {
+        let mut state: u64 = 7;
+        let returns: Vec<f64> = (0..300)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64 - 0.45) * 0.1
+            })
+            .collect();
+        let mut ratio: RollingInformationRatio<f64> =
+            RollingInformationRatio::with_risk_free(30, 0.002).unwrap();
+        for (i, x) in returns.iter().enumerate() {
+            ratio.update(*x);
+            if i > 0 {
+                let window: Vec<f64> = returns[(i + 1).saturating_sub(30)..=i]
+                    .iter()
+                    .map(|r| r - 0.002)
+                    .collect();
+                let n = window.len() as f64;
+                let mean = window.iter().sum::<f64>() / n;
+                let std =
+                    (window.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.)).sqrt();
+                assert!((ratio.get() - mean / std).abs() < 1e-9);
+            }
+        }
+    }
+}
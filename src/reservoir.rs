@@ -0,0 +1,120 @@
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::utils::SplitMix64;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SEED: u64 = 0x5eed;
+
+/// Uniform random sample of the stream, using reservoir sampling (Algorithm R).
+///
+/// The first `size` values fill the reservoir, then the `n`-th value replaces a random value of
+/// the reservoir with probability `size / n`, so every value seen has the same chance of being in
+/// the sample. An update costs `O(1)`.
+/// The random generator is seeded, `with_seed` gives reproducible samples.
+/// # Arguments
+/// * `size` - Number of values in the sample, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::reservoir::ReservoirSample;
+/// let mut reservoir: ReservoirSample<f64> = ReservoirSample::with_seed(3, 42).unwrap();
+/// for i in 0..1000{
+///     reservoir.update(i as f64);
+/// }
+/// assert_eq!(reservoir.sample().len(), 3);
+/// assert_eq!(reservoir.count(), 1000);
+/// ```
+/// # References
+/// [^1]: [Vitter, J.S., 1985. Random sampling with a reservoir. ACM Transactions on Mathematical Software, 11(1), pp.37-57.](https://dl.acm.org/doi/10.1145/3147.3165)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReservoirSample<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    size: usize,
+    sample: Vec<F>,
+    count: u64,
+    rng: SplitMix64,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ReservoirSample<F> {
+    pub fn new(size: usize) -> Result<Self, &'static str> {
+        Self::with_seed(size, DEFAULT_SEED)
+    }
+    /// Same as `new`, with the seed of the random generator.
+    pub fn with_seed(size: usize, seed: u64) -> Result<Self, &'static str> {
+        if size == 0 {
+            return Err("size should not equal to 0");
+        }
+        Ok(Self {
+            size,
+            sample: Vec::with_capacity(size),
+            count: 0,
+            rng: SplitMix64::new(seed),
+        })
+    }
+    pub fn update(&mut self, x: F) {
+        self.count += 1;
+        if self.sample.len() < self.size {
+            self.sample.push(x);
+            return;
+        }
+        let j = self.rng.next_below(self.count) as usize;
+        if j < self.size {
+            self.sample[j] = x;
+        }
+    }
+    /// The sampled values, in no particular order. Holds every value while fewer than `size` were seen.
+    pub fn sample(&self) -> &[F] {
+        &self.sample
+    }
+    /// Number of values seen.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn sample_mean_approximates_mean() {
+        let mut reservoir: ReservoirSample<f64> = ReservoirSample::new(1000).unwrap();
+        let n = 1_000_000;
+        for i in 0..n {
+            reservoir.update(i as f64);
+        }
+        let sample = reservoir.sample();
+        assert_eq!(sample.len(), 1000);
+        let sample_mean = sample.iter().sum::<f64>() / 1000.;
+        // The standard error of the sample mean is n / sqrt(12 * 1000), about 9129
+        assert!((sample_mean - (n - 1) as f64 / 2.).abs() < 4. * 9129.);
+        // Every tenth of the stream is about as present in the sample
+        let mut deciles = [0; 10];
+        for x in sample.iter() {
+            deciles[(*x as usize) * 10 / n] += 1;
+        }
+        assert!(deciles.iter().all(|count| (50..150).contains(count)));
+    }
+    #[test]
+    fn fewer_values_than_size() {
+        let mut reservoir: ReservoirSample<f64> = ReservoirSample::new(10).unwrap();
+        assert!(reservoir.sample().is_empty());
+        for x in [3., 1., 2.] {
+            reservoir.update(x);
+        }
+        assert_eq!(reservoir.sample(), &[3., 1., 2.]);
+    }
+    #[test]
+    fn seeded_samples_are_reproducible() {
+        let mut first: ReservoirSample<f64> = ReservoirSample::with_seed(20, 7).unwrap();
+        let mut second: ReservoirSample<f64> = ReservoirSample::with_seed(20, 7).unwrap();
+        let mut other_seed: ReservoirSample<f64> = ReservoirSample::with_seed(20, 8).unwrap();
+        for i in 0..10_000 {
+            first.update(i as f64);
+            second.update(i as f64);
+            other_seed.update(i as f64);
+        }
+        assert_eq!(first.sample(), second.sample());
+        assert_ne!(first.sample(), other_seed.sample());
+        assert!(ReservoirSample::<f64>::new(0).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::count::Count;
+use crate::stats::{Revertable, Univariate};
+use crate::utils::NeumaierSum;
+use serde::{Deserialize, Serialize};
+/// Running root mean square, `sqrt(sum(x²) / n)`.
+/// # Examples
+/// ```
+/// use watermill::rms::RMS;
+/// use watermill::stats::{Univariate, Revertable};
+/// let data: Vec<f64> = vec![3., 4.];
+/// let data_revert = data.clone();
+/// let mut running_rms: RMS<f64> = RMS::new();
+/// for x in data.into_iter(){
+///     running_rms.update(x);
+/// }
+/// assert_eq!(running_rms.get(), 12.5_f64.sqrt());
+/// // You can revert the root mean square
+///
+/// for x in data_revert.into_iter().rev(){
+///     running_rms.revert(x);
+/// }
+/// assert_eq!(running_rms.get(), 0.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the root mean square](https://www.wikiwand.com/en/Root_mean_square)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct RMS<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub sum_sq: F,
+    pub n: Count<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for RMS<F> {
+    fn default() -> Self {
+        Self {
+            sum_sq: F::from_f64(0.).unwrap(),
+            n: Count::new(),
+        }
+    }
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RMS<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RMS<F> {
+    fn update(&mut self, x: F) {
+        self.sum_sq += x * x;
+        self.n.update(x);
+    }
+    fn get(&self) -> F {
+        let n = self.n.get();
+        if n == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        // Reverting can leave a tiny negative residue
+        (self.sum_sq / n).max(F::from_f64(0.).unwrap()).sqrt()
+    }
+    fn n(&self) -> usize {
+        self.n.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for RMS<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.n.revert(x)?;
+        self.sum_sq -= x * x;
+        Ok(())
+    }
+}
+
+/// Rolling sum of squares, `sum(x²)` over the window.
+///
+/// The squares are summed with compensated summation, the square of a value leaving the window
+/// is subtracted from the sum. This is the energy of a signal over the window. Like `RollingSum`,
+/// a `NaN` or infinite value poisons the sum even after leaving the window, use
+/// `update_if_finite` to skip them. `get` returns `0` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::rms::RollingSumOfSquares;
+/// use watermill::stats::Univariate;
+/// let mut rolling_sum_sq: RollingSumOfSquares<f64> = RollingSumOfSquares::new(2).unwrap();
+/// for x in vec![10., 3., -4.].into_iter(){
+///     rolling_sum_sq.update(x);
+/// }
+/// assert_eq!(rolling_sum_sq.get(), 25.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingSumOfSquares<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sum_sq: NeumaierSum<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingSumOfSquares<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            sum_sq: NeumaierSum::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingSumOfSquares<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.sum_sq.add(-oldest * oldest);
+        }
+        self.window.push_back(x);
+        self.sum_sq.add(x * x);
+    }
+    fn get(&self) -> F {
+        // Subtracting the squares can leave a tiny negative residue, `max` would also hide a `NaN`
+        let sum_sq = self.sum_sq.value();
+        if sum_sq < F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        sum_sq
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+/// Rolling L2 norm, `sqrt(sum(x²))` over the window, the square root of `RollingSumOfSquares`.
+///
+/// `get` returns `0` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::rms::RollingL2Norm;
+/// use watermill::stats::Univariate;
+/// let mut rolling_norm: RollingL2Norm<f64> = RollingL2Norm::new(2).unwrap();
+/// for x in vec![10., 3., -4.].into_iter(){
+///     rolling_norm.update(x);
+/// }
+/// assert_eq!(rolling_norm.get(), 5.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingL2Norm<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub sum_sq: RollingSumOfSquares<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingL2Norm<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Ok(Self {
+            sum_sq: RollingSumOfSquares::new(window_size)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingL2Norm<F> {
+    fn update(&mut self, x: F) {
+        self.sum_sq.update(x);
+    }
+    fn get(&self) -> F {
+        self.sum_sq.get().sqrt()
+    }
+    fn n(&self) -> usize {
+        self.sum_sq.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_rms() {
+        let data: Vec<f64> = vec![10., -7., 1., -1., 1., -1., 2., -2.];
+        let mut running_rms: RMS<f64> = RMS::new();
+        let mut rolling_rms = Rolling::new(&mut running_rms, 4).unwrap();
+        for x in data.into_iter() {
+            rolling_rms.update(x);
+        }
+        assert!((rolling_rms.get() - 2.5_f64.sqrt()).abs() < 1e-12);
+    }
+    #[test]
+    fn rolling_sum_of_squares_matches_window() {
+        let mut state: u64 = 5;
+        let data: Vec<f64> = (0..500)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64 - 0.5) * 1e3
+            })
+            .collect();
+        let mut rolling_sum_sq: RollingSumOfSquares<f64> = RollingSumOfSquares::new(16).unwrap();
+        let mut rolling_norm: RollingL2Norm<f64> = RollingL2Norm::new(16).unwrap();
+        assert_eq!((rolling_sum_sq.get(), rolling_norm.get()), (0., 0.));
+        for (i, x) in data.iter().enumerate() {
+            rolling_sum_sq.update(*x);
+            rolling_norm.update(*x);
+            let window = &data[(i + 1).saturating_sub(16)..=i];
+            let expected: f64 = window.iter().map(|x| x * x).sum();
+            assert!((rolling_sum_sq.get() - expected).abs() < 1e-9 * expected.max(1.));
+            assert!((rolling_norm.get() - expected.sqrt()).abs() < 1e-9 * expected.sqrt().max(1.));
+            assert_eq!(rolling_norm.n(), window.len());
+        }
+    }
+    #[test]
+    fn rolling_sum_of_squares_large_then_small() {
+        let mut rolling_sum_sq: RollingSumOfSquares<f64> = RollingSumOfSquares::new(3).unwrap();
+        for x in [1e8, -1e8, 1e8, 1e-3, 2e-3, -2e-3] {
+            rolling_sum_sq.update(x);
+        }
+        assert!((rolling_sum_sq.get() - 9e-6).abs() < 1e-15);
+        assert!(RollingSumOfSquares::<f64>::new(0).is_err());
+        assert!(RollingL2Norm::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn rolling_sum_of_squares_non_finite() {
+        let mut rolling_norm: RollingL2Norm<f64> = RollingL2Norm::new(3).unwrap();
+        rolling_norm.update(f64::NAN);
+        assert!(rolling_norm.get().is_nan());
+        let mut skipping: RollingL2Norm<f64> = RollingL2Norm::new(3).unwrap();
+        for x in [3., f64::NAN, 4., f64::INFINITY] {
+            skipping.update_if_finite(x);
+        }
+        assert_eq!(skipping.get(), 5.);
+        assert_eq!(skipping.n(), 2);
+    }
+}
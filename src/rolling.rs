@@ -1,22 +1,21 @@
-use crate::stats::{RollableUnivariate, Univariate};
+use crate::stats::{Bivariate, RollableBivariate, RollableUnivariate, Univariate};
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::{
-    collections::VecDeque,
-    ops::{AddAssign, SubAssign},
-};
+use serde::{Deserialize, Serialize};
 
 /// Generic wrapper for performing rolling computations.
-/// This can be wrapped around any struct which implements a `Univariate` and a `Revertable` and `RollableUnivariate`
-/// traits.
+/// This can be wrapped around any struct which implements the `Univariate` and `Revertable` traits,
+/// which makes it a `RollableUnivariate`.
 /// Inputs to `update` are stored in a `VecDeque`. Elements of the queue are popped when the window is
 //  full.
 /// # Arguments
-/// * `to_roll` - A running statistics which implements `Univariate` and `Revertable` and `RollableUnivariate` trait.
+/// * `to_roll` - A running statistics which implements `Univariate` and `Revertable` traits.
 /// * `window_size` - Size of sliding window.
 /// # Examples
 /// ```
 ///
-/// use watermill::stats::{RollableUnivariate, Univariate};
+/// use watermill::stats::Univariate;
 /// use watermill::sum::Sum;
 /// use watermill::rolling::Rolling;
 /// let data = vec![9.,7.,3.,2.,6.,1., 8., 5., 4.];
@@ -30,10 +29,10 @@ use std::{
 /// ```
 pub struct Rolling<'a, U, F>
 where
-    U: RollableUnivariate<F>,  // Optimization: Generic over U (the concrete type) instead of dyn for static dispatch
+    U: RollableUnivariate<F>, // Optimization: Generic over U (the concrete type) instead of dyn for static dispatch
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
-    to_roll: &'a mut U,  // Optimization: &mut U instead of &mut dyn
+    to_roll: &'a mut U, // Optimization: &mut U instead of &mut dyn
     window_size: usize,
     window: VecDeque<F>,
 }
@@ -43,16 +42,43 @@ where
     U: RollableUnivariate<F>,
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
-    pub fn new(to_roll: &'a mut U, window_size: usize) -> Result<Self, &'static str> {  // Optimization: &'static str for error (clearer, no lifetime tie)
+    pub fn new(to_roll: &'a mut U, window_size: usize) -> Result<Self, &'static str> {
+        // Optimization: &'static str for error (clearer, no lifetime tie)
         if window_size == 0 {
             return Err("Window size should not equal to 0");
         }
         Ok(Self {
             to_roll,
             window_size,
-            window: VecDeque::with_capacity(window_size),  // Optimization: Preallocate to avoid reallocs during growth
+            window: VecDeque::with_capacity(window_size), // Optimization: Preallocate to avoid reallocs during growth
         })
     }
+    /// Values of the window, from the oldest to the newest.
+    /// # Examples
+    /// ```
+    /// use watermill::stats::Univariate;
+    /// use watermill::sum::Sum;
+    /// use watermill::rolling::Rolling;
+    /// let mut running_sum: Sum<f64> = Sum::new();
+    /// let mut rolling_sum: Rolling<_, f64> = Rolling::new(&mut running_sum, 3).unwrap();
+    /// for x in vec![9., 7., 3., 2.].into_iter(){
+    ///     rolling_sum.update(x);
+    /// }
+    /// assert_eq!(rolling_sum.window().copied().collect::<Vec<f64>>(), vec![7., 3., 2.]);
+    /// assert_eq!(rolling_sum.window_len(), 3);
+    /// ```
+    pub fn window(&self) -> impl Iterator<Item = &F> + '_ {
+        self.window.iter()
+    }
+    /// Number of values in the window, at most `window_size`.
+    pub fn window_len(&self) -> usize {
+        self.window.len()
+    }
+    /// Same as `update`, but the error of `revert` is returned instead of panicking. The window
+    /// and the statistic are left untouched on error.
+    pub fn try_update(&mut self, x: F) -> Result<(), &'static str> {
+        roll(self.to_roll, &mut self.window, self.window_size, x)
+    }
 }
 
 impl<'a, U, F> Univariate<F> for Rolling<'a, U, F>
@@ -60,22 +86,214 @@ where
     U: RollableUnivariate<F>,
     F: Float + FromPrimitive + AddAssign + SubAssign,
 {
+    /// # Panics
+    /// Panics if the oldest value of the window cannot be reverted, see `try_update`.
     fn update(&mut self, x: F) {
+        self.try_update(x).unwrap();
+    }
+
+    fn get(&self) -> F {
+        self.to_roll.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+// Push `x` into the window, reverting the oldest value out of `to_roll` once the window is full.
+// Nothing is modified if the revert fails.
+fn roll<U, F>(
+    to_roll: &mut U,
+    window: &mut VecDeque<F>,
+    window_size: usize,
+    x: F,
+) -> Result<(), &'static str>
+where
+    U: RollableUnivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    if window.len() == window_size {
+        let oldest = window.front().copied().expect("Window should not be empty"); // Optimization: copied() for clarity/safety (F is Copy-like for floats); expect for debug assert
+        to_roll.revert(oldest)?;
+        window.pop_front();
+    }
+    window.push_back(x);
+    to_roll.update(x);
+    Ok(())
+}
+
+/// Same as `Rolling`, but owning the statistic instead of borrowing it.
+///
+/// `RollingOwned` has no lifetime, so it can be stored in a struct, returned from a function or
+/// serialized along with its window.
+/// # Arguments
+/// * `to_roll` - A running statistics which implements `Univariate` and `Revertable` traits.
+/// * `window_size` - Size of sliding window.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::variance::Variance;
+/// use watermill::rolling::RollingOwned;
+/// fn rolling_variance(window_size: usize) -> RollingOwned<Variance<f64>, f64> {
+///     RollingOwned::new(Variance::default(), window_size).unwrap()
+/// }
+/// let mut rolling_var = rolling_variance(2);
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     rolling_var.update(x);
+/// }
+/// assert_eq!(rolling_var.get(), 0.5);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingOwned<U, F>
+where
+    U: RollableUnivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    to_roll: U,
+    window_size: usize,
+    window: VecDeque<F>,
+}
+
+impl<U, F> RollingOwned<U, F>
+where
+    U: RollableUnivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub fn new(to_roll: U, window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            to_roll,
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+        })
+    }
+    /// Values of the window, from the oldest to the newest.
+    pub fn window(&self) -> impl Iterator<Item = &F> + '_ {
+        self.window.iter()
+    }
+    /// Number of values in the window, at most `window_size`.
+    pub fn window_len(&self) -> usize {
+        self.window.len()
+    }
+    /// The rolled statistic, holding the values of the window.
+    pub fn inner(&self) -> &U {
+        &self.to_roll
+    }
+    /// Give back the rolled statistic.
+    pub fn into_inner(self) -> U {
+        self.to_roll
+    }
+    /// Same as `Rolling::try_update`.
+    pub fn try_update(&mut self, x: F) -> Result<(), &'static str> {
+        roll(&mut self.to_roll, &mut self.window, self.window_size, x)
+    }
+}
+
+impl<U, F> Univariate<F> for RollingOwned<U, F>
+where
+    U: RollableUnivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    /// # Panics
+    /// Panics if the oldest value of the window cannot be reverted, see `try_update`.
+    fn update(&mut self, x: F) {
+        self.try_update(x).unwrap();
+    }
+
+    fn get(&self) -> F {
+        self.to_roll.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+/// Sliding window wrapper for bivariate statistics, the `Rolling` of `(x, y)` pairs.
+///
+/// This can be wrapped around any struct which implements the `Bivariate` and
+/// `RevertableBivariate` traits, the oldest pair is reverted once the window is full.
+/// # Arguments
+/// * `to_roll` - A running statistics which implements `Bivariate` and `RevertableBivariate` traits.
+/// * `window_size` - Size of sliding window.
+/// # Examples
+/// ```
+/// use watermill::covariance::Covariance;
+/// use watermill::rolling::RollingBivariate;
+/// use watermill::stats::Bivariate;
+/// let mut rolling_cov: RollingBivariate<Covariance<f64>, f64> =
+///     RollingBivariate::new(Covariance::default(), 2).unwrap();
+/// for (x, y) in vec![(1., 5.), (2., 3.), (3., 4.)].into_iter(){
+///     rolling_cov.update(x, y);
+/// }
+/// assert_eq!(rolling_cov.get(), 0.5);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingBivariate<U, F>
+where
+    U: RollableBivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    to_roll: U,
+    window_size: usize,
+    window: VecDeque<(F, F)>,
+}
+
+impl<U, F> RollingBivariate<U, F>
+where
+    U: RollableBivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    pub fn new(to_roll: U, window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            to_roll,
+            window_size,
+            window: VecDeque::with_capacity(window_size),
+        })
+    }
+    /// Pairs of the window, from the oldest to the newest.
+    pub fn window(&self) -> impl Iterator<Item = &(F, F)> + '_ {
+        self.window.iter()
+    }
+    /// Number of pairs in the window, at most `window_size`.
+    pub fn window_len(&self) -> usize {
+        self.window.len()
+    }
+    /// The rolled statistic, holding the pairs of the window.
+    pub fn inner(&self) -> &U {
+        &self.to_roll
+    }
+    /// Give back the rolled statistic.
+    pub fn into_inner(self) -> U {
+        self.to_roll
+    }
+    /// Same as `update`, but the error of `revert` is returned instead of panicking. The window
+    /// and the statistic are left untouched on error.
+    pub fn try_update(&mut self, x: F, y: F) -> Result<(), &'static str> {
         if self.window.len() == self.window_size {
-            // To handle the error, the program panics because returning the error type would change
-            // the interface of the get method. This problem is unlikely to happen because we
-            // control the size of the sliding window in the constructor.
-            let oldest = self.window.front().copied().expect("Window should not be empty");  // Optimization: copied() for clarity/safety (F is Copy-like for floats); expect for debug assert
-            match self.to_roll.revert(oldest) {
-                Ok(()) => (),  // Assume revert returns Result<(), _>; adjust if different
-                Err(err) => panic!("{}", err),
-            };
+            let (oldest_x, oldest_y) = *self.window.front().expect("Window should not be empty");
+            self.to_roll.revert(oldest_x, oldest_y)?;
             self.window.pop_front();
-            self.window.push_back(x);
-        } else {
-            self.window.push_back(x);
         }
-        self.to_roll.update(x);
+        self.window.push_back((x, y));
+        self.to_roll.update(x, y);
+        Ok(())
+    }
+}
+
+impl<U, F> Bivariate<F> for RollingBivariate<U, F>
+where
+    U: RollableBivariate<F>,
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    /// # Panics
+    /// Panics if the oldest pair of the window cannot be reverted, see `try_update`.
+    fn update(&mut self, x: F, y: F) {
+        self.try_update(x, y).unwrap();
     }
 
     fn get(&self) -> F {
@@ -92,10 +310,147 @@ mod tests {
         let data = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
         let mut running_var: Variance<f64> = Variance::default();
         // We wrap `running_var` inside the `Rolling` struct.
-        let mut rolling_var: Rolling<_, f64> = Rolling::new(&mut running_var, 2).unwrap();  // Note: _ for type inference
+        let mut rolling_var: Rolling<_, f64> = Rolling::new(&mut running_var, 2).unwrap(); // Note: _ for type inference
         for x in data.iter() {
-            rolling_var.update(*x as f64);
+            rolling_var.update(*x);
         }
         assert_eq!(rolling_var.get(), 0.5);
     }
-}
\ No newline at end of file
+    #[test]
+    fn window_contents() {
+        use crate::mean::Mean;
+        use crate::rolling::Rolling;
+        use crate::stats::Univariate;
+        let data = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut rolling_mean: Rolling<_, f64> = Rolling::new(&mut running_mean, 4).unwrap();
+        assert_eq!(rolling_mean.window_len(), 0);
+        for (i, x) in data.iter().enumerate() {
+            rolling_mean.update(*x);
+            let expected = &data[(i + 1).saturating_sub(4)..=i];
+            assert_eq!(rolling_mean.window_len(), expected.len());
+            assert!(rolling_mean.window().eq(expected.iter()));
+        }
+        assert_eq!(
+            rolling_mean.window().copied().collect::<Vec<f64>>(),
+            vec![1., 8., 5., 4.]
+        );
+    }
+    #[test]
+    fn owned_as_struct_field() {
+        use crate::rolling::RollingOwned;
+        use crate::stats::Univariate;
+        use crate::variance::Variance;
+        // A long lived monitor holding its rolling statistic
+        struct Monitor {
+            rolling_var: RollingOwned<Variance<f64>, f64>,
+        }
+        let mut monitor = Monitor {
+            rolling_var: RollingOwned::new(Variance::default(), 2).unwrap(),
+        };
+        for x in [9., 7., 3., 2., 6., 1., 8., 5., 4.] {
+            monitor.rolling_var.update(x);
+        }
+        assert_eq!(monitor.rolling_var.get(), 0.5);
+        assert!(monitor.rolling_var.window().eq([5., 4.].iter()));
+        // The window is saved along with the statistic
+        let serialized = serde_json::to_string(&monitor.rolling_var).unwrap();
+        let mut resumed: RollingOwned<Variance<f64>, f64> =
+            serde_json::from_str(&serialized).unwrap();
+        resumed.update(8.);
+        assert_eq!(resumed.get(), 8.);
+        assert_eq!(monitor.rolling_var.into_inner().get(), 0.5);
+        assert!(RollingOwned::<Variance<f64>, f64>::new(Variance::default(), 0).is_err());
+    }
+    #[test]
+    fn sample_size_saturates() {
+        use crate::maximum::RollingArgMax;
+        use crate::mean::{Mean, RollingMean};
+        use crate::quantile::RollingQuantile;
+        use crate::rolling::{Rolling, RollingOwned};
+        use crate::stats::Univariate;
+        use crate::sum::Sum;
+        let mut running_mean: Mean<f64> = Mean::new();
+        let mut rolling_mean = Rolling::new(&mut running_mean, 3).unwrap();
+        let mut rolling_sum = RollingOwned::new(Sum::<f64>::new(), 3).unwrap();
+        let mut standalone_mean: RollingMean<f64> = RollingMean::new(3).unwrap();
+        let mut rolling_quantile: RollingQuantile<f64> = RollingQuantile::new(0.5, 3).unwrap();
+        let mut rolling_arg_max: RollingArgMax<f64> = RollingArgMax::new(3).unwrap();
+        assert_eq!(rolling_mean.n(), 0);
+        for (i, x) in [9., 7., 3., 2., 6., 1.].into_iter().enumerate() {
+            rolling_mean.update(x);
+            rolling_sum.update(x);
+            standalone_mean.update(x);
+            rolling_quantile.update(x);
+            rolling_arg_max.update(x);
+            let expected = (i + 1).min(3);
+            assert_eq!(rolling_mean.n(), expected);
+            assert_eq!(rolling_sum.n(), expected);
+            assert_eq!(rolling_sum.inner().n(), expected);
+            assert_eq!(standalone_mean.n(), expected);
+            assert_eq!(rolling_quantile.n(), expected);
+            assert_eq!(rolling_arg_max.n(), expected);
+        }
+        assert_eq!(running_mean.n(), 3);
+    }
+    #[test]
+    fn try_update_surfaces_revert_errors() {
+        use crate::rolling::{Rolling, RollingOwned};
+        use crate::stats::{Revertable, Univariate};
+        // Running sum refusing to revert negative values
+        #[derive(Default)]
+        struct PositiveSum {
+            sum: f64,
+            n: usize,
+        }
+        impl Univariate<f64> for PositiveSum {
+            fn update(&mut self, x: f64) {
+                self.sum += x;
+                self.n += 1;
+            }
+            fn get(&self) -> f64 {
+                self.sum
+            }
+            fn n(&self) -> usize {
+                self.n
+            }
+        }
+        impl Revertable<f64> for PositiveSum {
+            fn revert(&mut self, x: f64) -> Result<(), &'static str> {
+                if x < 0. {
+                    return Err("Negative values cannot be reverted");
+                }
+                self.sum -= x;
+                self.n -= 1;
+                Ok(())
+            }
+        }
+        let mut positive_sum = PositiveSum::default();
+        let mut rolling_sum = Rolling::new(&mut positive_sum, 2).unwrap();
+        assert_eq!(rolling_sum.try_update(1.), Ok(()));
+        assert_eq!(rolling_sum.try_update(-2.), Ok(()));
+        assert_eq!(rolling_sum.try_update(3.), Ok(()));
+        assert_eq!(
+            rolling_sum.try_update(4.),
+            Err("Negative values cannot be reverted")
+        );
+        // The failed update left the window as it was
+        assert!(rolling_sum.window().eq([-2., 3.].iter()));
+        assert_eq!(rolling_sum.get(), 1.);
+        let mut owned = RollingOwned::new(PositiveSum::default(), 1).unwrap();
+        assert_eq!(owned.try_update(-1.), Ok(()));
+        assert!(owned.try_update(1.).is_err());
+        assert_eq!(owned.get(), -1.);
+    }
+    #[test]
+    fn bivariate_try_update() {
+        use crate::covariance::Covariance;
+        use crate::rolling::RollingBivariate;
+        use crate::stats::Bivariate;
+        let mut rolling_cov = RollingBivariate::new(Covariance::<f64>::default(), 2).unwrap();
+        for (x, y) in [(1., 5.), (2., 3.), (3., 4.)] {
+            assert_eq!(rolling_cov.try_update(x, y), Ok(()));
+        }
+        assert_eq!(rolling_cov.get(), 0.5);
+    }
+}
@@ -0,0 +1,315 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::maximum::{Max, RollingMax};
+use crate::minimum::{Min, RollingMin};
+use crate::stats::Univariate;
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+
+/// Running standard scaler, the z-score `(x - mean) / std` of the latest value.
+///
+/// `update` feeds the running mean and variance, `get` returns the z-score of the latest value and
+/// `transform` scales any value with the current statistics without updating them.
+/// When the standard deviation is `0`, e.g. after a single value or a constant stream, the z-score is `0`.
+/// `get` returns `NaN` until a value is seen.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom of the variance. The divisor used in calculations is `n - ddof`.
+/// * `score_before_update` - If `true`, the latest value is scored with the statistics of the
+///   previous values only, otherwise it is included in them before scoring.
+/// # Examples
+/// ```
+/// use watermill::scaler::StandardScaler;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let mut scaler: StandardScaler<f64> = StandardScaler::default();
+/// for x in data.into_iter(){
+///     scaler.update(x);
+/// }
+/// assert!((scaler.get() - (12. - 41. / 6.) / 12.566666666666668_f64.sqrt()).abs() < 1e-12);
+/// // Scaling without updating the statistics
+/// assert!((scaler.transform(41. / 6.)).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct StandardScaler<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+    pub score_before_update: bool,
+    score: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> StandardScaler<F> {
+    pub fn new(ddof: u32, score_before_update: bool) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+            score_before_update,
+            score: None,
+        }
+    }
+    /// Z-score of `x` with the current mean and standard deviation, `0` when the standard deviation is `0`.
+    pub fn transform(&self, x: F) -> F {
+        let std = self.variance.get().sqrt();
+        if std == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        (x - self.variance.mean.get()) / std
+    }
+}
+
+impl<F> Default for StandardScaler<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(1, false)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for StandardScaler<F> {
+    fn update(&mut self, x: F) {
+        if self.score_before_update {
+            self.score = Some(self.transform(x));
+            self.variance.update(x);
+        } else {
+            self.variance.update(x);
+            self.score = Some(self.transform(x));
+        }
+    }
+    fn get(&self) -> F {
+        self.score.unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        self.score
+    }
+    fn n(&self) -> usize {
+        self.variance.n()
+    }
+}
+
+/// Running min-max scaler, maps values to `[0, 1]` with `(x - min) / (max - min)`.
+///
+/// `update` feeds the running min and max, `get` returns the scaled latest value and
+/// `transform` scales any value with the current bounds without updating them. Values outside of
+/// the bounds seen so far are mapped outside of `[0, 1]`.
+/// `transform` returns `NaN` until a value is seen.
+/// # Arguments
+/// * `midpoint_if_constant` - Value returned when `max == min`, `0.5` if `true`, `0` otherwise.
+/// # Examples
+/// ```
+/// use watermill::scaler::MinMaxScaler;
+/// use watermill::stats::Univariate;
+/// let mut scaler: MinMaxScaler<f64> = MinMaxScaler::default();
+/// for x in vec![2., 10., 4.].into_iter(){
+///     scaler.update(x);
+/// }
+/// assert_eq!(scaler.get(), 0.25);
+/// assert_eq!(scaler.transform(6.), 0.5);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MinMaxScaler<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub min: Min<F>,
+    pub max: Max<F>,
+    pub midpoint_if_constant: bool,
+    score: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> MinMaxScaler<F> {
+    pub fn new(midpoint_if_constant: bool) -> Self {
+        Self {
+            min: Min::new(),
+            max: Max::new(),
+            midpoint_if_constant,
+            score: None,
+        }
+    }
+    /// `x` scaled with the current min and max.
+    pub fn transform(&self, x: F) -> F {
+        match (self.min.try_get(), self.max.try_get()) {
+            (Some(min), Some(max)) => scale(x, min, max, self.midpoint_if_constant),
+            _ => F::nan(),
+        }
+    }
+}
+
+impl<F> Default for MinMaxScaler<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for MinMaxScaler<F> {
+    fn update(&mut self, x: F) {
+        self.min.update(x);
+        self.max.update(x);
+        self.score = Some(self.transform(x));
+    }
+    fn get(&self) -> F {
+        self.score.unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        self.score
+    }
+    fn n(&self) -> usize {
+        self.max.n()
+    }
+}
+
+/// Rolling min-max scaler, the bounds are the min and max of the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `midpoint_if_constant` - Value returned when `max == min`, `0.5` if `true`, `0` otherwise.
+/// # Examples
+/// ```
+/// use watermill::scaler::RollingMinMaxScaler;
+/// use watermill::stats::Univariate;
+/// let mut scaler: RollingMinMaxScaler<f64> = RollingMinMaxScaler::new(2, false).unwrap();
+/// for x in vec![0., 10., 4., 6.].into_iter(){
+///     scaler.update(x);
+/// }
+/// // The window holds 4 and 6
+/// assert_eq!(scaler.get(), 1.);
+/// assert_eq!(scaler.transform(5.), 0.5);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingMinMaxScaler<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    min: RollingMin<F>,
+    max: RollingMax<F>,
+    pub midpoint_if_constant: bool,
+    score: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingMinMaxScaler<F> {
+    pub fn new(window_size: usize, midpoint_if_constant: bool) -> Result<Self, &'static str> {
+        Ok(Self {
+            min: RollingMin::new(window_size)?,
+            max: RollingMax::new(window_size)?,
+            midpoint_if_constant,
+            score: None,
+        })
+    }
+    /// `x` scaled with the min and max of the window.
+    pub fn transform(&self, x: F) -> F {
+        match (self.min.try_get(), self.max.try_get()) {
+            (Some(min), Some(max)) => scale(x, min, max, self.midpoint_if_constant),
+            _ => F::nan(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingMinMaxScaler<F> {
+    fn update(&mut self, x: F) {
+        self.min.update(x);
+        self.max.update(x);
+        self.score = Some(self.transform(x));
+    }
+    fn get(&self) -> F {
+        self.score.unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        self.score
+    }
+    fn n(&self) -> usize {
+        self.max.n()
+    }
+}
+
+fn scale<F: Float + FromPrimitive>(x: F, min: F, max: F, midpoint_if_constant: bool) -> F {
+    if max == min {
+        if midpoint_if_constant {
+            return F::from_f64(0.5).unwrap();
+        }
+        return F::from_f64(0.).unwrap();
+    }
+    (x - min) / (max - min)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn score_after_update() {
+        let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+        let mut scaler: StandardScaler<f64> = StandardScaler::new(1, false);
+        assert_eq!(scaler.try_get(), None);
+        assert!(scaler.get().is_nan());
+        for (i, x) in data.iter().enumerate() {
+            scaler.update(*x);
+            let seen = &data[..=i];
+            let n = seen.len() as f64;
+            let mean = seen.iter().sum::<f64>() / n;
+            let expected = if i == 0 {
+                0.
+            } else {
+                let variance = seen.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / (n - 1.);
+                (x - mean) / variance.sqrt()
+            };
+            assert!((scaler.get() - expected).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn score_before_update() {
+        let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+        let mut scaler: StandardScaler<f64> = StandardScaler::new(1, true);
+        for x in data.iter() {
+            scaler.update(*x);
+        }
+        // Mean 5.8 and variance 7.7 of the first five values
+        assert!((scaler.get() - (12. - 5.8) / 7.7_f64.sqrt()).abs() < 1e-12);
+        assert!((scaler.transform(41. / 6.)).abs() < 1e-12);
+    }
+    #[test]
+    fn min_max_scale_ramp() {
+        let mut scaler: MinMaxScaler<f64> = MinMaxScaler::default();
+        assert!(scaler.transform(1.).is_nan());
+        for i in 0..=10 {
+            scaler.update(i as f64);
+        }
+        for i in 0..=10 {
+            assert!((scaler.transform(i as f64) - i as f64 / 10.).abs() < 1e-12);
+        }
+        assert_eq!(scaler.get(), 1.);
+    }
+    #[test]
+    fn min_max_constant() {
+        let mut scaler: MinMaxScaler<f64> = MinMaxScaler::new(false);
+        let mut midpoint_scaler: MinMaxScaler<f64> = MinMaxScaler::new(true);
+        let mut rolling_scaler: RollingMinMaxScaler<f64> =
+            RollingMinMaxScaler::new(3, true).unwrap();
+        for _ in 0..5 {
+            scaler.update(2.);
+            midpoint_scaler.update(2.);
+            rolling_scaler.update(2.);
+            assert_eq!(scaler.get(), 0.);
+            assert_eq!(midpoint_scaler.get(), 0.5);
+            assert_eq!(rolling_scaler.get(), 0.5);
+        }
+    }
+    #[test]
+    fn rolling_min_max_rebases() {
+        let mut scaler: RollingMinMaxScaler<f64> = RollingMinMaxScaler::new(5, false).unwrap();
+        assert_eq!(scaler.try_get(), None);
+        for i in 0..20_usize {
+            scaler.update(i as f64);
+            // The window is the ramp [i - 4, i]
+            let min = i.saturating_sub(4) as f64;
+            assert_eq!(scaler.transform(min), 0.);
+            if i > 0 {
+                assert_eq!(scaler.get(), 1.);
+                assert_eq!(scaler.transform(i as f64), 1.);
+            }
+        }
+        assert_eq!(scaler.transform(17.), 0.5);
+        assert!(RollingMinMaxScaler::<f64>::new(0, false).is_err());
+    }
+    #[test]
+    fn zero_variance() {
+        let mut scaler: StandardScaler<f64> = StandardScaler::default();
+        for _ in 0..10 {
+            scaler.update(4.);
+            assert_eq!(scaler.get(), 0.);
+        }
+        assert_eq!(scaler.transform(100.), 0.);
+    }
+}
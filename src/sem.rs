@@ -0,0 +1,96 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::count::Count;
+use crate::stats::{Revertable, Univariate};
+use crate::variance::Variance;
+use serde::{Deserialize, Serialize};
+/// Running standard error of the mean, `std / sqrt(n)`.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom forwarded to the variance. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen elements. Defaults to `1`, the sample variance, use `0` for the population variance.
+/// # Examples
+/// ```
+/// use watermill::sem::SEM;
+/// use watermill::stats::{Univariate, Revertable};
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let data_revert = data.clone();
+/// let mut running_sem: SEM<f64> = SEM::default();
+/// for x in data.into_iter(){
+///     running_sem.update(x);
+/// }
+/// assert_eq!(running_sem.get(), (12.566666666666668_f64 / 6.).sqrt());
+/// // You can revert the standard error of the mean
+///
+/// for x in data_revert.into_iter().rev(){
+///     running_sem.revert(x);
+/// }
+/// assert_eq!(running_sem.get(), 0.);
+/// ```
+/// # References
+/// [^1]: [Wikipedia article on the standard error](https://www.wikiwand.com/en/Standard_error)
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SEM<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+    pub n: Count<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> SEM<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+            n: Count::new(),
+        }
+    }
+}
+
+impl<F> Default for SEM<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            variance: Variance::default(),
+            n: Count::new(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for SEM<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+        self.n.update(x);
+    }
+    fn get(&self) -> F {
+        let n = self.n.get();
+        if n == F::from_f64(0.).unwrap() {
+            return F::from_f64(0.).unwrap();
+        }
+        (self.variance.get() / n).sqrt()
+    }
+    fn n(&self) -> usize {
+        self.n.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for SEM<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.variance.revert(x)?;
+        self.n.revert(x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_sem() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut running_sem: SEM<f64> = SEM::new(0);
+        let mut rolling_sem = Rolling::new(&mut running_sem, 4).unwrap();
+        for x in data.into_iter() {
+            rolling_sem.update(x);
+        }
+        // Last window is [1, 8, 5, 4], population variance 6.25
+        assert!((rolling_sem.get() - (6.25_f64 / 4.).sqrt()).abs() < 1e-12);
+    }
+}
@@ -1,8 +1,9 @@
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::moments::CentralMoments;
-use crate::stats::Univariate;
+use crate::stats::{Revertable, Univariate};
 use serde::{Deserialize, Serialize};
 /// Running Skew.
 /// # Arguments
@@ -31,6 +32,19 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_skew.get(),0.7712778091518129);
 /// ```
+/// Rolling skew over the `3` latest values.
+/// ```
+/// use watermill::rolling::Rolling;
+/// use watermill::skew::Skew;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![ 0.49671415, -0.1382643 ,  0.64768854,  1.52302986, -0.23415337,-0.23413696];
+/// let mut running_skew: Skew<f64> = Skew::new(true);
+/// let mut rolling_skew = Rolling::new(&mut running_skew, 3).unwrap();
+/// for x in data.iter(){
+///     rolling_skew.update(*x);
+/// }
+/// assert!((rolling_skew.get() - 0.7071067811865476).abs() < 1e-6);
+/// ```
 /// # References
 /// [^1]: [Wikipedia article on algorithms for calculating variance](https://www.wikiwand.com/en/Algorithms_for_calculating_variance#/Covariance)
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -83,4 +97,164 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Skew<F>
         }
         skew
     }
+    fn n(&self) -> usize {
+        self.central_moments.count.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Skew<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.central_moments.count.revert(x)?;
+        if self.central_moments.count.get() == F::from_f64(0.).unwrap() {
+            self.central_moments = CentralMoments::new();
+            return Ok(());
+        }
+        self.central_moments.revert_delta(x);
+        self.central_moments.revert_sum_delta();
+        self.central_moments.revert_m1(x);
+        self.central_moments.revert_m2();
+        self.central_moments.revert_m3();
+        Ok(())
+    }
+}
+
+/// Rolling skew.
+///
+/// Unlike `Rolling<Skew<F>>`, it owns its window and can be stored as a plain struct field.
+/// The oldest value is reverted from the central moments, which costs `O(1)` per update but lets
+/// rounding errors accumulate, they stay negligible unless the values are large compared to their spread.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `bias` - If `false`, then the calculations are corrected for statistical bias.
+/// # Examples
+/// ```
+/// use watermill::skew::RollingSkew;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![ 0.49671415, -0.1382643 ,  0.64768854,  1.52302986, -0.23415337,-0.23413696];
+/// let mut rolling_skew: RollingSkew<f64> = RollingSkew::new(3, true).unwrap();
+/// for x in data.into_iter(){
+///     rolling_skew.update(x);
+/// }
+/// assert!((rolling_skew.get() - 0.7071067811865476).abs() < 1e-6);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingSkew<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    skew: Skew<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingSkew<F> {
+    pub fn new(window_size: usize, bias: bool) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            skew: Skew::new(bias),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingSkew<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.skew
+                .revert(oldest)
+                .expect("The window holds as many values as the skew");
+        }
+        self.window.push_back(x);
+        self.skew.update(x);
+    }
+    fn get(&self) -> F {
+        self.skew.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    const DATA: [f64; 10] = [
+        0.49671415,
+        -0.1382643,
+        0.64768854,
+        1.52302986,
+        -0.23415337,
+        -0.23413696,
+        1.57921282,
+        0.76743473,
+        -0.46947439,
+        0.54256004,
+    ];
+    #[test]
+    fn rolling_skew_matches_window() {
+        let mut running_skew: Skew<f64> = Skew::default();
+        let mut rolling_skew = Rolling::new(&mut running_skew, 5).unwrap();
+        for (i, x) in DATA.iter().enumerate() {
+            rolling_skew.update(*x);
+            let mut window_skew: Skew<f64> = Skew::default();
+            for y in DATA[(i + 1).saturating_sub(5)..=i].iter() {
+                window_skew.update(*y);
+            }
+            assert!((rolling_skew.get() - window_skew.get()).abs() < 1e-9);
+        }
+        // scipy.stats.skew(DATA[-5:], bias=False)
+        assert!((rolling_skew.get() - 0.36072213880095805).abs() < 1e-9);
+    }
+    // scipy.stats.skew recomputed from the central moments of the window
+    fn brute_force(window: &[f64], bias: bool) -> f64 {
+        let n = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / n;
+        let m2 = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let m3 = window.iter().map(|x| (x - mean).powi(3)).sum::<f64>() / n;
+        if m2 == 0. {
+            return 0.;
+        }
+        let skew = m3 / m2.powf(1.5);
+        if !bias && n > 2. {
+            return (n * (n - 1.)).sqrt() / (n - 2.) * skew;
+        }
+        skew
+    }
+    #[test]
+    fn standalone_rolling_skew() {
+        let mut state: u64 = 3;
+        let data: Vec<f64> = (0..1000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+                // Right skewed values
+                -u.ln()
+            })
+            .collect();
+        for bias in [false, true] {
+            let mut rolling_skew: RollingSkew<f64> = RollingSkew::new(20, bias).unwrap();
+            for (i, x) in data.iter().enumerate() {
+                rolling_skew.update(*x);
+                let window = &data[(i + 1).saturating_sub(20)..=i];
+                assert!((rolling_skew.get() - brute_force(window, bias)).abs() < 1e-6);
+            }
+        }
+        assert!(RollingSkew::<f64>::new(0, false).is_err());
+    }
+    #[test]
+    fn revert_to_empty() {
+        let mut skew: Skew<f64> = Skew::default();
+        for x in DATA.iter() {
+            skew.update(*x);
+        }
+        for x in DATA.iter() {
+            skew.revert(*x).unwrap();
+        }
+        assert_eq!(skew.get(), 0.);
+        assert!(skew.revert(1.).is_err());
+    }
 }
@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Save a statistic to bytes, e.g. to checkpoint a long-running estimator, see `from_bytes`.
+///
+/// The bytes are the `bincode` encoding of the statistic, `NaN` and infinite floats are kept
+/// as they are. Only available with the `bincode` feature.
+/// # Examples
+/// ```
+/// use watermill::quantile::Quantile;
+/// use watermill::snapshot;
+/// use watermill::stats::Univariate;
+/// let mut running_median: Quantile<f64> = Quantile::new(0.5_f64).unwrap();
+/// for i in 0..10{
+///     running_median.update(i as f64);
+/// }
+/// let bytes = snapshot::to_bytes(&running_median);
+/// let restored: Quantile<f64> = snapshot::from_bytes(&bytes).unwrap();
+/// assert_eq!(restored.get(), running_median.get());
+/// ```
+pub fn to_bytes<T: Serialize>(stat: &T) -> Vec<u8> {
+    bincode::serialize(stat).expect("Statistics serialize without error")
+}
+
+/// Restore a statistic saved with `to_bytes`.
+/// # Errors
+/// Fails when the bytes are not the encoding of a `T`.
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, bincode::Error> {
+    bincode::deserialize(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::finite::{Finite, NonFinitePolicy};
+    use crate::maximum::Max;
+    use crate::mean::Mean;
+    use crate::quantile::Quantile;
+    use crate::stats::Univariate;
+    use crate::sum::Sum;
+    #[test]
+    fn resume_quantile() {
+        let data: Vec<f64> = (0..500).map(|i| ((i * 37) % 101) as f64 / 7.).collect();
+        let mut uninterrupted: Quantile<f64> = Quantile::new(0.9_f64).unwrap();
+        let mut checkpointed: Quantile<f64> = Quantile::new(0.9_f64).unwrap();
+        for x in data[..250].iter() {
+            uninterrupted.update(*x);
+            checkpointed.update(*x);
+        }
+        let bytes = to_bytes(&checkpointed);
+        let mut restored: Quantile<f64> = from_bytes(&bytes).unwrap();
+        for x in data[250..].iter() {
+            uninterrupted.update(*x);
+            restored.update(*x);
+            assert_eq!(restored.get(), uninterrupted.get());
+        }
+    }
+    #[test]
+    fn non_finite_states() {
+        let mut mean: Mean<f64> = Mean::new();
+        mean.update(1.);
+        mean.update(f64::NAN);
+        let restored: Mean<f64> = from_bytes(&to_bytes(&mean)).unwrap();
+        assert!(restored.get().is_nan());
+        assert_eq!(restored.n(), 2);
+
+        for x in [f64::INFINITY, f64::NEG_INFINITY] {
+            let mut sum: Sum<f64> = Sum::new();
+            sum.update(1.);
+            sum.update(x);
+            let restored: Sum<f64> = from_bytes(&to_bytes(&sum)).unwrap();
+            assert_eq!(restored.get(), x);
+
+            let mut max: Max<f64> = Max::new();
+            max.update(x);
+            max.update(1.);
+            let restored: Max<f64> = from_bytes(&to_bytes(&max)).unwrap();
+            assert_eq!(restored.get(), x.max(1.));
+        }
+
+        let mut propagated = Finite::new(Sum::<f64>::new(), NonFinitePolicy::Propagate);
+        propagated.update(f64::INFINITY);
+        propagated.update(f64::NEG_INFINITY);
+        let mut restored: Finite<Sum<f64>, f64> = from_bytes(&to_bytes(&propagated)).unwrap();
+        assert!(restored.get().is_nan());
+        restored.update(1.);
+        assert!(restored.get().is_nan());
+    }
+    #[test]
+    fn invalid_bytes() {
+        assert!(from_bytes::<Quantile<f64>>(b"not a quantile").is_err());
+    }
+}
@@ -1,31 +1,216 @@
+use alloc::collections::VecDeque;
+use alloc::{vec, vec::Vec};
+use core::ops::{AddAssign, Index, SubAssign};
 use num::{Float, FromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::VecDeque,
-    ops::{AddAssign, Index, SubAssign},
-};
+
+/// Window size used by the `Default` implementations of the rolling statistics.
+pub const DEFAULT_WINDOW_SIZE: usize = 100;
+
+/// How a `SortedWindow` handles `NaN` values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NanPolicy {
+    /// `NaN` values are dropped and do not take a slot in the window.
+    Skip,
+    /// Pushing a `NaN` value panics.
+    #[default]
+    Panic,
+    /// `NaN` values are stored and ordered after every other value, they are evicted like any
+    /// other value once they fall out of the window.
+    Propagate,
+}
+
+// Ordering used by the window, NaN values are greater than every other value and equal to each other
+fn less<F: Float>(a: F, b: F) -> bool {
+    !a.is_nan() && (b.is_nan() || a < b)
+}
+
+// Chunks are split once they hold twice this many values
+const CHUNK_SIZE: usize = 512;
+
+/// Sorted multiset stored as a list of sorted chunks, a two level B-tree.
+///
+/// Each chunk holds at most `2 * CHUNK_SIZE` values, a value is located by binary searching the
+/// chunk maxima and then the chunk itself. The chunk lengths are indexed by a Fenwick tree so
+/// that accessing a value by rank is `O(log n)`. Insertion and removal only shift the values of a
+/// single chunk instead of the whole window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SortedValues<F> {
+    chunks: Vec<Vec<F>>,
+    maxes: Vec<F>,
+    // Fenwick tree over the chunk lengths
+    index: Vec<usize>,
+    len: usize,
+}
+
+impl<F: Float> SortedValues<F> {
+    pub(crate) fn new() -> Self {
+        Self {
+            chunks: Vec::new(),
+            maxes: Vec::new(),
+            index: Vec::new(),
+            len: 0,
+        }
+    }
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Value of rank `index`, starting at `0` for the smallest.
+    pub(crate) fn get(&self, index: usize) -> Option<&F> {
+        if index >= self.len {
+            return None;
+        }
+        // Descend the Fenwick tree to find the chunk holding the value
+        let mut chunk = 0;
+        let mut remaining = index;
+        let mut step = self.index.len().next_power_of_two();
+        while step > 0 {
+            let next = chunk + step;
+            if next <= self.index.len() && self.index[next - 1] <= remaining {
+                chunk = next;
+                remaining -= self.index[next - 1];
+            }
+            step /= 2;
+        }
+        self.chunks[chunk].get(remaining)
+    }
+    pub(crate) fn front(&self) -> Option<&F> {
+        self.chunks.first().and_then(|chunk| chunk.first())
+    }
+    pub(crate) fn back(&self) -> Option<&F> {
+        self.maxes.last()
+    }
+    /// Values in ascending order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &F> + '_ {
+        self.chunks.iter().flatten()
+    }
+    pub(crate) fn insert(&mut self, value: F) {
+        if self.chunks.is_empty() {
+            self.chunks.push(vec![value]);
+            self.maxes.push(value);
+            self.len = 1;
+            self.rebuild_index();
+            return;
+        }
+        // Equal values are kept in insertion order, the new one goes after them
+        let chunk = self
+            .maxes
+            .partition_point(|max| !less(value, *max))
+            .min(self.chunks.len() - 1);
+        let position = self.chunks[chunk].partition_point(|x| !less(value, *x));
+        self.chunks[chunk].insert(position, value);
+        self.len += 1;
+        if position == self.chunks[chunk].len() - 1 {
+            self.maxes[chunk] = value;
+        }
+        if self.chunks[chunk].len() > 2 * CHUNK_SIZE {
+            let upper = self.chunks[chunk].split_off(CHUNK_SIZE);
+            self.maxes[chunk] = self.chunks[chunk][CHUNK_SIZE - 1];
+            self.maxes.insert(chunk + 1, upper[upper.len() - 1]);
+            self.chunks.insert(chunk + 1, upper);
+            self.rebuild_index();
+        } else {
+            self.add_to_index(chunk, true);
+        }
+    }
+    /// Remove one occurrence of `value`, returns `false` if there is none.
+    pub(crate) fn remove(&mut self, value: F) -> bool {
+        let chunk = self.maxes.partition_point(|max| less(*max, value));
+        if chunk == self.chunks.len() {
+            return false;
+        }
+        let position = self.chunks[chunk].partition_point(|x| less(*x, value));
+        if less(value, self.chunks[chunk][position]) {
+            return false;
+        }
+        self.chunks[chunk].remove(position);
+        self.len -= 1;
+        if self.chunks[chunk].is_empty() {
+            self.chunks.remove(chunk);
+            self.maxes.remove(chunk);
+            self.rebuild_index();
+        } else if self.chunks[chunk].len() < CHUNK_SIZE / 2 && self.chunks.len() > 1 {
+            // Merge small chunks with a neighbour to keep the number of chunks bounded
+            let lower = if chunk + 1 < self.chunks.len() {
+                chunk
+            } else {
+                chunk - 1
+            };
+            let upper = self.chunks.remove(lower + 1);
+            self.maxes.remove(lower + 1);
+            self.chunks[lower].extend(upper);
+            self.maxes[lower] = self.chunks[lower][self.chunks[lower].len() - 1];
+            if self.chunks[lower].len() > 2 * CHUNK_SIZE {
+                let upper = self.chunks[lower].split_off(CHUNK_SIZE);
+                self.maxes[lower] = self.chunks[lower][CHUNK_SIZE - 1];
+                self.maxes.insert(lower + 1, upper[upper.len() - 1]);
+                self.chunks.insert(lower + 1, upper);
+            }
+            self.rebuild_index();
+        } else {
+            self.maxes[chunk] = self.chunks[chunk][self.chunks[chunk].len() - 1];
+            self.add_to_index(chunk, false);
+        }
+        true
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index = self.chunks.iter().map(|chunk| chunk.len()).collect();
+        for i in 1..=self.index.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= self.index.len() {
+                self.index[parent - 1] += self.index[i - 1];
+            }
+        }
+    }
+    fn add_to_index(&mut self, chunk: usize, increment: bool) {
+        let mut i = chunk + 1;
+        while i <= self.index.len() {
+            if increment {
+                self.index[i - 1] += 1;
+            } else {
+                self.index[i - 1] -= 1;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+}
 
 #[doc(hidden)]
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SortedWindow<F: Float + FromPrimitive + AddAssign + SubAssign> {
-    pub(crate) sorted_window: VecDeque<F>,
+    pub(crate) sorted_window: SortedValues<F>,
     pub(crate) unsorted_window: VecDeque<F>,
     window_size: usize,
+    nan_policy: NanPolicy,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> SortedWindow<F> {
-    pub fn new(window_size: usize) -> Self {
-        Self {
-            sorted_window: VecDeque::with_capacity(window_size),
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        Self::with_nan_policy(window_size, NanPolicy::Panic)
+    }
+    pub fn with_nan_policy(
+        window_size: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            sorted_window: SortedValues::new(),
             unsorted_window: VecDeque::with_capacity(window_size),
             window_size,
-        }
+            nan_policy,
+        })
     }
     pub fn len(&self) -> usize {
         self.sorted_window.len()
     }
     pub fn is_empty(&self) -> bool {
-        self.sorted_window.len() == 0
+        self.sorted_window.is_empty()
     }
 
     pub fn front(&self) -> F {
@@ -36,11 +221,48 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> SortedWindow<F> {
         *self.sorted_window.back().expect("Window is empty")
     }
 
+    /// Values of the window in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &F> + '_ {
+        self.sorted_window.iter()
+    }
+
+    /// Values of the window in insertion order, from the oldest to the newest.
+    pub fn window(&self) -> impl Iterator<Item = &F> + '_ {
+        self.unsorted_window.iter()
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Change the size of the window. Growing keeps every value, shrinking evicts the oldest values
+    /// until at most `window_size` are left.
+    pub fn resize(&mut self, window_size: usize) -> Result<(), &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        while self.unsorted_window.len() > window_size {
+            let oldest = self
+                .unsorted_window
+                .pop_front()
+                .expect("Unsorted window should not be empty when it is larger than the window");
+            let removed = self.sorted_window.remove(oldest);
+            assert!(
+                removed,
+                "The value to remove was not found in the sorted window"
+            );
+        }
+        self.window_size = window_size;
+        Ok(())
+    }
+
     pub fn push_back(&mut self, value: F) {
-        // This will panic if `value` is NaN, which is the desired behavior
-        // to maintain a sorted list of non-NaN floats.
         if value.is_nan() {
-            panic!("Cannot push a NaN value into SortedWindow");
+            match self.nan_policy {
+                NanPolicy::Skip => return,
+                NanPolicy::Panic => panic!("Cannot push a NaN value into SortedWindow"),
+                NanPolicy::Propagate => {}
+            }
         }
 
         // Before add the newest value to the sorted window
@@ -50,40 +272,24 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> SortedWindow<F> {
                 .unsorted_window
                 .pop_front()
                 .expect("Unsorted window should not be empty when sorted window is full");
-
-            // Find the position of the value to remove using a custom comparison.
-            // `partial_cmp` returns None for NaN comparisons, so `expect` will panic,
-            // which is consistent with the behavior of NotNan.
-            let pos_to_remove = self
-                .sorted_window
-                .binary_search_by(|probe| {
-                    probe
-                        .partial_cmp(&oldest_unsorted)
-                        .expect("Stored values should not be NaN")
-                })
-                .expect("The value to remove was not found in the sorted window");
-
-            self.sorted_window.remove(pos_to_remove);
+            let removed = self.sorted_window.remove(oldest_unsorted);
+            assert!(
+                removed,
+                "The value to remove was not found in the sorted window"
+            );
         }
 
         self.unsorted_window.push_back(value);
-
-        let sorted_pos = self
-            .sorted_window
-            .binary_search_by(|probe| {
-                probe
-                    .partial_cmp(&value)
-                    .expect("Stored values should not be NaN")
-            })
-            .unwrap_or_else(|e| e);
-        self.sorted_window.insert(sorted_pos, value);
+        self.sorted_window.insert(value);
     }
 }
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Index<usize> for SortedWindow<F> {
     type Output = F;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.sorted_window[index]
+        self.sorted_window
+            .get(index)
+            .expect("Index out of bounds of the window")
     }
 }
 
@@ -94,7 +300,7 @@ mod tests {
 
     #[test]
     fn test_new_and_empty() {
-        let window: SortedWindow<f64> = SortedWindow::new(5);
+        let window: SortedWindow<f64> = SortedWindow::new(5).unwrap();
         assert!(window.is_empty());
         assert_eq!(window.len(), 0);
         assert_eq!(window.window_size, 5);
@@ -102,7 +308,7 @@ mod tests {
 
     #[test]
     fn test_push_and_sort() {
-        let mut window = SortedWindow::new(5);
+        let mut window = SortedWindow::new(5).unwrap();
         window.push_back(10.0);
         window.push_back(5.0);
         window.push_back(15.0);
@@ -128,12 +334,12 @@ mod tests {
 
     #[test]
     fn test_window_full_cycle() {
-        let mut window = SortedWindow::new(3);
+        let mut window = SortedWindow::new(3).unwrap();
 
         // 1. Fill the window
         window.push_back(10.0); // unsorted: [10], sorted: [10]
         window.push_back(20.0); // unsorted: [10, 20], sorted: [10, 20]
-        window.push_back(5.0);  // unsorted: [10, 20, 5], sorted: [5, 10, 20]
+        window.push_back(5.0); // unsorted: [10, 20, 5], sorted: [5, 10, 20]
 
         assert_eq!(window.len(), 3);
         assert_eq!(window.front(), 5.0);
@@ -144,8 +350,7 @@ mod tests {
         );
 
         // 2. Push a new element, should remove the oldest (10.0)
-        window.push_back(15.0); // oldest '10.0' is removed
-        // unsorted: [20, 5, 15], sorted: [5, 15, 20]
+        window.push_back(15.0); // oldest '10.0' is removed, unsorted: [20, 5, 15], sorted: [5, 15, 20]
 
         assert_eq!(window.len(), 3);
         assert_eq!(window.front(), 5.0);
@@ -160,8 +365,7 @@ mod tests {
         );
 
         // 3. Push another new element, should remove the oldest (20.0)
-        window.push_back(2.0); // oldest '20.0' is removed
-        // unsorted: [5, 15, 2], sorted: [2, 5, 15]
+        window.push_back(2.0); // oldest '20.0' is removed, unsorted: [5, 15, 2], sorted: [2, 5, 15]
 
         assert_eq!(window.len(), 3);
         assert_eq!(window.front(), 2.0);
@@ -178,7 +382,7 @@ mod tests {
 
     #[test]
     fn test_with_duplicate_values() {
-        let mut window = SortedWindow::new(4);
+        let mut window = SortedWindow::new(4).unwrap();
         window.push_back(10.0);
         window.push_back(5.0);
         window.push_back(10.0); // Duplicate value
@@ -212,7 +416,7 @@ mod tests {
 
     #[test]
     fn test_window_size_one() {
-        let mut window = SortedWindow::new(1);
+        let mut window = SortedWindow::new(1).unwrap();
 
         window.push_back(10.0);
         assert_eq!(window.len(), 1);
@@ -231,42 +435,202 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn test_window_size_zero() {
-        let mut window = SortedWindow::new(0);
-        window.push_back(10.0);
-
-        assert_eq!(window.len(), 0);
-        assert!(window.is_empty());
-        assert!(window.unsorted_window.is_empty());
+        let window: Result<SortedWindow<f64>, _> = SortedWindow::new(0);
+        assert_eq!(window.err(), Some("Window size should not equal to 0"));
+        let window: Result<SortedWindow<f64>, _> =
+            SortedWindow::with_nan_policy(0, NanPolicy::Skip);
+        assert!(window.is_err());
     }
 
     #[test]
     #[should_panic(expected = "Cannot push a NaN value into SortedWindow")]
     fn test_panic_on_nan_push() {
-        let mut window = SortedWindow::new(3);
+        let mut window = SortedWindow::new(3).unwrap();
         window.push_back(f64::NAN);
     }
 
     #[test]
     #[should_panic(expected = "Window is empty")]
     fn test_panic_on_front_empty() {
-        let window: SortedWindow<f64> = SortedWindow::new(3);
+        let window: SortedWindow<f64> = SortedWindow::new(3).unwrap();
         window.front();
     }
 
     #[test]
     #[should_panic(expected = "Window is empty")]
     fn test_panic_on_back_empty() {
-        let window: SortedWindow<f64> = SortedWindow::new(3);
+        let window: SortedWindow<f64> = SortedWindow::new(3).unwrap();
         window.back();
     }
 
     #[test]
     #[should_panic]
     fn test_panic_on_index_out_of_bounds() {
-        let mut window = SortedWindow::new(3);
+        let mut window = SortedWindow::new(3).unwrap();
         window.push_back(1.0);
         let _ = window[1]; // Should panic
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_public_views() {
+        let mut window = SortedWindow::new(4).unwrap();
+        for value in [5.0, 1.0, 4.0, 2.0, 3.0] {
+            window.push_back(value);
+        }
+        assert_eq!(
+            window.iter().copied().collect::<Vec<_>>(),
+            vec![1.0, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            window.window().copied().collect::<Vec<_>>(),
+            vec![1.0, 4.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_resize() {
+        let data = [5.0, 1.0, 4.0, 2.0, 3.0, 7.0, 6.0];
+        let mut window = SortedWindow::with_nan_policy(5, NanPolicy::Propagate).unwrap();
+        for value in data[..4].iter() {
+            window.push_back(*value);
+        }
+        window.push_back(f64::NAN);
+        // Shrinking a full window evicts 5, 1 and 4
+        window.resize(2).unwrap();
+        assert_eq!(window.window_size(), 2);
+        assert_eq!(window.window().copied().collect::<Vec<_>>()[0], 2.0);
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.front(), 2.0);
+        assert!(window.back().is_nan());
+        // Growing keeps the values and fills up before evicting again
+        window.resize(4).unwrap();
+        for value in data[4..].iter() {
+            window.push_back(*value);
+        }
+        assert_eq!(window.len(), 4);
+        assert_eq!(window.front(), 3.0);
+        assert_eq!(window[1], 6.0);
+        assert!(window.back().is_nan());
+        window.push_back(8.0);
+        assert_eq!(
+            window.iter().copied().collect::<Vec<_>>(),
+            vec![3.0, 6.0, 7.0, 8.0]
+        );
+        assert!(window.resize(0).is_err());
+        assert_eq!(window.window_size(), 4);
+    }
+
+    #[test]
+    fn test_nan_policy_skip() {
+        let mut window = SortedWindow::with_nan_policy(2, NanPolicy::Skip).unwrap();
+        window.push_back(1.0);
+        window.push_back(f64::NAN);
+        window.push_back(2.0);
+        assert_eq!(window.iter().copied().collect::<Vec<_>>(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_nan_policy_propagate() {
+        let mut window = SortedWindow::with_nan_policy(3, NanPolicy::Propagate).unwrap();
+        window.push_back(2.0);
+        window.push_back(f64::NAN);
+        window.push_back(1.0);
+        assert_eq!(window.front(), 1.0);
+        assert_eq!(window[1], 2.0);
+        assert!(window.back().is_nan());
+        window.push_back(f64::NAN);
+        window.push_back(3.0);
+        // The first NaN is out of the window, the second one is still there
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.front(), 1.0);
+        assert!(window[2].is_nan());
+        window.push_back(4.0);
+        assert_eq!(window.front(), 3.0);
+        assert_eq!(window[1], 4.0);
+        assert!(window.back().is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot push a NaN value into SortedWindow")]
+    fn test_nan_policy_panic() {
+        let mut window = SortedWindow::with_nan_policy(3, NanPolicy::Panic).unwrap();
+        window.push_back(f64::NAN);
+    }
+
+    // The previous implementation, keeping the sorted values in a `VecDeque`
+    struct NaiveSortedWindow {
+        sorted_window: VecDeque<f64>,
+        unsorted_window: VecDeque<f64>,
+        window_size: usize,
+    }
+
+    impl NaiveSortedWindow {
+        fn push_back(&mut self, value: f64) {
+            if self.sorted_window.len() == self.window_size {
+                let oldest = self.unsorted_window.pop_front().unwrap();
+                let pos = self
+                    .sorted_window
+                    .binary_search_by(|probe| probe.partial_cmp(&oldest).unwrap())
+                    .unwrap();
+                self.sorted_window.remove(pos);
+            }
+            self.unsorted_window.push_back(value);
+            let pos = self
+                .sorted_window
+                .binary_search_by(|probe| probe.partial_cmp(&value).unwrap())
+                .unwrap_or_else(|e| e);
+            self.sorted_window.insert(pos, value);
+        }
+    }
+
+    #[test]
+    fn test_matches_naive_implementation() {
+        let mut state: u64 = 42;
+        // The larger windows span several chunks, which are split and merged as values come and go
+        for window_size in [1, 2, 3, 5, 8, 13, 64, 257, 1500, 4000] {
+            for distinct_values in [3, 50, 1_000_000] {
+                let mut window = SortedWindow::new(window_size).unwrap();
+                let mut naive = NaiveSortedWindow {
+                    sorted_window: VecDeque::new(),
+                    unsorted_window: VecDeque::new(),
+                    window_size,
+                };
+                for _ in 0..(3 * window_size + 2000) {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    let x = ((state >> 33) % distinct_values) as f64 - 10.;
+                    window.push_back(x);
+                    naive.push_back(x);
+                    assert_eq!(window.len(), naive.sorted_window.len());
+                    assert_eq!(window.front(), naive.sorted_window[0]);
+                    assert_eq!(window.back(), *naive.sorted_window.back().unwrap());
+                    let k = (state as usize) % window.len();
+                    assert_eq!(window[k], naive.sorted_window[k]);
+                }
+                assert!(window
+                    .iter()
+                    .copied()
+                    .eq(naive.sorted_window.iter().copied()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut window = SortedWindow::new(3).unwrap();
+        for x in [4., 1., 3., 2.] {
+            window.push_back(x);
+        }
+        let serialized = serde_json::to_string(&window).unwrap();
+        let mut deserialized: SortedWindow<f64> = serde_json::from_str(&serialized).unwrap();
+        deserialized.push_back(0.);
+        window.push_back(0.);
+        assert!(window.iter().eq(deserialized.iter()));
+        assert_eq!(
+            deserialized.iter().copied().collect::<Vec<_>>(),
+            vec![0., 2., 3.]
+        );
+    }
+}
@@ -1,8 +1,89 @@
-use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive, PrimInt};
+/// Running statistics of a single variable.
+///
+/// The trait is object safe, so statistics of different types can be stored together as
+/// `Box<dyn Univariate<F>>`, see `compose::Tee`. Methods generic over their arguments, like
+/// `update_many`, require `Self: Sized` to keep it that way and are not available on trait objects.
+/// # Examples
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::quantile::Quantile;
+/// use watermill::stats::Univariate;
+/// use watermill::variance::Variance;
+/// let mut stats: Vec<Box<dyn Univariate<f64>>> = vec![
+///     Box::new(Mean::new()),
+///     Box::new(Variance::default()),
+///     Box::new(Quantile::new(0.5).unwrap()),
+/// ];
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     for stat in stats.iter_mut(){
+///         stat.update(x);
+///     }
+/// }
+/// let values: Vec<f64> = stats.iter().map(|stat| stat.get()).collect();
+/// assert_eq!(values[0], 5.);
+/// assert!((values[1] - 7.5).abs() < 1e-12);
+/// assert_eq!(values[2], 5.);
+/// assert!(stats.iter().all(|stat| stat.n() == 9));
+/// ```
 pub trait Univariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
     fn update(&mut self, x: F);
     fn get(&self) -> F;
+    /// Number of values currently contributing to the statistic, i.e. the number of updates for
+    /// running statistics and the number of values in the window for rolling ones. Weighted
+    /// updates count for their weight, rounded to the nearest integer. Defaults to `0` for
+    /// statistics that do not keep track of it, every statistic of this crate overrides it.
+    fn n(&self) -> usize {
+        0
+    }
+    /// Current value of the statistic, or `None` if it is not defined yet, e.g. the max of an
+    /// empty stream. Defaults to `Some(self.get())`.
+    fn try_get(&self) -> Option<F> {
+        Some(self.get())
+    }
+    /// Update the statistic with every value of `iter`, the same as calling `update` on each of
+    /// them in order.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// running_mean.update_many(data.iter().copied());
+    /// assert_eq!(running_mean.get(), 5.);
+    /// ```
+    fn update_many<I: IntoIterator<Item = F>>(&mut self, iter: I)
+    where
+        Self: Sized,
+    {
+        for x in iter {
+            self.update(x);
+        }
+    }
+    /// Update the statistic with `x` only if it is finite, `NaN` and infinite values are skipped
+    /// instead of poisoning the statistic. See `finite::Finite` to reject them or count them.
+    /// # Examples
+    /// ```
+    /// use watermill::mean::Mean;
+    /// use watermill::stats::Univariate;
+    /// let mut running_mean: Mean<f64> = Mean::new();
+    /// for x in [1., f64::NAN, 2., f64::INFINITY, 3.]{
+    ///     running_mean.update_if_finite(x);
+    /// }
+    /// assert_eq!(running_mean.get(), 2.);
+    /// ```
+    fn update_if_finite(&mut self, x: F) {
+        if x.is_finite() {
+            self.update(x);
+        }
+    }
+}
+
+/// Statistics over integers whose result is exact, see `sum::IntSum` and `count::IntCount`.
+pub trait IntUnivariate<I: PrimInt> {
+    fn update(&mut self, x: I);
+    fn get(&self) -> I;
 }
 
 pub trait Bivariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
@@ -10,11 +91,128 @@ pub trait Bivariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
     fn get(&self) -> F;
 }
 
+/// Statistics that can forget a value previously passed to `update`.
+///
+/// Calling `update(x)` followed by `revert(x)` must leave the statistic as it was before the
+/// `update`, up to floating point rounding. `revert` is only ever called with values that were
+/// updated with, in the order they were received (oldest first), which is what `rolling::Rolling`
+/// relies on to maintain a window. An `Err` should be returned when the value cannot be removed,
+/// e.g. when nothing is left to revert.
+///
+/// Any type implementing both `Univariate` and `Revertable` is a `RollableUnivariate` and can be
+/// wrapped in a `rolling::Rolling`.
+/// # Examples
+/// ```
+/// use watermill::rolling::Rolling;
+/// use watermill::stats::{Revertable, Univariate};
+/// // Running sum of squares
+/// #[derive(Default)]
+/// struct SumOfSquares {
+///     sum: f64,
+/// }
+/// impl Univariate<f64> for SumOfSquares {
+///     fn update(&mut self, x: f64) {
+///         self.sum += x * x;
+///     }
+///     fn get(&self) -> f64 {
+///         self.sum
+///     }
+/// }
+/// impl Revertable<f64> for SumOfSquares {
+///     fn revert(&mut self, x: f64) -> Result<(), &'static str> {
+///         self.sum -= x * x;
+///         Ok(())
+///     }
+/// }
+/// let mut sum_of_squares = SumOfSquares::default();
+/// let mut rolling_sum_of_squares = Rolling::new(&mut sum_of_squares, 2).unwrap();
+/// for x in vec![5., 1., 2.].into_iter(){
+///     rolling_sum_of_squares.update(x);
+/// }
+/// assert_eq!(rolling_sum_of_squares.get(), 5.);
+/// ```
 pub trait Revertable<F: Float + FromPrimitive + AddAssign + SubAssign> {
     fn revert(&mut self, x: F) -> Result<(), &'static str>;
 }
 
+/// Statistics accepting a weight with each value, e.g. pre-aggregated data where `x` occurred `w` times.
+///
+/// Weights are frequencies, `update_weighted(x, w)` with an integer `w` gives the same statistic
+/// as calling `update(x)` `w` times, up to floating point rounding.
+/// # Examples
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::stats::{Univariate, WeightedUnivariate};
+/// let mut running_mean: Mean<f64> = Mean::new();
+/// running_mean.update_weighted(5., 1000.);
+/// running_mean.update(-5.);
+/// assert!((running_mean.get() - 4995. / 1001.).abs() < 1e-12);
+/// ```
+pub trait WeightedUnivariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    fn update_weighted(&mut self, x: F, w: F);
+}
+
+/// Statistics whose accumulated values can be scaled down, which is how `decaying::Decaying`
+/// makes past values fade.
+///
+/// `scale(factor)` must weight every value seen so far by `factor`, e.g. a sum is multiplied by it.
+pub trait Scalable<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    fn scale(&mut self, factor: F);
+}
+
+pub trait RevertableBivariate<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    fn revert(&mut self, x: F, y: F) -> Result<(), &'static str>;
+}
+
+/// Statistics that can be combined with another instance computed on a different part of the data.
+///
+/// After `a.merge(&b)`, `a` holds the statistic it would have had if it had seen the values seen
+/// by `b` as well. This is meant for sharded or map-reduce style aggregation.
+/// # Examples
+/// ```
+/// use watermill::mean::Mean;
+/// use watermill::stats::{Mergeable, Univariate};
+/// let mut left: Mean<f64> = Mean::new();
+/// let mut right: Mean<f64> = Mean::new();
+/// for x in vec![1., 2., 3.].into_iter(){
+///     left.update(x);
+/// }
+/// right.update(10.);
+/// left.merge(&right);
+/// assert_eq!(left.get(), 4.);
+/// ```
+pub trait Mergeable {
+    fn merge(&mut self, other: &Self);
+}
+
+/// Statistics that can be used with `rolling::Rolling`.
+///
+/// This is implemented for every type implementing `Univariate` and `Revertable`, see `Revertable`
+/// for the contract `revert` has to follow.
 pub trait RollableUnivariate<F: Float + FromPrimitive + AddAssign + SubAssign>:
     Revertable<F> + Univariate<F>
 {
 }
+
+impl<F, T> RollableUnivariate<F> for T
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    T: Revertable<F> + Univariate<F>,
+{
+}
+
+/// Bivariate statistics that can be computed over a sliding window of pairs.
+///
+/// This is implemented for every type implementing `Bivariate` and `RevertableBivariate`, and
+/// such types can be wrapped in a `rolling::RollingBivariate`.
+pub trait RollableBivariate<F: Float + FromPrimitive + AddAssign + SubAssign>:
+    RevertableBivariate<F> + Bivariate<F>
+{
+}
+
+impl<F, T> RollableBivariate<F> for T
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+    T: RevertableBivariate<F> + Bivariate<F>,
+{
+}
@@ -0,0 +1,139 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Revertable, Univariate};
+use crate::variance::{RollingVariance, Variance};
+use serde::{Deserialize, Serialize};
+/// Running standard deviation, the square root of `variance::Variance`.
+/// # Arguments
+/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen elements. Defaults to `1`, the sample variance, use `0` for the population variance.
+/// # Examples
+/// ```
+/// use watermill::std_dev::StandardDeviation;
+/// use watermill::stats::{Univariate, Revertable};
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let data_revert = data.clone();
+/// let mut running_std: StandardDeviation<f64> = StandardDeviation::default();
+/// for x in data.into_iter(){
+///     running_std.update(x);
+/// }
+/// assert_eq!(running_std.get(), 12.566666666666668_f64.sqrt());
+/// // You can revert the standard deviation
+///
+/// for x in data_revert.into_iter().rev(){
+///     running_std.revert(x);
+/// }
+/// assert_eq!(running_std.get(), 0.);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct StandardDeviation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: Variance<F>,
+}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> StandardDeviation<F> {
+    pub fn new(ddof: u32) -> Self {
+        Self {
+            variance: Variance::new(ddof),
+        }
+    }
+}
+
+impl<F> Default for StandardDeviation<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            variance: Variance::default(),
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for StandardDeviation<F> {
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.variance.get().sqrt()
+    }
+    fn n(&self) -> usize {
+        self.variance.n()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for StandardDeviation<F> {
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.variance.revert(x)
+    }
+}
+
+/// Rolling standard deviation, the square root of `variance::RollingVariance`.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of values in the window.
+/// # Examples
+/// ```
+/// use watermill::std_dev::RollingStandardDeviation;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+/// let mut rolling_std: RollingStandardDeviation<f64> = RollingStandardDeviation::new(2, 1).unwrap();
+/// for x in data.into_iter(){
+///     rolling_std.update(x);
+/// }
+/// assert_eq!(rolling_std.get(), 0.5_f64.sqrt());
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingStandardDeviation<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    pub variance: RollingVariance<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingStandardDeviation<F> {
+    pub fn new(window_size: usize, ddof: u32) -> Result<Self, &'static str> {
+        Ok(Self {
+            variance: RollingVariance::new(window_size, ddof)?,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F>
+    for RollingStandardDeviation<F>
+{
+    fn update(&mut self, x: F) {
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        // Reverting can leave a tiny negative residue
+        self.variance.get().max(F::from_f64(0.).unwrap()).sqrt()
+    }
+    fn n(&self) -> usize {
+        self.variance.n()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_std() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut running_std: StandardDeviation<f64> = StandardDeviation::default();
+        let mut rolling_std = Rolling::new(&mut running_std, 2).unwrap();
+        for x in data.into_iter() {
+            rolling_std.update(x);
+        }
+        assert_eq!(rolling_std.get(), 0.5_f64.sqrt());
+    }
+    #[test]
+    fn standalone_rolling_std() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut running_std: StandardDeviation<f64> = StandardDeviation::new(0);
+        let mut rolling_std = Rolling::new(&mut running_std, 3).unwrap();
+        let mut standalone: RollingStandardDeviation<f64> =
+            RollingStandardDeviation::new(3, 0).unwrap();
+        for x in data.into_iter() {
+            rolling_std.update(x);
+            standalone.update(x);
+            assert!((standalone.get() - rolling_std.get()).abs() < 1e-9);
+        }
+    }
+}
@@ -1,7 +1,11 @@
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
-use num::{Float, FromPrimitive};
+use crate::stats::{
+    IntUnivariate, Mergeable, Revertable, Scalable, Univariate, WeightedUnivariate,
+};
+use crate::utils::{compensated_add, NeumaierSum};
+use alloc::collections::VecDeque;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive, PrimInt};
 use serde::{Deserialize, Serialize};
-use std::ops::{AddAssign, SubAssign};
 /// Running sum.
 /// # Examples
 /// ```
@@ -20,16 +24,36 @@ use std::ops::{AddAssign, SubAssign};
 /// }
 /// assert_eq!(running_sum.get(), 0.);
 /// ```
-///
+/// `new_compensated` uses Neumaier compensated summation, which keeps small values added to a
+/// large sum at the cost of a few more floating point operations per update:
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::sum::Sum;
+/// let mut naive_sum: Sum<f64> = Sum::new();
+/// let mut compensated_sum: Sum<f64> = Sum::new_compensated();
+/// for x in vec![1e16, 1., 1., 1., 1.].into_iter(){
+///     naive_sum.update(x);
+///     compensated_sum.update(x);
+/// }
+/// assert_eq!(naive_sum.get(), 1e16);
+/// assert_eq!(compensated_sum.get(), 1e16 + 4.);
+/// ```
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Sum<F: Float + FromPrimitive + AddAssign + SubAssign> {
     pub sum: F,
+    // Rounding error lost by `sum`, only tracked by compensated sums
+    #[serde(default = "Option::default")]
+    compensation: Option<F>,
+    #[serde(default)]
+    n: usize,
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for Sum<F> {
     fn default() -> Self {
         Self {
             sum: F::from_f64(0.0).unwrap(),
+            compensation: None,
+            n: 0,
         }
     }
 }
@@ -37,21 +61,272 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Sum<F> {
     pub fn new() -> Self {
         Self::default()
     }
+    pub fn new_compensated() -> Self {
+        Self {
+            compensation: Some(F::from_f64(0.0).unwrap()),
+            ..Self::default()
+        }
+    }
+    fn add(&mut self, x: F) {
+        match self.compensation.as_mut() {
+            Some(compensation) => compensated_add(&mut self.sum, compensation, x),
+            None => self.sum += x,
+        }
+    }
 }
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Sum<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Sum<F> {
     fn update(&mut self, x: F) {
-        self.sum += x;
+        self.add(x);
+        self.n += 1;
     }
     fn get(&self) -> F {
-        self.sum
+        match self.compensation {
+            Some(compensation) => self.sum + compensation,
+            None => self.sum,
+        }
+    }
+    fn n(&self) -> usize {
+        self.n
     }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Sum<F> {
-    fn revert(&mut self, x: F) -> std::result::Result<(), &'static str> {
-        self.sum -= x;
+    fn revert(&mut self, x: F) -> Result<(), &'static str> {
+        self.add(-x);
+        self.n = self.n.saturating_sub(1);
         Ok(())
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Sum<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Sum<F> {
+    fn merge(&mut self, other: &Self) {
+        self.add(other.sum);
+        if let Some(compensation) = other.compensation {
+            self.add(compensation);
+        }
+        self.n += other.n;
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WeightedUnivariate<F> for Sum<F> {
+    fn update_weighted(&mut self, x: F, w: F) {
+        self.add(w * x);
+        self.n += w.round().to_usize().unwrap_or(0);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Scalable<F> for Sum<F> {
+    fn scale(&mut self, factor: F) {
+        self.sum = self.sum * factor;
+        if let Some(compensation) = self.compensation.as_mut() {
+            *compensation = *compensation * factor;
+        }
+    }
+}
+
+/// Rolling sum.
+///
+/// Unlike `Rolling<Sum<F>>`, values leaving the window are subtracted with Neumaier compensated
+/// summation, so rounding errors do not pile up over long streams mixing large and small values.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::stats::Univariate;
+/// use watermill::sum::RollingSum;
+/// let mut rolling_sum: RollingSum<f64> = RollingSum::new(2).unwrap();
+/// for x in vec![1e16, 1., 1., 1.].into_iter(){
+///     rolling_sum.update(x);
+/// }
+/// assert_eq!(rolling_sum.get(), 2.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingSum<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sum: NeumaierSum<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingSum<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            sum: NeumaierSum::new(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingSum<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.sum.add(-oldest);
+        }
+        self.window.push_back(x);
+        self.sum.add(x);
+    }
+    fn get(&self) -> F {
+        self.sum.value()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+/// Running sum of integers, exact as long as it fits in `I`.
+/// # Examples
+/// ```
+/// use watermill::stats::IntUnivariate;
+/// use watermill::sum::IntSum;
+/// let mut running_sum: IntSum<u64> = IntSum::new();
+/// running_sum.update(1 << 53);
+/// running_sum.update(1);
+/// assert_eq!(running_sum.get(), (1 << 53) + 1);
+/// ```
+/// # Panics
+/// `update` panics if the sum overflows `I`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct IntSum<I: PrimInt> {
+    pub sum: I,
+}
+
+impl<I: PrimInt> Default for IntSum<I> {
+    fn default() -> Self {
+        Self { sum: I::zero() }
+    }
+}
+impl<I: PrimInt> IntSum<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<I: PrimInt> IntUnivariate<I> for IntSum<I> {
+    fn update(&mut self, x: I) {
+        self.sum = self.sum.checked_add(&x).expect("IntSum overflowed");
+    }
+    fn get(&self) -> I {
+        self.sum
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_sum_does_not_drift() {
+        let window_size = 1000;
+        // Large values alternating with small fractions that are lost by a naive sum
+        let value = |i: usize| {
+            if i.is_multiple_of(2) {
+                1e13 + (i % 7) as f64
+            } else {
+                (i % 11) as f64 * 1e-3
+            }
+        };
+        let mut rolling_sum: RollingSum<f64> = RollingSum::new(window_size).unwrap();
+        let mut naive_sum: Sum<f64> = Sum::new();
+        let mut naive = Rolling::new(&mut naive_sum, window_size).unwrap();
+        let (mut worst_compensated, mut worst_naive) = (0_f64, 0_f64);
+        for i in 0..10_000_000 {
+            rolling_sum.update(value(i));
+            naive.update(value(i));
+            if (i + 1).is_multiple_of(1_000_000) {
+                let mut fresh = NeumaierSum::new();
+                for j in (i + 1 - window_size)..=i {
+                    fresh.add(value(j));
+                }
+                worst_compensated =
+                    worst_compensated.max((rolling_sum.get() - fresh.value()).abs());
+                worst_naive = worst_naive.max((naive.get() - fresh.value()).abs());
+            }
+        }
+        assert!(worst_compensated < 1e-6);
+        assert!(worst_naive > 0.1);
+        assert!(RollingSum::<f64>::new(0).is_err());
+    }
+    #[test]
+    fn update_many() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut single: Sum<f64> = Sum::new();
+        for x in data.iter() {
+            single.update(*x);
+        }
+        let mut batch: Sum<f64> = Sum::new();
+        batch.update(1.);
+        batch.update_many(data.iter().copied());
+        assert_eq!(batch.get(), single.get() + 1.);
+    }
+    #[test]
+    fn compensated_sum_keeps_small_values() {
+        let mut naive_sum: Sum<f32> = Sum::new();
+        let mut compensated_sum: Sum<f32> = Sum::new_compensated();
+        naive_sum.update(1e8);
+        compensated_sum.update(1e8);
+        for _ in 0..1000 {
+            naive_sum.update(1.);
+            compensated_sum.update(1.);
+        }
+        // 1 is below half the spacing of f32 around 1e8, every addition is lost
+        assert_eq!(naive_sum.get(), 1e8);
+        assert_eq!(compensated_sum.get(), 1e8 + 1000.);
+        for _ in 0..1000 {
+            compensated_sum.revert(1.).unwrap();
+        }
+        assert_eq!(compensated_sum.get(), 1e8);
+        let mut batch: Sum<f32> = Sum::new_compensated();
+        batch.update_many(core::iter::once(1e8).chain(core::iter::repeat_n(1., 1000)));
+        assert_eq!(batch.get(), 1e8 + 1000.);
+    }
+    #[test]
+    fn int_sum_is_exact() {
+        let mut int_sum: IntSum<u64> = IntSum::new();
+        let mut float_sum: Sum<f64> = Sum::new();
+        int_sum.update(1 << 53);
+        float_sum.update((1u64 << 53) as f64);
+        for _ in 0..1000 {
+            int_sum.update(1);
+            float_sum.update(1.);
+        }
+        assert_eq!(int_sum.get(), (1 << 53) + 1000);
+        // Every `+ 1` is lost past 2^53
+        assert_eq!(float_sum.get(), (1u64 << 53) as f64);
+    }
+    #[test]
+    #[should_panic(expected = "IntSum overflowed")]
+    fn int_sum_overflow() {
+        let mut int_sum: IntSum<u8> = IntSum::new();
+        int_sum.update(200);
+        int_sum.update(100);
+    }
+    #[test]
+    fn merge() {
+        let data: Vec<f64> = (0..100).map(|i| ((i * 37) % 11) as f64 - 5.).collect();
+        let mut single_pass: Sum<f64> = Sum::new();
+        let mut left: Sum<f64> = Sum::new();
+        let mut right: Sum<f64> = Sum::new();
+        for (i, x) in data.into_iter().enumerate() {
+            single_pass.update(x);
+            if i < 30 {
+                left.update(x);
+            } else {
+                right.update(x);
+            }
+        }
+        left.merge(&right);
+        assert!((left.get() - single_pass.get()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,302 @@
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::{Mergeable, Univariate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Centroid<F> {
+    mean: F,
+    weight: F,
+}
+
+/// Merging t-digest, a sketch of the whole distribution answering any quantile after the fact.
+///
+/// Values are buffered and periodically merged into centroids whose size is bounded by the `k1`
+/// scale function, centroids are small near the tails which keeps extreme quantiles accurate.
+/// `get` returns the median, use `quantile` and `cdf` for other queries.
+/// # Arguments
+/// * `compression` - Bounds the number of centroids, higher values are more accurate and use more memory. Defaults to `100`.
+/// # Examples
+/// ```
+/// use watermill::tdigest::TDigest;
+/// use watermill::stats::Univariate;
+/// let mut digest: TDigest<f64> = TDigest::default();
+/// for i in 0..10000{
+///     digest.update(i as f64);
+/// }
+/// assert!((digest.quantile(0.99) - 9900.).abs() < 10.);
+/// assert!((digest.cdf(2500.) - 0.25).abs() < 1e-3);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+/// # References
+/// [^1]: [Dunning, T. and Ertl, O., 2019. Computing extremely accurate quantiles using t-digests. arXiv preprint arXiv:1902.04023.](https://arxiv.org/abs/1902.04023)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TDigest<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    compression: F,
+    centroids: Vec<Centroid<F>>,
+    buffer: Vec<Centroid<F>>,
+    buffer_size: usize,
+    count: F,
+    min: F,
+    max: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> TDigest<F> {
+    pub fn new(compression: F) -> Result<Self, &'static str> {
+        if compression.is_nan() || compression < F::from_f64(1.).unwrap() {
+            return Err("compression should be greater or equal to 1");
+        }
+        Ok(Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            buffer_size: (compression * F::from_f64(5.).unwrap())
+                .to_usize()
+                .unwrap_or(usize::MAX),
+            count: F::from_f64(0.).unwrap(),
+            min: F::infinity(),
+            max: F::neg_infinity(),
+        })
+    }
+    /// Number of values seen.
+    pub fn count(&self) -> F {
+        self.count
+    }
+    /// Estimated `q` quantile, `q` is clamped to `[0, 1]`. Returns `NaN` if no value was seen.
+    pub fn quantile(&self, q: F) -> F {
+        let centroids = self.merged();
+        if centroids.is_empty() {
+            return F::nan();
+        }
+        let zero = F::from_f64(0.).unwrap();
+        let one = F::from_f64(1.).unwrap();
+        let half = F::from_f64(0.5).unwrap();
+        let target = q.max(zero).min(one) * self.count;
+        // Each centroid is placed at the middle of the weight it covers, the extremes are pinned
+        // to the min and the max
+        let (mut previous_position, mut previous_value) = (zero, self.min);
+        let mut cumulative_weight = zero;
+        for centroid in centroids.iter() {
+            let position = cumulative_weight + centroid.weight * half;
+            if target < position {
+                return previous_value
+                    + (centroid.mean - previous_value) * (target - previous_position)
+                        / (position - previous_position);
+            }
+            cumulative_weight += centroid.weight;
+            previous_position = position;
+            previous_value = centroid.mean;
+        }
+        if self.count <= previous_position {
+            return self.max;
+        }
+        previous_value
+            + (self.max - previous_value) * (target - previous_position)
+                / (self.count - previous_position)
+    }
+    /// Estimated fraction of values lower or equal to `x`. Returns `NaN` if no value was seen.
+    pub fn cdf(&self, x: F) -> F {
+        let centroids = self.merged();
+        if centroids.is_empty() {
+            return F::nan();
+        }
+        let zero = F::from_f64(0.).unwrap();
+        if x < self.min {
+            return zero;
+        }
+        if x >= self.max {
+            return F::from_f64(1.).unwrap();
+        }
+        let half = F::from_f64(0.5).unwrap();
+        let (mut previous_position, mut previous_value) = (zero, self.min);
+        let mut cumulative_weight = zero;
+        for centroid in centroids.iter() {
+            let position = cumulative_weight + centroid.weight * half;
+            if x < centroid.mean {
+                return (previous_position
+                    + (position - previous_position) * (x - previous_value)
+                        / (centroid.mean - previous_value))
+                    / self.count;
+            }
+            cumulative_weight += centroid.weight;
+            previous_position = position;
+            previous_value = centroid.mean;
+        }
+        (previous_position
+            + (self.count - previous_position) * (x - previous_value) / (self.max - previous_value))
+            / self.count
+    }
+
+    // Centroids and buffered values sorted by mean, without compressing them
+    fn merged(&self) -> Vec<Centroid<F>> {
+        let mut centroids: Vec<Centroid<F>> = self
+            .centroids
+            .iter()
+            .chain(self.buffer.iter())
+            .copied()
+            .collect();
+        centroids.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap());
+        centroids
+    }
+    fn scale(&self, q: F) -> F {
+        let two = F::from_f64(2.).unwrap();
+        let pi = F::from_f64(core::f64::consts::PI).unwrap();
+        self.compression / (two * pi) * (two * q - F::from_f64(1.).unwrap()).asin()
+    }
+    fn inverse_scale(&self, k: F) -> F {
+        let one = F::from_f64(1.).unwrap();
+        let two = F::from_f64(2.).unwrap();
+        let pi = F::from_f64(core::f64::consts::PI).unwrap();
+        if k >= self.compression / F::from_f64(4.).unwrap() {
+            return one;
+        }
+        ((k * two * pi / self.compression).sin() + one) / two
+    }
+    fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let centroids = self.merged();
+        self.buffer.clear();
+        let one = F::from_f64(1.).unwrap();
+        let mut compressed: Vec<Centroid<F>> = Vec::new();
+        let mut current = centroids[0];
+        let mut weight_so_far = F::from_f64(0.).unwrap();
+        let mut q_limit = self.inverse_scale(self.scale(weight_so_far) + one);
+        for centroid in centroids.into_iter().skip(1) {
+            let proposed_weight = current.weight + centroid.weight;
+            if (weight_so_far + proposed_weight) / self.count <= q_limit {
+                current.mean += (centroid.mean - current.mean) * centroid.weight / proposed_weight;
+                current.weight = proposed_weight;
+            } else {
+                weight_so_far += current.weight;
+                compressed.push(current);
+                q_limit = self.inverse_scale(self.scale(weight_so_far / self.count) + one);
+                current = centroid;
+            }
+        }
+        compressed.push(current);
+        self.centroids = compressed;
+    }
+}
+
+impl<F> Default for TDigest<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self::new(F::from_f64(100.).unwrap()).unwrap()
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for TDigest<F> {
+    fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("TDigest does not accept NaN values");
+        }
+        self.buffer.push(Centroid {
+            mean: x,
+            weight: F::from_f64(1.).unwrap(),
+        });
+        self.count += F::from_f64(1.).unwrap();
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        if self.buffer.len() >= self.buffer_size {
+            self.compress();
+        }
+    }
+    fn get(&self) -> F {
+        self.quantile(F::from_f64(0.5).unwrap())
+    }
+    fn try_get(&self) -> Option<F> {
+        if self.count == F::from_f64(0.).unwrap() {
+            return None;
+        }
+        Some(self.get())
+    }
+    fn n(&self) -> usize {
+        self.count.round().to_usize().unwrap_or(0)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for TDigest<F> {
+    /// The compression of `self` is kept.
+    fn merge(&mut self, other: &Self) {
+        self.buffer
+            .extend(other.centroids.iter().chain(other.buffer.iter()).copied());
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.compress();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Exponentially distributed values, the right tail is long
+    fn exponential_sample(n: usize) -> Vec<f64> {
+        let mut state: u64 = 42;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+                -u.ln()
+            })
+            .collect()
+    }
+    fn rank_error(sorted: &[f64], value: f64, q: f64) -> f64 {
+        let rank = sorted.partition_point(|x| *x <= value) as f64;
+        (rank / sorted.len() as f64 - q).abs()
+    }
+    #[test]
+    fn tail_accuracy() {
+        let data = exponential_sample(100_000);
+        let mut digest: TDigest<f64> = TDigest::default();
+        for x in data.iter() {
+            digest.update(*x);
+        }
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(rank_error(&sorted, digest.quantile(0.5), 0.5) < 5e-3);
+        assert!(rank_error(&sorted, digest.quantile(0.99), 0.99) < 5e-4);
+        assert!(rank_error(&sorted, digest.quantile(0.999), 0.999) < 2e-4);
+        let exact_p999 = sorted[99_900];
+        assert!((digest.cdf(exact_p999) - 0.999).abs() < 5e-4);
+        assert_eq!(digest.quantile(0.), sorted[0]);
+        assert_eq!(digest.quantile(1.), sorted[99_999]);
+    }
+    #[test]
+    fn merge() {
+        let data = exponential_sample(20_000);
+        let mut left: TDigest<f64> = TDigest::default();
+        let mut right: TDigest<f64> = TDigest::default();
+        for (i, x) in data.iter().enumerate() {
+            if i % 3 == 0 {
+                left.update(*x);
+            } else {
+                right.update(*x);
+            }
+        }
+        left.merge(&right);
+        let mut sorted = data.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(left.count(), 20_000.);
+        for q in [0.01, 0.5, 0.99, 0.999] {
+            assert!(rank_error(&sorted, left.quantile(q), q) < 2e-3);
+        }
+    }
+    #[test]
+    fn empty_and_invalid() {
+        let digest: TDigest<f64> = TDigest::default();
+        assert!(digest.quantile(0.5).is_nan());
+        assert!(digest.cdf(0.).is_nan());
+        assert_eq!(digest.try_get(), None);
+        assert!(TDigest::<f64>::new(0.5).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::sorted_window::SortedWindow;
+use serde::{Deserialize, Serialize};
+
+// Total order on the non-NaN floats accepted by `TopK` and `BottomK`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Ordered<F>(F);
+
+impl<F: Float> Eq for Ordered<F> {}
+
+impl<F: Float> PartialOrd for Ordered<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for Ordered<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .expect("NaN values are rejected")
+    }
+}
+
+/// Running `k` largest values.
+///
+/// The values are kept in a min-heap of at most `k` values, so an update costs `O(log k)`.
+/// `get` returns a `Vec` instead of a single value, which is why it does not implement `Univariate`.
+/// # Arguments
+/// * `k` - Number of values to keep, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::topk::TopK;
+/// let mut top_3: TopK<f64> = TopK::new(3).unwrap();
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     top_3.update(x);
+/// }
+/// assert_eq!(top_3.get(), vec![9., 8., 7.]);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopK<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    k: usize,
+    heap: BinaryHeap<Reverse<Ordered<F>>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> TopK<F> {
+    pub fn new(k: usize) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k should not equal to 0");
+        }
+        Ok(Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        })
+    }
+    pub fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("TopK does not accept NaN values");
+        }
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(Ordered(x)));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if x > smallest.0 .0 {
+                *smallest = Reverse(Ordered(x));
+            }
+        }
+    }
+    /// The `k` largest values in decreasing order, fewer if less than `k` values were seen.
+    pub fn get(&self) -> Vec<F> {
+        self.heap
+            .clone()
+            .into_sorted_vec()
+            .into_iter()
+            .map(|value| value.0 .0)
+            .collect()
+    }
+}
+
+/// Running `k` smallest values, the mirror of `TopK`.
+/// # Arguments
+/// * `k` - Number of values to keep, must be greater than 0.
+/// # Examples
+/// ```
+/// use watermill::topk::BottomK;
+/// let mut bottom_3: BottomK<f64> = BottomK::new(3).unwrap();
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     bottom_3.update(x);
+/// }
+/// assert_eq!(bottom_3.get(), vec![1., 2., 3.]);
+/// ```
+/// # Panics
+/// `update` panics on `NaN` values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BottomK<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    k: usize,
+    heap: BinaryHeap<Ordered<F>>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> BottomK<F> {
+    pub fn new(k: usize) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k should not equal to 0");
+        }
+        Ok(Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        })
+    }
+    pub fn update(&mut self, x: F) {
+        if x.is_nan() {
+            panic!("BottomK does not accept NaN values");
+        }
+        if self.heap.len() < self.k {
+            self.heap.push(Ordered(x));
+        } else if let Some(mut largest) = self.heap.peek_mut() {
+            if x < largest.0 {
+                *largest = Ordered(x);
+            }
+        }
+    }
+    /// The `k` smallest values in increasing order, fewer if less than `k` values were seen.
+    pub fn get(&self) -> Vec<F> {
+        self.heap
+            .clone()
+            .into_sorted_vec()
+            .into_iter()
+            .map(|value| value.0)
+            .collect()
+    }
+}
+
+/// Rolling `k` largest values.
+/// # Arguments
+/// * `k` - Number of values to return, must be greater than 0.
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::topk::RollingTopK;
+/// let mut rolling_top_2: RollingTopK<f64> = RollingTopK::new(2, 3).unwrap();
+/// for x in vec![9., 7., 3., 2., 6., 1., 8., 5., 4.].into_iter(){
+///     rolling_top_2.update(x);
+/// }
+/// assert_eq!(rolling_top_2.get(), vec![8., 5.]);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingTopK<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    k: usize,
+    sorted_window: SortedWindow<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingTopK<F> {
+    pub fn new(k: usize, window_size: usize) -> Result<Self, &'static str> {
+        if k == 0 {
+            return Err("k should not equal to 0");
+        }
+        Ok(Self {
+            k,
+            sorted_window: SortedWindow::new(window_size)?,
+        })
+    }
+    pub fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    /// The `k` largest values of the window in decreasing order, fewer if the window holds less than `k` values.
+    pub fn get(&self) -> Vec<F> {
+        let len = self.sorted_window.len();
+        (0..self.k.min(len))
+            .map(|i| self.sorted_window[len - 1 - i])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const SHUFFLED: [f64; 12] = [4., 11., 0., 7., 2., 9., 5., 10., 1., 8., 3., 6.];
+    #[test]
+    fn top_and_bottom_3() {
+        let mut top_3: TopK<f64> = TopK::new(3).unwrap();
+        let mut bottom_3: BottomK<f64> = BottomK::new(3).unwrap();
+        for x in SHUFFLED.iter() {
+            top_3.update(*x);
+            bottom_3.update(*x);
+        }
+        assert_eq!(top_3.get(), vec![11., 10., 9.]);
+        assert_eq!(bottom_3.get(), vec![0., 1., 2.]);
+    }
+    #[test]
+    fn fewer_than_k_values() {
+        let mut top_3: TopK<f64> = TopK::new(3).unwrap();
+        let mut bottom_3: BottomK<f64> = BottomK::new(3).unwrap();
+        let mut rolling_top_3: RollingTopK<f64> = RollingTopK::new(3, 10).unwrap();
+        assert!(top_3.get().is_empty());
+        assert!(bottom_3.get().is_empty());
+        assert!(rolling_top_3.get().is_empty());
+        for x in [2., 5.] {
+            top_3.update(x);
+            bottom_3.update(x);
+            rolling_top_3.update(x);
+        }
+        assert_eq!(top_3.get(), vec![5., 2.]);
+        assert_eq!(bottom_3.get(), vec![2., 5.]);
+        assert_eq!(rolling_top_3.get(), vec![5., 2.]);
+    }
+    #[test]
+    fn rolling_top_3() {
+        let mut rolling_top_3: RollingTopK<f64> = RollingTopK::new(3, 5).unwrap();
+        for (i, x) in SHUFFLED.iter().enumerate() {
+            rolling_top_3.update(*x);
+            let mut window = SHUFFLED[(i + 1).saturating_sub(5)..=i].to_vec();
+            window.sort_by(|a, b| b.partial_cmp(a).unwrap());
+            window.truncate(3);
+            assert_eq!(rolling_top_3.get(), window);
+        }
+    }
+    #[test]
+    fn invalid_parameters() {
+        assert!(TopK::<f64>::new(0).is_err());
+        assert!(BottomK::<f64>::new(0).is_err());
+        assert!(RollingTopK::<f64>::new(0, 5).is_err());
+        assert!(RollingTopK::<f64>::new(3, 0).is_err());
+    }
+}
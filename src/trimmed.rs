@@ -0,0 +1,116 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Rolling trimmed mean.
+///
+/// The `floor(trim_fraction * n)` lowest and highest values of the `n` values of the window are
+/// discarded and the remaining ones are averaged, which makes the mean robust to a few outliers.
+/// `get` returns `NaN` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `trim_fraction` - Fraction of the window discarded at each end, in `[0, 0.5)`.
+/// # Examples
+/// ```
+/// use watermill::trimmed::RollingTrimmedMean;
+/// use watermill::stats::Univariate;
+/// let mut rolling_trimmed_mean: RollingTrimmedMean<f64> = RollingTrimmedMean::new(5, 0.2).unwrap();
+/// for x in vec![9., 7., 3., 2., 100., 1., 8., 5., 4.].into_iter(){
+///     rolling_trimmed_mean.update(x);
+/// }
+/// // 1 and 100 are discarded from the window 100, 1, 8, 5, 4
+/// assert_eq!(rolling_trimmed_mean.get(), 17. / 3.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingTrimmedMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    trim_fraction: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingTrimmedMean<F> {
+    pub fn new(window_size: usize, trim_fraction: F) -> Result<Self, &'static str> {
+        if !(trim_fraction >= F::from_f64(0.).unwrap() && trim_fraction < F::from_f64(0.5).unwrap())
+        {
+            return Err("trim_fraction should be between 0 and 0.5");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+            trim_fraction,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingTrimmedMean<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        let len = self.sorted_window.len();
+        if len == 0 {
+            return None;
+        }
+        let trimmed = (self.trim_fraction * F::from_usize(len).unwrap())
+            .floor()
+            .to_usize()
+            .unwrap();
+        let kept = len - 2 * trimmed;
+        let mut sum = F::from_f64(0.).unwrap();
+        for x in self.sorted_window.iter().skip(trimmed).take(kept) {
+            sum += *x;
+        }
+        Some(sum / F::from_usize(kept).unwrap())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mean::RollingMean;
+    #[test]
+    fn no_trimming_is_the_mean() {
+        let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+        let mut rolling_trimmed_mean: RollingTrimmedMean<f64> =
+            RollingTrimmedMean::new(4, 0.).unwrap();
+        let mut rolling_mean: RollingMean<f64> = RollingMean::new(4).unwrap();
+        assert_eq!(rolling_trimmed_mean.try_get(), None);
+        for x in data.into_iter() {
+            rolling_trimmed_mean.update(x);
+            rolling_mean.update(x);
+            assert!((rolling_trimmed_mean.get() - rolling_mean.get()).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn robust_to_outliers() {
+        let mut rolling_trimmed_mean: RollingTrimmedMean<f64> =
+            RollingTrimmedMean::new(20, 0.1).unwrap();
+        let mut rolling_mean: RollingMean<f64> = RollingMean::new(20).unwrap();
+        for i in 0..100 {
+            // Bulk between 10 and 12 with a large outlier every 10 values
+            let x = if i % 10 == 0 {
+                1e6
+            } else {
+                10. + (i % 3) as f64
+            };
+            rolling_trimmed_mean.update(x);
+            rolling_mean.update(x);
+        }
+        assert!((rolling_trimmed_mean.get() - 11.).abs() < 1.);
+        assert!((rolling_mean.get() - 11.).abs() > 1e4);
+    }
+    #[test]
+    fn invalid_trim_fraction() {
+        assert!(RollingTrimmedMean::<f64>::new(10, 0.5).is_err());
+        assert!(RollingTrimmedMean::<f64>::new(10, -0.1).is_err());
+        assert!(RollingTrimmedMean::<f64>::new(10, f64::NAN).is_err());
+        assert!(RollingTrimmedMean::<f64>::new(0, 0.1).is_err());
+    }
+}
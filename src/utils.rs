@@ -0,0 +1,82 @@
+use num::{Float, FromPrimitive};
+use serde::{Deserialize, Serialize};
+
+// Floats are neither `Eq` nor `Hash`, frequency tables are keyed by the bit pattern of the value
+// converted to `f64`. `-0.0` is merged with `0.0` and every NaN shares the same key.
+pub(crate) fn to_key<F: Float>(x: F) -> u64 {
+    let x = x.to_f64().unwrap();
+    if x == 0. {
+        return 0_f64.to_bits();
+    }
+    if x.is_nan() {
+        return f64::NAN.to_bits();
+    }
+    x.to_bits()
+}
+
+pub(crate) fn from_key<F: Float + FromPrimitive>(key: u64) -> F {
+    F::from_f64(f64::from_bits(key)).unwrap()
+}
+
+// Neumaier's variant of Kahan summation, adds `x` to `sum` and its rounding error to `compensation`
+pub(crate) fn compensated_add<F: Float>(sum: &mut F, compensation: &mut F, x: F) {
+    let new_sum = *sum + x;
+    if sum.abs() >= x.abs() {
+        *compensation = *compensation + ((*sum - new_sum) + x);
+    } else {
+        *compensation = *compensation + ((x - new_sum) + *sum);
+    }
+    *sum = new_sum;
+}
+
+// Running total using `compensated_add`, the rounding error of every addition is accumulated in
+// `compensation` so the total stays accurate even when the terms have very different magnitudes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct NeumaierSum<F> {
+    sum: F,
+    compensation: F,
+}
+
+impl<F: Float + FromPrimitive> NeumaierSum<F> {
+    pub(crate) fn new() -> Self {
+        Self {
+            sum: F::from_f64(0.).unwrap(),
+            compensation: F::from_f64(0.).unwrap(),
+        }
+    }
+    pub(crate) fn add(&mut self, x: F) {
+        compensated_add(&mut self.sum, &mut self.compensation, x);
+    }
+    pub(crate) fn value(&self) -> F {
+        self.sum + self.compensation
+    }
+}
+
+// SplitMix64 pseudo random generator, small and seedable, for the sampling based estimators.
+// It is not meant for anything cryptographic.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+    // Uniform integer in `[0, bound)`, the bias of the multiply-shift reduction is negligible for
+    // the bounds used here
+    pub(crate) fn next_below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+    // Uniform float in `[0, 1)`, made of the 53 high bits
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
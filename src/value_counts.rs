@@ -0,0 +1,239 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::stats::Univariate;
+use crate::utils::{from_key, to_key};
+use serde::{Deserialize, Serialize};
+
+// Frequency table keyed by the bit pattern of the values
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Counts {
+    counts: BTreeMap<u64, usize>,
+    n: usize,
+}
+
+impl Counts {
+    fn add(&mut self, key: u64) {
+        *self.counts.entry(key).or_insert(0) += 1;
+        self.n += 1;
+    }
+    fn remove(&mut self, key: u64) {
+        let count = self.counts.get_mut(&key).expect("Value should be counted");
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(&key);
+        }
+        self.n -= 1;
+    }
+    fn count(&self, key: u64) -> usize {
+        self.counts.get(&key).copied().unwrap_or(0)
+    }
+    fn proportion<F: Float + FromPrimitive>(&self, key: u64) -> F {
+        if self.n == 0 {
+            return F::nan();
+        }
+        F::from_usize(self.count(key)).unwrap() / F::from_usize(self.n).unwrap()
+    }
+    fn to_vec<F: Float + FromPrimitive>(&self) -> Vec<(F, usize)> {
+        let mut counts: Vec<(F, usize)> = self
+            .counts
+            .iter()
+            .map(|(key, count)| (from_key(*key), *count))
+            .collect();
+        // `NaN` is sorted last
+        counts.sort_by(|a, b| a.0.to_f64().unwrap().total_cmp(&b.0.to_f64().unwrap()));
+        counts
+    }
+}
+
+/// Running count of every distinct value.
+///
+/// Values are compared by their exact bit pattern, which makes `ValueCounts` suited for discrete
+/// (e.g. categorical-coded) streams. `-0.0` is counted as `0.0` and every `NaN` as the same value.
+/// `get` returns how many times the latest value was seen, `0` before any update.
+/// # Examples
+/// ```
+/// use watermill::value_counts::ValueCounts;
+/// use watermill::stats::Univariate;
+/// let mut value_counts: ValueCounts<f64> = ValueCounts::new();
+/// for x in vec![2., 1., 2., 3., 2.].into_iter(){
+///     value_counts.update(x);
+/// }
+/// assert_eq!(value_counts.get(), 3.);
+/// assert_eq!(value_counts.counts(), vec![(1., 1), (2., 3), (3., 1)]);
+/// assert_eq!(value_counts.proportion(2.), 0.6);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValueCounts<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counts: Counts,
+    latest: Option<F>,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Default for ValueCounts<F> {
+    fn default() -> Self {
+        Self {
+            counts: Counts::default(),
+            latest: None,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> ValueCounts<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The distinct values with their count, in increasing order of value.
+    pub fn counts(&self) -> Vec<(F, usize)> {
+        self.counts.to_vec()
+    }
+    /// Number of times `x` was seen.
+    pub fn count(&self, x: F) -> usize {
+        self.counts.count(to_key(x))
+    }
+    /// Share of the values equal to `x`, `NaN` before any update.
+    pub fn proportion(&self, x: F) -> F {
+        self.counts.proportion(to_key(x))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for ValueCounts<F> {
+    fn update(&mut self, x: F) {
+        self.counts.add(to_key(x));
+        self.latest = Some(x);
+    }
+    fn get(&self) -> F {
+        F::from_usize(self.latest.map_or(0, |x| self.count(x))).unwrap()
+    }
+    fn n(&self) -> usize {
+        self.counts.n
+    }
+}
+
+/// Rolling count of every distinct value of the window.
+///
+/// Values are compared by their exact bit pattern, the counts are decremented as values leave the
+/// window. `get` returns how many times the latest value appears in the window.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// # Examples
+/// ```
+/// use watermill::value_counts::RollingValueCounts;
+/// use watermill::stats::Univariate;
+/// let mut rolling_value_counts: RollingValueCounts<f64> = RollingValueCounts::new(3).unwrap();
+/// for x in vec![2., 1., 2., 3., 2.].into_iter(){
+///     rolling_value_counts.update(x);
+/// }
+/// assert_eq!(rolling_value_counts.get(), 2.);
+/// assert_eq!(rolling_value_counts.counts(), vec![(2., 2), (3., 1)]);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingValueCounts<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    counts: Counts,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingValueCounts<F> {
+    pub fn new(window_size: usize) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            counts: Counts::default(),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+    /// The distinct values of the window with their count, in increasing order of value.
+    pub fn counts(&self) -> Vec<(F, usize)> {
+        self.counts.to_vec()
+    }
+    /// Number of times `x` appears in the window.
+    pub fn count(&self, x: F) -> usize {
+        self.counts.count(to_key(x))
+    }
+    /// Share of the values of the window equal to `x`, `NaN` while the window is empty.
+    pub fn proportion(&self, x: F) -> F {
+        self.counts.proportion(to_key(x))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingValueCounts<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.counts.remove(to_key(oldest));
+        }
+        self.window.push_back(x);
+        self.counts.add(to_key(x));
+    }
+    fn get(&self) -> F {
+        F::from_usize(self.window.back().map_or(0, |x| self.count(*x))).unwrap()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const ALPHABET: [f64; 4] = [0., 1., 2., 3.];
+    #[test]
+    fn small_alphabet() {
+        let mut value_counts: ValueCounts<f64> = ValueCounts::new();
+        assert!(value_counts.counts().is_empty());
+        assert!(value_counts.proportion(1.).is_nan());
+        assert_eq!(value_counts.get(), 0.);
+        // Letter `i` appears `i + 1` times
+        for (i, x) in ALPHABET.iter().enumerate() {
+            for _ in 0..=i {
+                value_counts.update(*x);
+            }
+        }
+        assert_eq!(
+            value_counts.counts(),
+            vec![(0., 1), (1., 2), (2., 3), (3., 4)]
+        );
+        for (i, x) in ALPHABET.iter().enumerate() {
+            assert_eq!(value_counts.count(*x), i + 1);
+            assert!((value_counts.proportion(*x) - (i + 1) as f64 / 10.).abs() < 1e-12);
+        }
+        assert_eq!(value_counts.proportion(7.), 0.);
+        assert_eq!(value_counts.get(), 4.);
+        // -0.0 and 0.0 are the same value
+        value_counts.update(-0.);
+        assert_eq!(value_counts.count(0.), 2);
+        value_counts.update(f64::NAN);
+        assert!(value_counts.counts().last().unwrap().0.is_nan());
+    }
+    #[test]
+    fn rolling_matches_window() {
+        let data: Vec<f64> = vec![1., 1., 2., 3., 1., 0., 0., 0., 2., 3., 3., 1.];
+        let mut rolling_value_counts: RollingValueCounts<f64> = RollingValueCounts::new(5).unwrap();
+        for (i, x) in data.iter().enumerate() {
+            rolling_value_counts.update(*x);
+            let window = &data[(i + 1).saturating_sub(5)..=i];
+            let mut expected: Vec<(f64, usize)> = Vec::new();
+            for letter in ALPHABET.iter() {
+                let count = window.iter().filter(|y| *y == letter).count();
+                if count > 0 {
+                    expected.push((*letter, count));
+                }
+                assert!(
+                    (rolling_value_counts.proportion(*letter) - count as f64 / window.len() as f64)
+                        .abs()
+                        < 1e-12
+                );
+            }
+            assert_eq!(rolling_value_counts.counts(), expected);
+            assert_eq!(
+                rolling_value_counts.get(),
+                window.iter().filter(|y| *y == x).count() as f64
+            );
+        }
+        assert!(RollingValueCounts::<f64>::new(0).is_err());
+    }
+}
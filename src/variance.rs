@@ -1,12 +1,18 @@
+use alloc::collections::VecDeque;
+use core::marker::PhantomData;
+use core::ops::{AddAssign, SubAssign};
 use num::{Float, FromPrimitive};
-use std::ops::{AddAssign, SubAssign};
 
 use crate::mean::Mean;
-use crate::stats::{Revertable, RollableUnivariate, Univariate};
+use crate::sorted_window::DEFAULT_WINDOW_SIZE;
+use crate::stats::{Mergeable, Revertable, Univariate, WeightedUnivariate};
 use serde::{Deserialize, Serialize};
-/// Running variance using Belford Algorithm.
+/// Running variance using Welford's algorithm.
+///
+/// The mean and the sum of squared deviations are updated together, which avoids the catastrophic
+/// cancellation of the sum of squares formula on large values with a small variance.
 /// # Arguments
-/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen elements.
+/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of seen elements. Defaults to `1`, the sample variance, use `0` for the population variance.
 /// # Examples
 /// ```
 /// use watermill::variance::Variance;
@@ -25,6 +31,14 @@ use serde::{Deserialize, Serialize};
 /// }
 /// assert_eq!(running_variance.get(), 0.);
 /// ```
+/// It can also be collected from an iterator, using the default `ddof` of `1`:
+/// ```
+/// use watermill::variance::Variance;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+/// let running_variance: Variance<f64> = data.into_iter().collect();
+/// assert_eq!(running_variance.get(), 12.566666666666668);
+/// ```
 /// # References
 /// [^1]: [Wikipedia article on algorithms for calculating variance](https://www.wikiwand.com/en/Algorithms_for_calculating_variance#/Covariance)
 ///
@@ -43,6 +57,10 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Variance<F> {
             state: F::from_f64(0.).unwrap(),
         }
     }
+    /// Builder of a `Variance`, starting from the sample variance.
+    pub fn builder() -> VarianceBuilder<F> {
+        VarianceBuilder::default()
+    }
 }
 
 impl<F> Default for Variance<F>
@@ -58,6 +76,55 @@ where
     }
 }
 
+/// Builder of a `Variance`.
+/// # Examples
+/// ```
+/// use watermill::variance::Variance;
+/// use watermill::stats::Univariate;
+/// let mut running_variance: Variance<f64> = Variance::builder().ddof(0).build().unwrap();
+/// for x in vec![1., 2., 3., 4.].into_iter(){
+///     running_variance.update(x);
+/// }
+/// assert_eq!(running_variance.get(), 1.25);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct VarianceBuilder<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    ddof: u32,
+    _float: PhantomData<F>,
+}
+
+impl<F> Default for VarianceBuilder<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            ddof: 1,
+            _float: PhantomData,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> VarianceBuilder<F> {
+    /// Delta Degrees of Freedom, `1` for the sample variance and `0` for the population variance.
+    pub fn ddof(mut self, ddof: u32) -> Self {
+        self.ddof = ddof;
+        self
+    }
+    /// Every option is valid for a `Variance`, the `Result` keeps the builders alike.
+    pub fn build(self) -> Result<Variance<F>, &'static str> {
+        Ok(Variance::new(self.ddof))
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> FromIterator<F> for Variance<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        let mut stat = Self::default();
+        stat.update_many(iter);
+        stat
+    }
+}
+
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Variance<F> {
     fn update(&mut self, x: F) {
         let mean_old = self.mean.get();
@@ -72,6 +139,9 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for Varianc
         }
         F::from_f64(0.).unwrap()
     }
+    fn n(&self) -> usize {
+        self.mean.n()
+    }
 }
 
 impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Variance<F> {
@@ -84,4 +154,315 @@ impl<F: Float + FromPrimitive + AddAssign + SubAssign> Revertable<F> for Varianc
     }
 }
 
-impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollableUnivariate<F> for Variance<F> {}
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Mergeable for Variance<F> {
+    /// Parallel combination of Chan et al., the `ddof` of `self` is kept.
+    fn merge(&mut self, other: &Self) {
+        let n_self = self.mean.n.get();
+        let n_other = other.mean.n.get();
+        let n = n_self + n_other;
+        if n == F::from_f64(0.).unwrap() {
+            return;
+        }
+        let delta = other.mean.get() - self.mean.get();
+        self.state += other.state + delta * delta * (n_self / n) * n_other;
+        self.mean.merge(&other.mean);
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> WeightedUnivariate<F> for Variance<F> {
+    /// West's weighted update, the divisor is the sum of the weights minus `ddof`.
+    fn update_weighted(&mut self, x: F, w: F) {
+        let mean_old = self.mean.get();
+        self.mean.update_weighted(x, w);
+        let mean_new = self.mean.get();
+        self.state += w * (x - mean_old) * (x - mean_new);
+    }
+}
+
+/// Rolling variance.
+///
+/// Unlike `Rolling<Variance<F>>`, it owns its window and can be stored as a plain struct field.
+/// The variance is updated and reverted with Welford's algorithm.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `ddof` - Delta Degrees of Freedom. The divisor used in calculations is `n - ddof`, where `n` represents the number of values in the window.
+/// # Examples
+/// ```
+/// use watermill::variance::RollingVariance;
+/// use watermill::stats::Univariate;
+/// let data: Vec<f64> = vec![9., 7., 3., 2., 6., 1., 8., 5., 4.];
+/// let mut rolling_variance: RollingVariance<f64> = RollingVariance::new(3, 1).unwrap();
+/// for x in data.into_iter(){
+///     rolling_variance.update(x);
+/// }
+/// assert!((rolling_variance.get() - 13. / 3.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingVariance<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    variance: Variance<F>,
+    window: VecDeque<F>,
+    window_size: usize,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingVariance<F> {
+    pub fn new(window_size: usize, ddof: u32) -> Result<Self, &'static str> {
+        if window_size == 0 {
+            return Err("Window size should not equal to 0");
+        }
+        Ok(Self {
+            variance: Variance::new(ddof),
+            window: VecDeque::with_capacity(window_size),
+            window_size,
+        })
+    }
+    /// Builder of a `RollingVariance`, starting from the sample variance over
+    /// `sorted_window::DEFAULT_WINDOW_SIZE` values.
+    pub fn builder() -> RollingVarianceBuilder<F> {
+        RollingVarianceBuilder::default()
+    }
+}
+
+/// Builder of a `RollingVariance`, the options are validated by `build`.
+/// # Examples
+/// ```
+/// use watermill::variance::RollingVariance;
+/// use watermill::stats::Univariate;
+/// let mut rolling_variance: RollingVariance<f64> =
+///     RollingVariance::builder().window_size(3).ddof(0).build().unwrap();
+/// for x in vec![9., 1., 2., 3.].into_iter(){
+///     rolling_variance.update(x);
+/// }
+/// assert!((rolling_variance.get() - 2. / 3.).abs() < 1e-12);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RollingVarianceBuilder<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    window_size: usize,
+    ddof: u32,
+    _float: PhantomData<F>,
+}
+
+impl<F> Default for RollingVarianceBuilder<F>
+where
+    F: Float + FromPrimitive + AddAssign + SubAssign,
+{
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            ddof: 1,
+            _float: PhantomData,
+        }
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingVarianceBuilder<F> {
+    /// Size of the rolling window, must not be `0`.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+    /// Delta Degrees of Freedom, `1` for the sample variance and `0` for the population variance.
+    pub fn ddof(mut self, ddof: u32) -> Self {
+        self.ddof = ddof;
+        self
+    }
+    pub fn build(self) -> Result<RollingVariance<F>, &'static str> {
+        RollingVariance::new(self.window_size, self.ddof)
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingVariance<F> {
+    fn update(&mut self, x: F) {
+        if self.window.len() == self.window_size {
+            let oldest = self.window.pop_front().expect("Window should not be empty");
+            self.variance
+                .revert(oldest)
+                .expect("The window holds as many values as the variance");
+        }
+        self.window.push_back(x);
+        self.variance.update(x);
+    }
+    fn get(&self) -> F {
+        self.variance.get()
+    }
+    fn n(&self) -> usize {
+        self.window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rolling::Rolling;
+    #[test]
+    fn rolling_variance() {
+        let data: Vec<f64> = (0..200)
+            .map(|i| ((i * 37) % 101) as f64 / 7. - 3.)
+            .collect();
+        for ddof in [0, 1] {
+            let mut variance: Variance<f64> = Variance::new(ddof);
+            let mut rolling_variance = Rolling::new(&mut variance, 10).unwrap();
+            let mut standalone: RollingVariance<f64> = RollingVariance::new(10, ddof).unwrap();
+            for (i, x) in data.iter().enumerate() {
+                rolling_variance.update(*x);
+                standalone.update(*x);
+                assert_eq!(standalone.get(), rolling_variance.get());
+
+                let window = &data[(i + 1).saturating_sub(10)..=i];
+                let n = window.len() as f64;
+                let mean = window.iter().sum::<f64>() / n;
+                let naive = if n > ddof as f64 {
+                    window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - ddof as f64)
+                } else {
+                    0.
+                };
+                assert!((standalone.get() - naive).abs() < 1e-9);
+            }
+        }
+        assert!(RollingVariance::<f64>::new(0, 1).is_err());
+    }
+    #[test]
+    fn population_and_sample_ddof() {
+        use crate::cv::CoefficientOfVariation;
+        use crate::sem::SEM;
+        use crate::std_dev::StandardDeviation;
+        // Mean 5 and sum of squared deviations 32
+        let data: Vec<f64> = vec![2., 4., 4., 4., 5., 5., 7., 9.];
+        let mut population: Variance<f64> = Variance::new(0);
+        let mut sample: Variance<f64> = Variance::new(1);
+        let mut default: Variance<f64> = Variance::default();
+        let mut population_std: StandardDeviation<f64> = StandardDeviation::new(0);
+        let mut population_sem: SEM<f64> = SEM::new(0);
+        let mut population_cv: CoefficientOfVariation<f64> = CoefficientOfVariation::new(0, true);
+        for x in data.into_iter() {
+            population.update(x);
+            sample.update(x);
+            default.update(x);
+            population_std.update(x);
+            population_sem.update(x);
+            population_cv.update(x);
+        }
+        assert_eq!(population.get(), 4.);
+        assert_eq!(sample.get(), 32. / 7.);
+        assert_eq!(default.get(), sample.get());
+        assert_eq!(population_std.get(), 2.);
+        assert!((population_sem.get() - 2. / 8_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(population_cv.get(), 0.4);
+    }
+    #[test]
+    fn large_values_small_variance() {
+        let data: Vec<f64> = vec![1e9, 1e9 + 1.0, 1e9 + 2.0];
+        let mut variance: Variance<f64> = Variance::default();
+        for x in data.iter() {
+            variance.update(*x);
+        }
+        assert_eq!(variance.get(), 1.0);
+        // The sum of squares formula cancels out most of the digits
+        let n = data.len() as f64;
+        let sum = data.iter().sum::<f64>();
+        let sum_sq = data.iter().map(|x| x * x).sum::<f64>();
+        assert_ne!((sum_sq - sum * sum / n) / (n - 1.), 1.0);
+        // Reverting keeps the precision for the rolling case
+        let mut rolling_variance: RollingVariance<f64> = RollingVariance::new(3, 1).unwrap();
+        for i in 0..1000 {
+            rolling_variance.update(1e9 + (i % 3) as f64);
+        }
+        assert!((rolling_variance.get() - 1.0).abs() < 1e-6);
+    }
+    #[test]
+    fn update_many() {
+        let data: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+        let mut single: Variance<f64> = Variance::default();
+        for x in data.iter() {
+            single.update(*x);
+        }
+        let mut batch: Variance<f64> = Variance::default();
+        batch.update_many(data);
+        assert_eq!(batch.get(), single.get());
+    }
+    #[test]
+    fn update_if_finite() {
+        let clean: Vec<f64> = vec![3., 5., 4., 7., 10., 12.];
+        let gappy: Vec<f64> = vec![
+            f64::NAN,
+            3.,
+            5.,
+            f64::INFINITY,
+            4.,
+            7.,
+            f64::NAN,
+            f64::NEG_INFINITY,
+            10.,
+            12.,
+            f64::NAN,
+        ];
+        let mut expected: Variance<f64> = Variance::default();
+        expected.update_many(clean);
+        let mut skipping: Variance<f64> = Variance::default();
+        for x in gappy.into_iter() {
+            skipping.update_if_finite(x);
+        }
+        assert_eq!(skipping.get(), expected.get());
+        assert_eq!(skipping.mean.get(), expected.mean.get());
+    }
+    #[test]
+    fn update_weighted() {
+        for ddof in [0, 1] {
+            let mut repeated: Variance<f64> = Variance::new(ddof);
+            let mut weighted: Variance<f64> = Variance::new(ddof);
+            for (x, w) in [(5., 1000), (-2.5, 3), (7., 1), (0.25, 40)] {
+                for _ in 0..w {
+                    repeated.update(x);
+                }
+                weighted.update_weighted(x, w as f64);
+                assert!((weighted.get() - repeated.get()).abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn merge() {
+        let data: Vec<f64> = (0..1000)
+            .map(|i| 1e3 + ((i * 37) % 101) as f64 / 7.)
+            .collect();
+        for split in [0, 1, 10, 500, 999, 1000] {
+            let mut single_pass: Variance<f64> = Variance::default();
+            let mut left: Variance<f64> = Variance::default();
+            let mut right: Variance<f64> = Variance::default();
+            for (i, x) in data.iter().enumerate() {
+                single_pass.update(*x);
+                if i < split {
+                    left.update(*x);
+                } else {
+                    right.update(*x);
+                }
+            }
+            left.merge(&right);
+            assert!((left.get() - single_pass.get()).abs() < 1e-9);
+            assert!((left.mean.get() - single_pass.mean.get()).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn builders_match_constructors() {
+        let mut running_variance: Variance<f64> = Variance::default();
+        let mut built_variance: Variance<f64> = Variance::builder().build().unwrap();
+        let mut rolling_variance: RollingVariance<f64> = RollingVariance::new(10, 0).unwrap();
+        let mut built_rolling_variance: RollingVariance<f64> = RollingVariance::builder()
+            .ddof(0)
+            .window_size(10)
+            .build()
+            .unwrap();
+        for i in 0..100 {
+            let x = ((i * 37) % 101) as f64 / 7.;
+            running_variance.update(x);
+            built_variance.update(x);
+            rolling_variance.update(x);
+            built_rolling_variance.update(x);
+            assert_eq!(running_variance.get(), built_variance.get());
+            assert_eq!(rolling_variance.get(), built_rolling_variance.get());
+        }
+        assert_eq!(RollingVariance::<f64>::builder().build().unwrap().n(), 0);
+        assert!(RollingVariance::<f64>::builder()
+            .window_size(0)
+            .build()
+            .is_err());
+    }
+}
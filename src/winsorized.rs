@@ -0,0 +1,113 @@
+use core::ops::{AddAssign, SubAssign};
+use num::{Float, FromPrimitive};
+
+use crate::sorted_window::SortedWindow;
+use crate::stats::Univariate;
+use serde::{Deserialize, Serialize};
+
+/// Rolling winsorized mean.
+///
+/// The `floor(limit * n)` lowest values of the `n` values of the window are replaced by the
+/// lowest value kept, likewise for the highest values, then all the `n` values are averaged.
+/// Unlike `trimmed::RollingTrimmedMean`, every value of the window still counts.
+/// `get` returns `NaN` while the window is empty.
+/// # Arguments
+/// * `window_size` - Size of the rolling window.
+/// * `limit` - Fraction of the window clamped at each end, in `[0, 0.5)`.
+/// # Examples
+/// ```
+/// use watermill::winsorized::RollingWinsorizedMean;
+/// use watermill::stats::Univariate;
+/// let mut rolling_winsorized_mean: RollingWinsorizedMean<f64> = RollingWinsorizedMean::new(5, 0.2).unwrap();
+/// for x in vec![9., 7., 3., 2., 100., 1., 8., 5., 4.].into_iter(){
+///     rolling_winsorized_mean.update(x);
+/// }
+/// // The window 100, 1, 8, 5, 4 becomes 8, 4, 8, 5, 4
+/// assert_eq!(rolling_winsorized_mean.get(), 29. / 5.);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollingWinsorizedMean<F: Float + FromPrimitive + AddAssign + SubAssign> {
+    sorted_window: SortedWindow<F>,
+    limit: F,
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> RollingWinsorizedMean<F> {
+    pub fn new(window_size: usize, limit: F) -> Result<Self, &'static str> {
+        if !(limit >= F::from_f64(0.).unwrap() && limit < F::from_f64(0.5).unwrap()) {
+            return Err("limit should be between 0 and 0.5");
+        }
+        Ok(Self {
+            sorted_window: SortedWindow::new(window_size)?,
+            limit,
+        })
+    }
+}
+
+impl<F: Float + FromPrimitive + AddAssign + SubAssign> Univariate<F> for RollingWinsorizedMean<F> {
+    fn update(&mut self, x: F) {
+        self.sorted_window.push_back(x);
+    }
+    fn get(&self) -> F {
+        self.try_get().unwrap_or_else(F::nan)
+    }
+    fn try_get(&self) -> Option<F> {
+        let len = self.sorted_window.len();
+        if len == 0 {
+            return None;
+        }
+        let clamped = (self.limit * F::from_usize(len).unwrap())
+            .floor()
+            .to_usize()
+            .unwrap();
+        let lowest = self.sorted_window[clamped];
+        let highest = self.sorted_window[len - 1 - clamped];
+        let mut sum = F::from_f64(0.).unwrap();
+        for x in self.sorted_window.iter() {
+            sum += x.max(lowest).min(highest);
+        }
+        Some(sum / F::from_usize(len).unwrap())
+    }
+    fn n(&self) -> usize {
+        self.sorted_window.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    // Clamp the `k` lowest and highest values of the window by hand
+    fn winsorized_mean(window: &[f64], limit: f64) -> f64 {
+        let mut sorted = window.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = sorted.len();
+        let k = (limit * n as f64).floor() as usize;
+        for i in 0..k {
+            sorted[i] = sorted[k];
+            sorted[n - 1 - i] = sorted[n - 1 - k];
+        }
+        sorted.iter().sum::<f64>() / n as f64
+    }
+    #[test]
+    fn matches_hand_winsorized_window() {
+        let data: Vec<f64> = vec![
+            9., -70., 3., 2., 6., 1., 800., 5., 4., 10., 0., 3.5, -2., 55., 7.,
+        ];
+        for limit in [0., 0.1, 0.25, 0.4] {
+            let mut rolling_winsorized_mean: RollingWinsorizedMean<f64> =
+                RollingWinsorizedMean::new(8, limit).unwrap();
+            assert_eq!(rolling_winsorized_mean.try_get(), None);
+            for (i, x) in data.iter().enumerate() {
+                rolling_winsorized_mean.update(*x);
+                let window = &data[(i + 1).saturating_sub(8)..=i];
+                let expected = winsorized_mean(window, limit);
+                assert!((rolling_winsorized_mean.get() - expected).abs() < 1e-12);
+            }
+        }
+    }
+    #[test]
+    fn invalid_limit() {
+        assert!(RollingWinsorizedMean::<f64>::new(10, 0.5).is_err());
+        assert!(RollingWinsorizedMean::<f64>::new(10, -0.1).is_err());
+        assert!(RollingWinsorizedMean::<f64>::new(0, 0.1).is_err());
+    }
+}